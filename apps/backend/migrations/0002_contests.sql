@@ -78,11 +78,20 @@ WHERE r.contest_id IS NULL
   AND dc.election_id = r.election_id
   AND dc.is_default = true;
 
-UPDATE votes v
-SET contest_id = r.contest_id
-FROM vote_receipts r
-WHERE v.contest_id IS NULL
-  AND r.id = v.receipt_id;
+-- receipt_id is dropped by 0019_ballot_secrecy.sql; skip this on re-runs after that.
+DO $$
+BEGIN
+  IF EXISTS (
+    SELECT 1 FROM information_schema.columns
+    WHERE table_name = 'votes' AND column_name = 'receipt_id'
+  ) THEN
+    UPDATE votes v
+    SET contest_id = r.contest_id
+    FROM vote_receipts r
+    WHERE v.contest_id IS NULL
+      AND r.id = v.receipt_id;
+  END IF;
+END $$;
 
 -- Foreign keys for contest_id columns.
 DO $$
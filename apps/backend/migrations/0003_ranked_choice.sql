@@ -0,0 +1,38 @@
+-- Ranked-choice contests.
+-- Contests declare how ballots are marked and counted; existing contests stay plurality.
+-- Ranked ballots store each preference as a votes row with its position in `rank`.
+
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS voting_method TEXT;
+UPDATE contests SET voting_method = 'plurality' WHERE voting_method IS NULL;
+ALTER TABLE contests ALTER COLUMN voting_method SET DEFAULT 'plurality';
+ALTER TABLE contests ALTER COLUMN voting_method SET NOT NULL;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'contests_voting_method_check'
+  ) THEN
+    ALTER TABLE contests ADD CONSTRAINT contests_voting_method_check
+      CHECK (voting_method IN ('plurality', 'ranked'));
+  END IF;
+END $$;
+
+ALTER TABLE votes ADD COLUMN IF NOT EXISTS rank INT;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'votes_rank_check'
+  ) THEN
+    ALTER TABLE votes ADD CONSTRAINT votes_rank_check CHECK (rank IS NULL OR rank >= 1);
+  END IF;
+END $$;
+
+-- receipt_id is dropped by 0019_ballot_secrecy.sql; skip this on re-runs after that.
+DO $$
+BEGIN
+  IF EXISTS (
+    SELECT 1 FROM information_schema.columns
+    WHERE table_name = 'votes' AND column_name = 'receipt_id'
+  ) THEN
+    CREATE INDEX IF NOT EXISTS idx_votes_receipt ON votes(receipt_id);
+  END IF;
+END $$;
@@ -0,0 +1,15 @@
+-- Multi-seat contests.
+-- Ranked contests with more than one seat are tabulated with single transferable vote.
+
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS seats INT;
+UPDATE contests SET seats = 1 WHERE seats IS NULL;
+ALTER TABLE contests ALTER COLUMN seats SET DEFAULT 1;
+ALTER TABLE contests ALTER COLUMN seats SET NOT NULL;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'contests_seats_check'
+  ) THEN
+    ALTER TABLE contests ADD CONSTRAINT contests_seats_check CHECK (seats >= 1);
+  END IF;
+END $$;
@@ -0,0 +1,16 @@
+-- Condorcet (Schulze) contests use the same ranked ballots as instant-runoff/STV contests.
+-- The method list is widened only while it lacks 'schulze', so re-running this file after later
+-- migrations never narrows it again.
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint
+    WHERE conname = 'contests_voting_method_check'
+      AND pg_get_constraintdef(oid) LIKE '%''schulze''%'
+  ) THEN
+    ALTER TABLE contests DROP CONSTRAINT IF EXISTS contests_voting_method_check;
+    ALTER TABLE contests ADD CONSTRAINT contests_voting_method_check
+      CHECK (voting_method IN ('plurality', 'ranked', 'schulze'));
+  END IF;
+END $$;
@@ -0,0 +1,34 @@
+-- Score (range) and approval contests.
+-- min_selections enforces a required number of marks; score contests bound each mark's score.
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint
+    WHERE conname = 'contests_voting_method_check'
+      AND pg_get_constraintdef(oid) LIKE '%''approval''%'
+  ) THEN
+    ALTER TABLE contests DROP CONSTRAINT IF EXISTS contests_voting_method_check;
+    ALTER TABLE contests ADD CONSTRAINT contests_voting_method_check
+      CHECK (voting_method IN ('plurality', 'ranked', 'schulze', 'score', 'approval'));
+  END IF;
+END $$;
+
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS min_selections INT;
+UPDATE contests SET min_selections = 1 WHERE min_selections IS NULL;
+ALTER TABLE contests ALTER COLUMN min_selections SET DEFAULT 1;
+ALTER TABLE contests ALTER COLUMN min_selections SET NOT NULL;
+
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS score_min INT;
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS score_max INT;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'contests_score_range_check'
+  ) THEN
+    ALTER TABLE contests ADD CONSTRAINT contests_score_range_check
+      CHECK (score_min IS NULL OR score_max IS NULL OR score_min < score_max);
+  END IF;
+END $$;
+
+ALTER TABLE votes ADD COLUMN IF NOT EXISTS score INT;
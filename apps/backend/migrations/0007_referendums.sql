@@ -0,0 +1,51 @@
+-- Referendum / ballot-measure contests.
+-- Referendum ballots record a fixed response instead of a candidate, so votes.candidate_id
+-- becomes optional and each row must carry either a candidate or a response.
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint
+    WHERE conname = 'contests_voting_method_check'
+      AND pg_get_constraintdef(oid) LIKE '%''referendum''%'
+  ) THEN
+    ALTER TABLE contests DROP CONSTRAINT IF EXISTS contests_voting_method_check;
+    ALTER TABLE contests ADD CONSTRAINT contests_voting_method_check
+      CHECK (voting_method IN (
+        'plurality', 'ranked', 'schulze', 'score', 'approval', 'referendum'
+      ));
+  END IF;
+END $$;
+
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS pass_threshold TEXT;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'contests_pass_threshold_check'
+  ) THEN
+    ALTER TABLE contests ADD CONSTRAINT contests_pass_threshold_check
+      CHECK (pass_threshold IS NULL OR pass_threshold IN ('simple_majority', 'two_thirds', 'majority_of_eligible'));
+  END IF;
+END $$;
+
+ALTER TABLE votes ALTER COLUMN candidate_id DROP NOT NULL;
+ALTER TABLE votes ADD COLUMN IF NOT EXISTS response TEXT;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'votes_response_check'
+  ) THEN
+    ALTER TABLE votes ADD CONSTRAINT votes_response_check
+      CHECK (response IS NULL OR response IN ('yes', 'no', 'abstain'));
+  END IF;
+END $$;
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'votes_choice_check'
+  ) THEN
+    ALTER TABLE votes ADD CONSTRAINT votes_choice_check
+      CHECK (candidate_id IS NOT NULL OR response IS NOT NULL);
+  END IF;
+END $$;
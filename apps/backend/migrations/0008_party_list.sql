@@ -0,0 +1,78 @@
+-- Parties and party-list proportional contests.
+-- Parties belong to an election so the same party can field district candidates and a national
+-- list. Party-list ballots record a party instead of a candidate.
+
+CREATE TABLE IF NOT EXISTS parties (
+  id UUID PRIMARY KEY,
+  election_id UUID NOT NULL REFERENCES elections(id) ON DELETE CASCADE,
+  name TEXT NOT NULL,
+  abbreviation TEXT,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_parties_election ON parties(election_id);
+CREATE UNIQUE INDEX IF NOT EXISTS uniq_parties_election_name ON parties(election_id, lower(name));
+
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS party_id UUID;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'candidates_party_id_fkey'
+  ) THEN
+    ALTER TABLE candidates
+      ADD CONSTRAINT candidates_party_id_fkey
+      FOREIGN KEY (party_id) REFERENCES parties(id) ON DELETE SET NULL;
+  END IF;
+END $$;
+CREATE INDEX IF NOT EXISTS idx_candidates_party ON candidates(party_id);
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint
+    WHERE conname = 'contests_voting_method_check'
+      AND pg_get_constraintdef(oid) LIKE '%''party_list''%'
+  ) THEN
+    ALTER TABLE contests DROP CONSTRAINT IF EXISTS contests_voting_method_check;
+    ALTER TABLE contests ADD CONSTRAINT contests_voting_method_check
+      CHECK (voting_method IN (
+        'plurality', 'ranked', 'schulze', 'score', 'approval', 'referendum', 'party_list'
+      ));
+  END IF;
+END $$;
+
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS allocation_method TEXT;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'contests_allocation_method_check'
+  ) THEN
+    ALTER TABLE contests ADD CONSTRAINT contests_allocation_method_check
+      CHECK (allocation_method IS NULL OR allocation_method IN ('dhondt', 'sainte_lague'));
+  END IF;
+END $$;
+
+ALTER TABLE votes ADD COLUMN IF NOT EXISTS party_id UUID;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'votes_party_id_fkey'
+  ) THEN
+    ALTER TABLE votes
+      ADD CONSTRAINT votes_party_id_fkey
+      FOREIGN KEY (party_id) REFERENCES parties(id) ON DELETE CASCADE;
+  END IF;
+END $$;
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint
+    WHERE conname = 'votes_choice_check'
+      AND pg_get_constraintdef(oid) LIKE '%party_id%'
+  ) THEN
+    ALTER TABLE votes DROP CONSTRAINT IF EXISTS votes_choice_check;
+    ALTER TABLE votes ADD CONSTRAINT votes_choice_check
+      CHECK (candidate_id IS NOT NULL OR response IS NOT NULL OR party_id IS NOT NULL);
+  END IF;
+END $$;
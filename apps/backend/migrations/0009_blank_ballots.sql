@@ -0,0 +1,26 @@
+-- Explicit blank / "none of the above" ballots.
+-- Contests opt in with allow_blank; a blank submission is stored as a single votes row with
+-- is_blank = true so it is counted separately from candidate totals.
+
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS allow_blank BOOLEAN;
+UPDATE contests SET allow_blank = false WHERE allow_blank IS NULL;
+ALTER TABLE contests ALTER COLUMN allow_blank SET DEFAULT false;
+ALTER TABLE contests ALTER COLUMN allow_blank SET NOT NULL;
+
+ALTER TABLE votes ADD COLUMN IF NOT EXISTS is_blank BOOLEAN;
+UPDATE votes SET is_blank = false WHERE is_blank IS NULL;
+ALTER TABLE votes ALTER COLUMN is_blank SET DEFAULT false;
+ALTER TABLE votes ALTER COLUMN is_blank SET NOT NULL;
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint
+    WHERE conname = 'votes_choice_check'
+      AND pg_get_constraintdef(oid) LIKE '%is_blank%'
+  ) THEN
+    ALTER TABLE votes DROP CONSTRAINT IF EXISTS votes_choice_check;
+    ALTER TABLE votes ADD CONSTRAINT votes_choice_check
+      CHECK (candidate_id IS NOT NULL OR response IS NOT NULL OR party_id IS NOT NULL OR is_blank);
+  END IF;
+END $$;
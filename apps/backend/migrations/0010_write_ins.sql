@@ -0,0 +1,53 @@
+-- Write-in selections and their adjudication.
+-- Write-ins are stored apart from votes until an officer maps the text to a candidate;
+-- candidate_marks combines both so adjudicated write-ins count toward candidate totals.
+
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS allow_write_ins BOOLEAN;
+UPDATE contests SET allow_write_ins = false WHERE allow_write_ins IS NULL;
+ALTER TABLE contests ALTER COLUMN allow_write_ins SET DEFAULT false;
+ALTER TABLE contests ALTER COLUMN allow_write_ins SET NOT NULL;
+
+CREATE TABLE IF NOT EXISTS write_ins (
+  id UUID PRIMARY KEY,
+  receipt_id UUID NOT NULL REFERENCES vote_receipts(id) ON DELETE CASCADE,
+  election_id UUID NOT NULL REFERENCES elections(id) ON DELETE CASCADE,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  name TEXT NOT NULL,
+  normalized_name TEXT NOT NULL,
+  rank INT CHECK (rank IS NULL OR rank >= 1),
+  score INT,
+  candidate_id UUID REFERENCES candidates(id) ON DELETE SET NULL,
+  adjudicated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+  adjudicated_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_write_ins_contest_name ON write_ins(contest_id, normalized_name);
+-- receipt_id is dropped by 0019_ballot_secrecy.sql; skip this on re-runs after that.
+DO $$
+BEGIN
+  IF EXISTS (
+    SELECT 1 FROM information_schema.columns
+    WHERE table_name = 'write_ins' AND column_name = 'receipt_id'
+  ) THEN
+    CREATE INDEX IF NOT EXISTS idx_write_ins_receipt ON write_ins(receipt_id);
+  END IF;
+END $$;
+
+-- 0019_ballot_secrecy.sql replaces this view once receipt_id is gone.
+DO $$
+BEGIN
+  IF EXISTS (
+    SELECT 1 FROM information_schema.columns
+    WHERE table_name = 'votes' AND column_name = 'receipt_id'
+  ) THEN
+    CREATE OR REPLACE VIEW candidate_marks AS
+    SELECT receipt_id, election_id, contest_id, candidate_id, rank, score
+    FROM votes
+    WHERE candidate_id IS NOT NULL
+    UNION ALL
+    SELECT receipt_id, election_id, contest_id, candidate_id, rank, score
+    FROM write_ins
+    WHERE candidate_id IS NOT NULL;
+  END IF;
+END $$;
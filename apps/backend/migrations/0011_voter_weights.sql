@@ -0,0 +1,44 @@
+-- Weighted voting (shares / units).
+-- Each roll entry carries a weight that is copied onto the voter's votes and write-ins when the
+-- ballot is cast, so tallies sum weights without needing to join back to the voter.
+
+ALTER TABLE voter_rolls ADD COLUMN IF NOT EXISTS weight BIGINT;
+UPDATE voter_rolls SET weight = 1 WHERE weight IS NULL;
+ALTER TABLE voter_rolls ALTER COLUMN weight SET DEFAULT 1;
+ALTER TABLE voter_rolls ALTER COLUMN weight SET NOT NULL;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'voter_rolls_weight_check'
+  ) THEN
+    ALTER TABLE voter_rolls ADD CONSTRAINT voter_rolls_weight_check CHECK (weight >= 1);
+  END IF;
+END $$;
+
+ALTER TABLE votes ADD COLUMN IF NOT EXISTS weight BIGINT;
+UPDATE votes SET weight = 1 WHERE weight IS NULL;
+ALTER TABLE votes ALTER COLUMN weight SET DEFAULT 1;
+ALTER TABLE votes ALTER COLUMN weight SET NOT NULL;
+
+ALTER TABLE write_ins ADD COLUMN IF NOT EXISTS weight BIGINT;
+UPDATE write_ins SET weight = 1 WHERE weight IS NULL;
+ALTER TABLE write_ins ALTER COLUMN weight SET DEFAULT 1;
+ALTER TABLE write_ins ALTER COLUMN weight SET NOT NULL;
+
+-- 0019_ballot_secrecy.sql replaces this view once receipt_id is gone.
+DO $$
+BEGIN
+  IF EXISTS (
+    SELECT 1 FROM information_schema.columns
+    WHERE table_name = 'votes' AND column_name = 'receipt_id'
+  ) THEN
+    CREATE OR REPLACE VIEW candidate_marks AS
+    SELECT receipt_id, election_id, contest_id, candidate_id, rank, score, weight
+    FROM votes
+    WHERE candidate_id IS NOT NULL
+    UNION ALL
+    SELECT receipt_id, election_id, contest_id, candidate_id, rank, score, weight
+    FROM write_ins
+    WHERE candidate_id IS NOT NULL;
+  END IF;
+END $$;
@@ -0,0 +1,62 @@
+-- Cumulative voting.
+-- Voters distribute up to `total_points` points across candidates, several on the same candidate
+-- if they wish. Each selection row carries its points; receipts record the total used.
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint
+    WHERE conname = 'contests_voting_method_check'
+      AND pg_get_constraintdef(oid) LIKE '%''cumulative''%'
+  ) THEN
+    ALTER TABLE contests DROP CONSTRAINT IF EXISTS contests_voting_method_check;
+    ALTER TABLE contests ADD CONSTRAINT contests_voting_method_check
+      CHECK (voting_method IN (
+        'plurality', 'ranked', 'schulze', 'score', 'approval', 'referendum', 'party_list',
+        'cumulative'
+      ));
+  END IF;
+END $$;
+
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS total_points INT;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'contests_total_points_check'
+  ) THEN
+    ALTER TABLE contests ADD CONSTRAINT contests_total_points_check
+      CHECK (total_points IS NULL OR total_points >= 1);
+  END IF;
+END $$;
+
+ALTER TABLE votes ADD COLUMN IF NOT EXISTS points INT;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'votes_points_check'
+  ) THEN
+    ALTER TABLE votes ADD CONSTRAINT votes_points_check CHECK (points IS NULL OR points >= 1);
+  END IF;
+END $$;
+
+ALTER TABLE write_ins ADD COLUMN IF NOT EXISTS points INT;
+
+ALTER TABLE vote_receipts ADD COLUMN IF NOT EXISTS points_used INT;
+
+-- 0019_ballot_secrecy.sql replaces this view once receipt_id is gone.
+DO $$
+BEGIN
+  IF EXISTS (
+    SELECT 1 FROM information_schema.columns
+    WHERE table_name = 'votes' AND column_name = 'receipt_id'
+  ) THEN
+    CREATE OR REPLACE VIEW candidate_marks AS
+    SELECT receipt_id, election_id, contest_id, candidate_id, rank, score, weight, points
+    FROM votes
+    WHERE candidate_id IS NOT NULL
+    UNION ALL
+    SELECT receipt_id, election_id, contest_id, candidate_id, rank, score, weight, points
+    FROM write_ins
+    WHERE candidate_id IS NOT NULL;
+  END IF;
+END $$;
@@ -0,0 +1,19 @@
+-- Quadratic voting.
+-- `total_points` is the voter's voice-credit budget and each selection's `points` is the number of
+-- votes cast for that candidate, costing points * points credits.
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint
+    WHERE conname = 'contests_voting_method_check'
+      AND pg_get_constraintdef(oid) LIKE '%''quadratic''%'
+  ) THEN
+    ALTER TABLE contests DROP CONSTRAINT IF EXISTS contests_voting_method_check;
+    ALTER TABLE contests ADD CONSTRAINT contests_voting_method_check
+      CHECK (voting_method IN (
+        'plurality', 'ranked', 'schulze', 'score', 'approval', 'referendum', 'party_list',
+        'cumulative', 'quadratic'
+      ));
+  END IF;
+END $$;
@@ -0,0 +1,34 @@
+-- Election-configured tie-break policy.
+-- `lot` orders tied candidates by hashing them with a seed fixed while the election is a draft
+-- (its SHA-256 is shown as a commitment and the seed revealed once closed).
+-- `registration_order` favours the earliest-registered candidate; `manual` only flags the tie.
+-- Each applied or flagged tie is recorded once per contest and stage.
+
+ALTER TABLE elections ADD COLUMN IF NOT EXISTS tie_break_policy TEXT;
+UPDATE elections SET tie_break_policy = 'manual' WHERE tie_break_policy IS NULL;
+ALTER TABLE elections ALTER COLUMN tie_break_policy SET DEFAULT 'manual';
+ALTER TABLE elections ALTER COLUMN tie_break_policy SET NOT NULL;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'elections_tie_break_policy_check'
+  ) THEN
+    ALTER TABLE elections ADD CONSTRAINT elections_tie_break_policy_check
+      CHECK (tie_break_policy IN ('lot', 'registration_order', 'manual'));
+  END IF;
+END $$;
+
+ALTER TABLE elections ADD COLUMN IF NOT EXISTS tie_break_seed TEXT;
+
+CREATE TABLE IF NOT EXISTS tie_breaks (
+  id UUID PRIMARY KEY,
+  election_id UUID NOT NULL REFERENCES elections(id) ON DELETE CASCADE,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  stage TEXT NOT NULL,
+  policy TEXT NOT NULL,
+  tied JSONB NOT NULL,
+  drawn JSONB NOT NULL,
+  seats INT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE (contest_id, stage)
+);
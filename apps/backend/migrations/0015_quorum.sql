@@ -0,0 +1,18 @@
+-- Turnout quorum.
+-- A contest may require a minimum number of ballots and/or a minimum share of its voter roll;
+-- results below quorum are reported as invalid. Turnout counts receipts, blank ballots included.
+
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS quorum_ballots INT;
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS quorum_percent DOUBLE PRECISION;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'contests_quorum_check'
+  ) THEN
+    ALTER TABLE contests ADD CONSTRAINT contests_quorum_check
+      CHECK (
+        (quorum_ballots IS NULL OR quorum_ballots >= 1)
+        AND (quorum_percent IS NULL OR (quorum_percent > 0 AND quorum_percent <= 100))
+      );
+  END IF;
+END $$;
@@ -0,0 +1,29 @@
+-- Candidate order on the ballot.
+-- `alphabetical` keeps the original name ordering, `fixed` follows each candidate's
+-- `ballot_position` (unpositioned candidates last, by name), and `random` shuffles per voter with
+-- a seed derived from the voter and contest ids so any ballot's order can be reproduced.
+
+ALTER TABLE contests ADD COLUMN IF NOT EXISTS ballot_order TEXT;
+UPDATE contests SET ballot_order = 'alphabetical' WHERE ballot_order IS NULL;
+ALTER TABLE contests ALTER COLUMN ballot_order SET DEFAULT 'alphabetical';
+ALTER TABLE contests ALTER COLUMN ballot_order SET NOT NULL;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'contests_ballot_order_check'
+  ) THEN
+    ALTER TABLE contests ADD CONSTRAINT contests_ballot_order_check
+      CHECK (ballot_order IN ('alphabetical', 'fixed', 'random'));
+  END IF;
+END $$;
+
+ALTER TABLE candidates ADD COLUMN IF NOT EXISTS ballot_position INT;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'candidates_ballot_position_check'
+  ) THEN
+    ALTER TABLE candidates ADD CONSTRAINT candidates_ballot_position_check
+      CHECK (ballot_position IS NULL OR ballot_position >= 1);
+  END IF;
+END $$;
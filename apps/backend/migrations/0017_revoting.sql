@@ -0,0 +1,16 @@
+-- Revoting.
+-- Elections with `allow_revote` let a voter replace their ballot in a contest until close. The
+-- earlier receipt is kept and marked superseded, its selections are removed so only the latest
+-- ballot is counted, and at most one receipt per voter and contest is current.
+
+ALTER TABLE elections ADD COLUMN IF NOT EXISTS allow_revote BOOLEAN;
+UPDATE elections SET allow_revote = false WHERE allow_revote IS NULL;
+ALTER TABLE elections ALTER COLUMN allow_revote SET DEFAULT false;
+ALTER TABLE elections ALTER COLUMN allow_revote SET NOT NULL;
+
+ALTER TABLE vote_receipts ADD COLUMN IF NOT EXISTS superseded_at TIMESTAMPTZ;
+
+ALTER TABLE vote_receipts DROP CONSTRAINT IF EXISTS vote_receipts_contest_id_voter_id_key;
+CREATE UNIQUE INDEX IF NOT EXISTS uniq_vote_receipts_current
+  ON vote_receipts(contest_id, voter_id)
+  WHERE superseded_at IS NULL;
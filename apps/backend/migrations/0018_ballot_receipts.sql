@@ -0,0 +1,26 @@
+-- Multi-contest ballot submission.
+-- A ballot receipt groups the per-contest receipts of one submission covering several contests,
+-- all written in a single transaction.
+
+CREATE TABLE IF NOT EXISTS ballot_receipts (
+  id UUID PRIMARY KEY,
+  election_id UUID NOT NULL REFERENCES elections(id) ON DELETE CASCADE,
+  voter_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  idempotency_key TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE (election_id, voter_id, idempotency_key)
+);
+
+ALTER TABLE vote_receipts ADD COLUMN IF NOT EXISTS ballot_receipt_id UUID;
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'vote_receipts_ballot_receipt_id_fkey'
+  ) THEN
+    ALTER TABLE vote_receipts
+      ADD CONSTRAINT vote_receipts_ballot_receipt_id_fkey
+      FOREIGN KEY (ballot_receipt_id) REFERENCES ballot_receipts(id) ON DELETE SET NULL;
+  END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_vote_receipts_ballot_receipt ON vote_receipts(ballot_receipt_id);
@@ -0,0 +1,91 @@
+-- Ballot secrecy.
+-- Participation (vote_receipts: who voted in which contest) and ballot content (ballots, votes,
+-- write_ins: what was chosen) share no key, and ballot rows carry no timestamps that could be
+-- matched against receipt times. Only ballots in elections that allow revoting keep a
+-- `revote_tag`, an HMAC of the receipt id under BALLOT_LINK_KEY, which lives in the application's
+-- environment and never in the database.
+
+CREATE TABLE IF NOT EXISTS ballots (
+  id UUID PRIMARY KEY,
+  election_id UUID NOT NULL REFERENCES elections(id) ON DELETE CASCADE,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  points_used INT,
+  revote_tag TEXT UNIQUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_ballots_contest ON ballots(contest_id);
+
+ALTER TABLE votes ADD COLUMN IF NOT EXISTS ballot_id UUID;
+ALTER TABLE write_ins ADD COLUMN IF NOT EXISTS ballot_id UUID;
+
+-- Existing ballots are moved under fresh random ids (inserted in random order); the mapping from
+-- receipts only lives inside this migration.
+DO $$
+BEGIN
+  IF EXISTS (
+    SELECT 1 FROM information_schema.columns
+    WHERE table_name = 'votes' AND column_name = 'receipt_id'
+  ) THEN
+    CREATE TEMP TABLE ballot_links AS
+      SELECT r.id AS receipt_id, gen_random_uuid() AS ballot_id, r.election_id, r.contest_id,
+             r.points_used
+      FROM vote_receipts r
+      WHERE EXISTS (SELECT 1 FROM votes v WHERE v.receipt_id = r.id)
+         OR EXISTS (SELECT 1 FROM write_ins w WHERE w.receipt_id = r.id);
+
+    INSERT INTO ballots (id, election_id, contest_id, points_used)
+    SELECT ballot_id, election_id, contest_id, points_used
+    FROM ballot_links
+    ORDER BY ballot_id;
+
+    UPDATE votes v SET ballot_id = l.ballot_id
+    FROM ballot_links l
+    WHERE v.receipt_id = l.receipt_id;
+
+    UPDATE write_ins w SET ballot_id = l.ballot_id
+    FROM ballot_links l
+    WHERE w.receipt_id = l.receipt_id;
+
+    DROP TABLE ballot_links;
+  END IF;
+END $$;
+
+DROP VIEW IF EXISTS candidate_marks;
+
+ALTER TABLE votes DROP COLUMN IF EXISTS receipt_id;
+ALTER TABLE votes DROP COLUMN IF EXISTS created_at;
+ALTER TABLE votes ALTER COLUMN ballot_id SET NOT NULL;
+ALTER TABLE write_ins DROP COLUMN IF EXISTS receipt_id;
+ALTER TABLE write_ins DROP COLUMN IF EXISTS created_at;
+ALTER TABLE write_ins ALTER COLUMN ballot_id SET NOT NULL;
+ALTER TABLE vote_receipts DROP COLUMN IF EXISTS points_used;
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'votes_ballot_id_fkey'
+  ) THEN
+    ALTER TABLE votes
+      ADD CONSTRAINT votes_ballot_id_fkey
+      FOREIGN KEY (ballot_id) REFERENCES ballots(id) ON DELETE CASCADE;
+  END IF;
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'write_ins_ballot_id_fkey'
+  ) THEN
+    ALTER TABLE write_ins
+      ADD CONSTRAINT write_ins_ballot_id_fkey
+      FOREIGN KEY (ballot_id) REFERENCES ballots(id) ON DELETE CASCADE;
+  END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_votes_ballot ON votes(ballot_id);
+CREATE INDEX IF NOT EXISTS idx_write_ins_ballot ON write_ins(ballot_id);
+
+CREATE OR REPLACE VIEW candidate_marks AS
+SELECT ballot_id, election_id, contest_id, candidate_id, rank, score, weight, points
+FROM votes
+WHERE candidate_id IS NOT NULL
+UNION ALL
+SELECT ballot_id, election_id, contest_id, candidate_id, rank, score, weight, points
+FROM write_ins
+WHERE candidate_id IS NOT NULL;
@@ -0,0 +1,22 @@
+-- Hash-chained audit log.
+-- Every event records the hash of the previous event in the global log (prev_hash) and of the
+-- previous event for its election (election_prev_hash), and its own hash covers both. Events
+-- written before this migration keep a NULL hash and are reported as unsealed by verification.
+
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS seq BIGINT;
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS prev_hash TEXT;
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS election_prev_hash TEXT;
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS hash TEXT;
+
+UPDATE audit_events a
+SET seq = ordered.seq
+FROM (
+  SELECT id, ROW_NUMBER() OVER (ORDER BY created_at ASC, id ASC) AS seq
+  FROM audit_events
+) ordered
+WHERE a.id = ordered.id AND a.seq IS NULL;
+
+ALTER TABLE audit_events ALTER COLUMN seq SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS uniq_audit_events_seq ON audit_events(seq);
+CREATE INDEX IF NOT EXISTS idx_audit_events_election_seq ON audit_events(election_id, seq);
@@ -0,0 +1,21 @@
+-- Public bulletin board.
+-- Each cast ballot posts a salted commitment to its contest's board. Entries are append-only and
+-- carry no ballot or voter reference; the receipt keeps the commitment so its voter can ask for
+-- an inclusion proof. Closing the election snapshots every contest's Merkle root.
+
+CREATE TABLE IF NOT EXISTS bulletin_entries (
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  position BIGINT NOT NULL CHECK (position >= 1),
+  commitment TEXT NOT NULL UNIQUE,
+  PRIMARY KEY (contest_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS bulletin_snapshots (
+  contest_id UUID PRIMARY KEY REFERENCES contests(id) ON DELETE CASCADE,
+  merkle_root TEXT NOT NULL,
+  leaf_count BIGINT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE vote_receipts ADD COLUMN IF NOT EXISTS ballot_commitment TEXT;
+CREATE INDEX IF NOT EXISTS idx_vote_receipts_ballot_commitment ON vote_receipts(ballot_commitment);
@@ -0,0 +1,68 @@
+-- End-to-end verifiable elections.
+-- With encrypted_ballots set, voters submit exponential-ElGamal ciphertexts (one per candidate)
+-- with validity proofs instead of plaintext selections. The election key comes from a
+-- distributed key ceremony between trustees, and tallies are only decrypted after close once
+-- `trustee_threshold` trustees have posted verified decryption shares.
+
+ALTER TABLE elections ADD COLUMN IF NOT EXISTS encrypted_ballots BOOLEAN;
+UPDATE elections SET encrypted_ballots = false WHERE encrypted_ballots IS NULL;
+ALTER TABLE elections ALTER COLUMN encrypted_ballots SET DEFAULT false;
+ALTER TABLE elections ALTER COLUMN encrypted_ballots SET NOT NULL;
+
+ALTER TABLE elections ADD COLUMN IF NOT EXISTS trustee_threshold INT;
+ALTER TABLE elections ADD COLUMN IF NOT EXISTS encryption_public_key TEXT;
+
+CREATE TABLE IF NOT EXISTS trustees (
+  id UUID PRIMARY KEY,
+  election_id UUID NOT NULL REFERENCES elections(id) ON DELETE CASCADE,
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  trustee_index INT NOT NULL CHECK (trustee_index >= 1),
+  transport_key TEXT,
+  commitments JSONB,
+  commitment_proof JSONB,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE (election_id, user_id),
+  UNIQUE (election_id, trustee_index)
+);
+
+-- Key shares are encrypted by the dealing trustee for the recipient's transport key; the server
+-- only relays them.
+CREATE TABLE IF NOT EXISTS trustee_shares (
+  dealer_id UUID NOT NULL REFERENCES trustees(id) ON DELETE CASCADE,
+  recipient_id UUID NOT NULL REFERENCES trustees(id) ON DELETE CASCADE,
+  encrypted_share TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (dealer_id, recipient_id)
+);
+
+CREATE TABLE IF NOT EXISTS encrypted_votes (
+  id UUID PRIMARY KEY,
+  ballot_id UUID NOT NULL REFERENCES ballots(id) ON DELETE CASCADE,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  candidate_id UUID NOT NULL REFERENCES candidates(id) ON DELETE CASCADE,
+  ciphertext_a TEXT NOT NULL,
+  ciphertext_b TEXT NOT NULL,
+  proof JSONB NOT NULL,
+  weight BIGINT NOT NULL DEFAULT 1
+);
+
+CREATE INDEX IF NOT EXISTS idx_encrypted_votes_contest ON encrypted_votes(contest_id);
+
+ALTER TABLE ballots ADD COLUMN IF NOT EXISTS selection_count_proof JSONB;
+
+CREATE TABLE IF NOT EXISTS decryption_shares (
+  trustee_id UUID NOT NULL REFERENCES trustees(id) ON DELETE CASCADE,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  candidate_id UUID NOT NULL REFERENCES candidates(id) ON DELETE CASCADE,
+  share TEXT NOT NULL,
+  proof JSONB NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (trustee_id, candidate_id)
+);
+
+CREATE TABLE IF NOT EXISTS encrypted_tallies (
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  candidate_id UUID PRIMARY KEY REFERENCES candidates(id) ON DELETE CASCADE,
+  total BIGINT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,29 @@
+-- Benaloh challenges for encrypted elections.
+-- A prepared ballot is encrypted by the server for one voter and waits until the voter casts it
+-- (the row is deleted and the randomness discarded) or challenges it (the randomness is revealed
+-- and the ballot is published as spoiled). Like `ballots`, spoiled ballots carry no voter and no
+-- timestamp, so the published choices cannot be traced back to who made them.
+
+CREATE TABLE IF NOT EXISTS prepared_ballots (
+  id UUID PRIMARY KEY,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  voter_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  ballot JSONB NOT NULL,
+  randomness JSONB NOT NULL,
+  ballot_hash TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_prepared_ballots_contest_voter
+  ON prepared_ballots(contest_id, voter_id);
+
+CREATE TABLE IF NOT EXISTS spoiled_ballots (
+  id UUID PRIMARY KEY,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  ballot JSONB NOT NULL,
+  randomness JSONB NOT NULL,
+  selections JSONB NOT NULL,
+  ballot_hash TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_spoiled_ballots_contest ON spoiled_ballots(contest_id);
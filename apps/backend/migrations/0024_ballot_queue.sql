@@ -0,0 +1,24 @@
+-- Ballot queue.
+-- Casting writes the receipt and queues the ballot in one transaction; the queued ballot is sealed
+-- under a key derived from BALLOT_LINK_KEY, so the row linked to the receipt reveals nothing.
+-- Queued ballots of a contest are moved into `ballots` in batches, in random order, by a
+-- transaction of their own, so no ballot row shares a transaction id (`xmin`) with the receipt
+-- that cast it. Each ballot is stored once per power of two in the voter's weight, so marks carry
+-- weights that every voter with that bit set shares rather than the voter's exact roll weight.
+
+CREATE TABLE IF NOT EXISTS pending_ballots (
+  id UUID PRIMARY KEY,
+  contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+  receipt_id UUID NOT NULL UNIQUE REFERENCES vote_receipts(id) ON DELETE CASCADE,
+  sealed TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_ballots_contest ON pending_ballots(contest_id);
+
+-- Ballots cast before the queue were written with their receipt. Rewriting the rows gives them
+-- this migration's transaction id instead; the old row versions remain on disk until vacuumed.
+-- Their weights are left whole.
+UPDATE ballots SET id = id;
+UPDATE votes SET id = id;
+UPDATE write_ins SET id = id;
+UPDATE encrypted_votes SET id = id;
@@ -0,0 +1,11 @@
+-- Bulletin commitments on counted ballots.
+-- The commitment is stored with the ballot it was posted for, on the first part of a split
+-- weight, so auditors can match the board against the ballots counted. The board entry is posted
+-- when the ballot leaves the queue rather than with the receipt, and the receipt keeps its
+-- commitment sealed under a key derived from BALLOT_LINK_KEY, so the commitment does not link a
+-- receipt to its ballot. Receipts cast before this keep their plaintext `ballot_commitment`.
+
+ALTER TABLE ballots ADD COLUMN IF NOT EXISTS commitment TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS uniq_ballots_commitment ON ballots(commitment);
+
+ALTER TABLE vote_receipts ADD COLUMN IF NOT EXISTS sealed_commitment TEXT;
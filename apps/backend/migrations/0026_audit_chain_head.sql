@@ -0,0 +1,17 @@
+-- Audit chain head.
+-- Writers lock this single row, which holds the newest event's seq and hash, instead of the whole
+-- audit_events table. Events are hashed over an explicitly ordered, length-prefixed encoding from
+-- hash_version 2; events written before keep version 1.
+
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS hash_version SMALLINT NOT NULL DEFAULT 1;
+
+CREATE TABLE IF NOT EXISTS audit_chain_head (
+  id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+  seq BIGINT NOT NULL,
+  hash TEXT
+);
+
+INSERT INTO audit_chain_head (id, seq, hash)
+SELECT true, COALESCE(MAX(seq), 0), (SELECT hash FROM audit_events ORDER BY seq DESC LIMIT 1)
+FROM audit_events
+ON CONFLICT (id) DO NOTHING;
@@ -0,0 +1,10 @@
+-- Encrypted ballot proof nonces.
+-- Proofs on an encrypted ballot are bound to a nonce issued to its voter, which is stored and
+-- published with the ballot so the proofs can be rechecked. Ballots cast before have none and
+-- were proven against the contest and candidate ids alone. The index serves the check that
+-- refuses a ciphertext already cast in the contest.
+
+ALTER TABLE ballots ADD COLUMN IF NOT EXISTS proof_nonce TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_encrypted_votes_contest_ciphertext
+  ON encrypted_votes(contest_id, ciphertext_a);
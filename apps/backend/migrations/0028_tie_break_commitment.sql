@@ -0,0 +1,9 @@
+-- Tie-break seed commitments.
+-- The seed's SHA-256 is frozen when the election is published, and a lot draw only uses a seed
+-- that still matches it. Elections published before keep the commitment of their current seed.
+
+ALTER TABLE elections ADD COLUMN IF NOT EXISTS tie_break_commitment TEXT;
+
+UPDATE elections
+SET tie_break_commitment = encode(sha256(convert_to(tie_break_seed, 'UTF8')), 'hex')
+WHERE status <> 'draft' AND tie_break_seed IS NOT NULL AND tie_break_commitment IS NULL;
@@ -0,0 +1,21 @@
+-- Referendum turnout quorum.
+-- The `majority_of_eligible` pass threshold is renamed `quorum_of_eligible`: a referendum passes
+-- with more yes than no once ballots from more than half of the roll's weight have been cast.
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint
+    WHERE conname = 'contests_pass_threshold_check'
+      AND pg_get_constraintdef(oid) LIKE '%quorum_of_eligible%'
+  ) THEN
+    ALTER TABLE contests DROP CONSTRAINT IF EXISTS contests_pass_threshold_check;
+    UPDATE contests SET pass_threshold = 'quorum_of_eligible'
+    WHERE pass_threshold = 'majority_of_eligible';
+    ALTER TABLE contests ADD CONSTRAINT contests_pass_threshold_check
+      CHECK (
+        pass_threshold IS NULL
+        OR pass_threshold IN ('simple_majority', 'two_thirds', 'quorum_of_eligible')
+      );
+  END IF;
+END $$;
@@ -0,0 +1,17 @@
+-- Prepared ballots keep their randomness sealed under a key derived from BALLOT_LINK_KEY and bound
+-- to the prepared ballot id, so the voter id stored beside it cannot be used to open the ballot
+-- with database access alone. Plaintext randomness cannot be sealed in SQL, so prepared ballots
+-- waiting from before this migration are discarded and have to be prepared again.
+
+DO $$
+BEGIN
+  IF EXISTS (
+    SELECT 1 FROM information_schema.columns
+    WHERE table_name = 'prepared_ballots' AND column_name = 'randomness'
+  ) THEN
+    DELETE FROM prepared_ballots;
+    ALTER TABLE prepared_ballots DROP COLUMN randomness;
+  END IF;
+END $$;
+
+ALTER TABLE prepared_ballots ADD COLUMN IF NOT EXISTS sealed_randomness TEXT NOT NULL;
@@ -0,0 +1,32 @@
+-- Write-ins that duplicate a ballot's own candidate mark.
+-- A write-in adjudicated to a candidate the same ballot already marks (directly or through
+-- another write-in) is flagged `duplicate_mark` and left out of candidate_marks, so the ballot
+-- counts once and keeps one rank. The ballot's own mark is kept; among write-ins alone, the
+-- best-ranked (then lowest id) one is.
+
+ALTER TABLE write_ins ADD COLUMN IF NOT EXISTS duplicate_mark BOOLEAN NOT NULL DEFAULT false;
+
+UPDATE write_ins w
+SET duplicate_mark = true
+WHERE w.candidate_id IS NOT NULL
+  AND (
+    EXISTS (
+      SELECT 1 FROM votes v WHERE v.ballot_id = w.ballot_id AND v.candidate_id = w.candidate_id
+    )
+    OR EXISTS (
+      SELECT 1
+      FROM write_ins o
+      WHERE o.ballot_id = w.ballot_id
+        AND o.candidate_id = w.candidate_id
+        AND (COALESCE(o.rank, 0), o.id) < (COALESCE(w.rank, 0), w.id)
+    )
+  );
+
+CREATE OR REPLACE VIEW candidate_marks AS
+SELECT ballot_id, election_id, contest_id, candidate_id, rank, score, weight, points
+FROM votes
+WHERE candidate_id IS NOT NULL
+UNION ALL
+SELECT ballot_id, election_id, contest_id, candidate_id, rank, score, weight, points
+FROM write_ins
+WHERE candidate_id IS NOT NULL AND NOT duplicate_mark;
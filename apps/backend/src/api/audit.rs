@@ -0,0 +1,55 @@
+use actix_web::{get, web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::UserRole,
+    errors::AppError,
+    middleware::{require_roles, AuthenticatedUser},
+    services::audit,
+};
+
+#[get("/audit/verify")]
+async fn verify_global(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Admin, UserRole::Auditor])?;
+
+    let report = audit::verify(pool.get_ref(), None).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": report })))
+}
+
+#[get("/elections/{id}/audit/verify")]
+async fn verify_election(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(
+        &auth,
+        &[
+            UserRole::Admin,
+            UserRole::ElectionOfficer,
+            UserRole::Auditor,
+        ],
+    )?;
+
+    let id = path.into_inner();
+
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM elections WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool.get_ref())
+        .await
+        .map_err(|_| AppError::Internal)?
+        .ok_or_else(|| AppError::NotFound("election not found".to_string()))?;
+
+    let report = audit::verify(pool.get_ref(), Some(id)).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": report })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(verify_global).service(verify_election);
+}
@@ -10,11 +10,14 @@ use crate::{
         voter_roll_import::{parse_import_identifiers, resolve_user_by_identifier},
     },
     domain::{
-        AddVoterRollRequest, CreateCandidateRequest, CreateContestRequest, ImportVoterRollRequest,
-        UpdateCandidateRequest, UpdateContestRequest, UserRole,
+        AddVoterRollRequest, AdjudicateWriteInRequest, AllocationMethod, BallotOrder,
+        CreateCandidateRequest,
+        CreateContestRequest, CreateRunoffRequest, ImportVoterRollRequest, PassThreshold,
+        UpdateCandidateRequest, UpdateContestRequest, UserRole, VotingMethod,
     },
     errors::AppError,
     middleware::{require_roles, AuthenticatedUser},
+    services::{bulletin, results, runoff, write_in},
 };
 
 async fn ensure_contest_election_draft(pool: &PgPool, contest_id: Uuid) -> Result<Uuid, AppError> {
@@ -41,7 +44,10 @@ async fn ensure_contest_election_draft(pool: &PgPool, contest_id: Uuid) -> Resul
     Ok(row.0)
 }
 
-async fn ensure_election_draft(pool: &PgPool, election_id: Uuid) -> Result<(), AppError> {
+pub(crate) async fn ensure_election_draft(
+    pool: &PgPool,
+    election_id: Uuid,
+) -> Result<(), AppError> {
     let status = sqlx::query_scalar::<_, String>("SELECT status FROM elections WHERE id = $1")
         .bind(election_id)
         .fetch_optional(pool)
@@ -58,6 +64,196 @@ async fn ensure_election_draft(pool: &PgPool, election_id: Uuid) -> Result<(), A
     Ok(())
 }
 
+pub(crate) async fn ensure_party_in_election(
+    pool: &PgPool,
+    election_id: Uuid,
+    party_id: Uuid,
+) -> Result<(), AppError> {
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM parties WHERE id = $1 AND election_id = $2",
+    )
+    .bind(party_id)
+    .bind(election_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    if exists == 0 {
+        return Err(AppError::BadRequest(
+            "party_id does not belong to this election".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_ballot_position(position: Option<i32>) -> Result<(), AppError> {
+    if position.is_some_and(|position| position < 1) {
+        return Err(AppError::BadRequest("ballot_position must be >= 1".to_string()));
+    }
+    Ok(())
+}
+
+struct ContestRules {
+    voting_method: VotingMethod,
+    max_selections: i32,
+    min_selections: i32,
+    seats: i32,
+    score_min: Option<i32>,
+    score_max: Option<i32>,
+    pass_threshold: Option<PassThreshold>,
+    allocation_method: Option<AllocationMethod>,
+    total_points: Option<i32>,
+    quorum_ballots: Option<i32>,
+    quorum_percent: Option<f64>,
+    allow_blank: bool,
+    allow_write_ins: bool,
+    ballot_order: BallotOrder,
+}
+
+impl ContestRules {
+    fn normalized(mut self) -> Self {
+        if self.voting_method == VotingMethod::Score {
+            self.score_min = Some(self.score_min.unwrap_or(0));
+            self.score_max = Some(self.score_max.unwrap_or(5));
+        } else {
+            self.score_min = None;
+            self.score_max = None;
+        }
+
+        if self.voting_method == VotingMethod::Referendum {
+            self.max_selections = 1;
+            self.min_selections = 1;
+            self.pass_threshold =
+                Some(self.pass_threshold.unwrap_or(PassThreshold::SimpleMajority));
+        } else {
+            self.pass_threshold = None;
+        }
+
+        if matches!(
+            self.voting_method,
+            VotingMethod::Referendum | VotingMethod::PartyList
+        ) {
+            self.allow_write_ins = false;
+        }
+
+        if self.voting_method == VotingMethod::PartyList {
+            self.max_selections = 1;
+            self.min_selections = 1;
+            self.allocation_method =
+                Some(self.allocation_method.unwrap_or(AllocationMethod::Dhondt));
+        } else {
+            self.allocation_method = None;
+        }
+
+        // Every cumulative or quadratic selection costs at least one point, so the budget also
+        // bounds how many selections a ballot can hold. Corporate boards default to one point
+        // per seat; quadratic ballots default to 100 voice credits.
+        if self.voting_method.uses_points() {
+            let default_points = match self.voting_method {
+                VotingMethod::Quadratic => 100,
+                _ => self.seats,
+            };
+            let total_points = self.total_points.unwrap_or(default_points);
+            self.total_points = Some(total_points);
+            self.max_selections = total_points;
+            self.min_selections = 1;
+        } else {
+            self.total_points = None;
+        }
+        self
+    }
+
+    fn validate(&self) -> Result<(), AppError> {
+        if self.max_selections < 1 {
+            return Err(AppError::BadRequest(
+                "max_selections must be >= 1".to_string(),
+            ));
+        }
+        if self.min_selections < 1 || self.min_selections > self.max_selections {
+            return Err(AppError::BadRequest(
+                "min_selections must be between 1 and max_selections".to_string(),
+            ));
+        }
+        if self.seats < 1 {
+            return Err(AppError::BadRequest("seats must be >= 1".to_string()));
+        }
+        if self.total_points.is_some_and(|points| points < 1) {
+            return Err(AppError::BadRequest("total_points must be >= 1".to_string()));
+        }
+        if self.quorum_ballots.is_some_and(|ballots| ballots < 1) {
+            return Err(AppError::BadRequest("quorum_ballots must be >= 1".to_string()));
+        }
+        if self
+            .quorum_percent
+            .is_some_and(|percent| !(percent > 0.0 && percent <= 100.0))
+        {
+            return Err(AppError::BadRequest(
+                "quorum_percent must be greater than 0 and at most 100".to_string(),
+            ));
+        }
+        if let (Some(score_min), Some(score_max)) = (self.score_min, self.score_max) {
+            if score_min >= score_max {
+                return Err(AppError::BadRequest(
+                    "score_max must be greater than score_min".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn load_contest_rules(pool: &PgPool, contest_id: Uuid) -> Result<ContestRules, AppError> {
+    let row = sqlx::query_as::<
+        _,
+        (
+            String,
+            i32,
+            i32,
+            i32,
+            Option<i32>,
+            Option<i32>,
+            Option<String>,
+            Option<String>,
+            Option<i32>,
+            Option<i32>,
+            Option<f64>,
+            bool,
+            bool,
+            String,
+        ),
+    >(
+        r#"
+        SELECT voting_method, max_selections, min_selections, seats, score_min, score_max,
+               pass_threshold, allocation_method, total_points, quorum_ballots, quorum_percent,
+               allow_blank, allow_write_ins, ballot_order
+        FROM contests
+        WHERE id = $1
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
+
+    Ok(ContestRules {
+        voting_method: VotingMethod::from_db(&row.0).ok_or(AppError::Internal)?,
+        max_selections: row.1,
+        min_selections: row.2,
+        seats: row.3,
+        score_min: row.4,
+        score_max: row.5,
+        pass_threshold: row.6.as_deref().and_then(PassThreshold::from_db),
+        allocation_method: row.7.as_deref().and_then(AllocationMethod::from_db),
+        total_points: row.8,
+        quorum_ballots: row.9,
+        quorum_percent: row.10,
+        allow_blank: row.11,
+        allow_write_ins: row.12,
+        ballot_order: BallotOrder::from_db(&row.13).ok_or(AppError::Internal)?,
+    })
+}
+
 #[get("/elections/{id}/contests")]
 async fn list_contests(
     pool: web::Data<PgPool>,
@@ -82,6 +278,13 @@ async fn list_contests(
             String,
             Option<String>,
             i32,
+            i32,
+            String,
+            i32,
+            Option<i32>,
+            Option<i32>,
+            Option<String>,
+            Option<String>,
             serde_json::Value,
             bool,
             chrono::DateTime<chrono::Utc>,
@@ -95,6 +298,13 @@ async fn list_contests(
           c.title,
           c.description,
           c.max_selections,
+          c.min_selections,
+          c.voting_method,
+          c.seats,
+          c.score_min,
+          c.score_max,
+          c.pass_threshold,
+          c.allocation_method,
           c.metadata,
           c.is_default,
           c.created_at,
@@ -104,7 +314,7 @@ async fn list_contests(
         LEFT JOIN candidates cand ON cand.contest_id = c.id
         LEFT JOIN voter_rolls vr ON vr.contest_id = c.id
         WHERE c.election_id = $1
-        GROUP BY c.id, c.title, c.description, c.max_selections, c.metadata, c.is_default, c.created_at
+        GROUP BY c.id
         ORDER BY c.is_default DESC, c.created_at ASC
         "#,
     )
@@ -113,16 +323,64 @@ async fn list_contests(
     .await
     .map_err(|_| AppError::Internal)?;
 
+    // Ballot settings added after the listing's columns, loaded for all contests at once.
+    let settings = sqlx::query_as::<
+        _,
+        (Uuid, Option<i32>, Option<i32>, Option<f64>, bool, bool, String),
+    >(
+        r#"
+        SELECT id, total_points, quorum_ballots, quorum_percent, allow_blank, allow_write_ins,
+               ballot_order
+        FROM contests
+        WHERE election_id = $1
+        "#,
+    )
+    .bind(election_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|_| AppError::Internal)?;
+
     let items: Vec<_> = rows
         .into_iter()
         .map(
-            |(id, title, description, max_selections, metadata, is_default, created_at, candidate_count, voter_count)| {
+            |(
+                id,
+                title,
+                description,
+                max_selections,
+                min_selections,
+                voting_method,
+                seats,
+                score_min,
+                score_max,
+                pass_threshold,
+                allocation_method,
+                metadata,
+                is_default,
+                created_at,
+                candidate_count,
+                voter_count,
+            )| {
+                let setting = settings.iter().find(|setting| setting.0 == id);
                 serde_json::json!({
                     "id": id,
                     "election_id": election_id,
                     "title": title,
                     "description": description,
                     "max_selections": max_selections,
+                    "min_selections": min_selections,
+                    "voting_method": voting_method,
+                    "seats": seats,
+                    "score_min": score_min,
+                    "score_max": score_max,
+                    "pass_threshold": pass_threshold,
+                    "allocation_method": allocation_method,
+                    "total_points": setting.and_then(|setting| setting.1),
+                    "quorum_ballots": setting.and_then(|setting| setting.2),
+                    "quorum_percent": setting.and_then(|setting| setting.3),
+                    "allow_blank": setting.map(|setting| setting.4),
+                    "allow_write_ins": setting.map(|setting| setting.5),
+                    "ballot_order": setting.map(|setting| &setting.6),
                     "metadata": metadata,
                     "is_default": is_default,
                     "created_at": created_at,
@@ -155,25 +413,58 @@ async fn create_contest(
         return Err(AppError::BadRequest("contest title is required".to_string()));
     }
 
-    let max_selections = body.max_selections.unwrap_or(1);
-    if max_selections < 1 {
-        return Err(AppError::BadRequest(
-            "max_selections must be >= 1".to_string(),
-        ));
+    let rules = ContestRules {
+        voting_method: body.voting_method.unwrap_or(VotingMethod::Plurality),
+        max_selections: body.max_selections.unwrap_or(1),
+        min_selections: body.min_selections.unwrap_or(1),
+        seats: body.seats.unwrap_or(1),
+        score_min: body.score_min,
+        score_max: body.score_max,
+        pass_threshold: body.pass_threshold,
+        allocation_method: body.allocation_method,
+        total_points: body.total_points,
+        quorum_ballots: body.quorum_ballots,
+        quorum_percent: body.quorum_percent,
+        allow_blank: body.allow_blank.unwrap_or(false),
+        allow_write_ins: body.allow_write_ins.unwrap_or(false),
+        ballot_order: body.ballot_order.unwrap_or(BallotOrder::Alphabetical),
     }
+    .normalized();
+    rules.validate()?;
 
     let contest_id = Uuid::new_v4();
     sqlx::query(
         r#"
-        INSERT INTO contests (id, election_id, title, description, max_selections, metadata, is_default)
-        VALUES ($1, $2, $3, $4, $5, $6, false)
+        INSERT INTO contests (
+          id, election_id, title, description, max_selections, min_selections, voting_method,
+          seats, score_min, score_max, pass_threshold, allocation_method, total_points,
+          quorum_ballots, quorum_percent, allow_blank, allow_write_ins, ballot_order, metadata,
+          is_default
+        )
+        VALUES (
+          $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19,
+          false
+        )
         "#,
     )
     .bind(contest_id)
     .bind(election_id)
     .bind(title)
     .bind(body.description.clone())
-    .bind(max_selections)
+    .bind(rules.max_selections)
+    .bind(rules.min_selections)
+    .bind(rules.voting_method.as_str())
+    .bind(rules.seats)
+    .bind(rules.score_min)
+    .bind(rules.score_max)
+    .bind(rules.pass_threshold.map(|threshold| threshold.as_str()))
+    .bind(rules.allocation_method.map(|method| method.as_str()))
+    .bind(rules.total_points)
+    .bind(rules.quorum_ballots)
+    .bind(rules.quorum_percent)
+    .bind(rules.allow_blank)
+    .bind(rules.allow_write_ins)
+    .bind(rules.ballot_order.as_str())
     .bind(body.metadata.clone().unwrap_or_else(|| serde_json::json!({})))
     .execute(pool.get_ref())
     .await
@@ -200,23 +491,55 @@ async fn update_contest(
     if title.is_empty() {
         return Err(AppError::BadRequest("contest title is required".to_string()));
     }
-    if body.max_selections < 1 {
-        return Err(AppError::BadRequest(
-            "max_selections must be >= 1".to_string(),
-        ));
+
+    let current = load_contest_rules(pool.get_ref(), contest_id).await?;
+    let rules = ContestRules {
+        voting_method: body.voting_method.unwrap_or(current.voting_method),
+        max_selections: body.max_selections,
+        min_selections: body.min_selections.unwrap_or(current.min_selections),
+        seats: body.seats.unwrap_or(current.seats),
+        score_min: body.score_min.or(current.score_min),
+        score_max: body.score_max.or(current.score_max),
+        pass_threshold: body.pass_threshold.or(current.pass_threshold),
+        allocation_method: body.allocation_method.or(current.allocation_method),
+        total_points: body.total_points.or(current.total_points),
+        quorum_ballots: body.quorum_ballots.or(current.quorum_ballots),
+        quorum_percent: body.quorum_percent.or(current.quorum_percent),
+        allow_blank: body.allow_blank.unwrap_or(current.allow_blank),
+        allow_write_ins: body.allow_write_ins.unwrap_or(current.allow_write_ins),
+        ballot_order: body.ballot_order.unwrap_or(current.ballot_order),
     }
+    .normalized();
+    rules.validate()?;
 
     let affected = sqlx::query(
         r#"
         UPDATE contests
-        SET title = $1, description = $2, max_selections = $3, metadata = $4
-        WHERE id = $5
+        SET title = $1, description = $2, max_selections = $3, metadata = $4,
+            voting_method = $5, seats = $6, min_selections = $7, score_min = $8, score_max = $9,
+            pass_threshold = $10, allocation_method = $11, total_points = $12,
+            quorum_ballots = $13, quorum_percent = $14, allow_blank = $15, allow_write_ins = $16,
+            ballot_order = $17
+        WHERE id = $18
         "#,
     )
     .bind(title)
     .bind(body.description.clone())
-    .bind(body.max_selections)
+    .bind(rules.max_selections)
     .bind(body.metadata.clone().unwrap_or_else(|| serde_json::json!({})))
+    .bind(rules.voting_method.as_str())
+    .bind(rules.seats)
+    .bind(rules.min_selections)
+    .bind(rules.score_min)
+    .bind(rules.score_max)
+    .bind(rules.pass_threshold.map(|threshold| threshold.as_str()))
+    .bind(rules.allocation_method.map(|method| method.as_str()))
+    .bind(rules.total_points)
+    .bind(rules.quorum_ballots)
+    .bind(rules.quorum_percent)
+    .bind(rules.allow_blank)
+    .bind(rules.allow_write_ins)
+    .bind(rules.ballot_order.as_str())
     .bind(contest_id)
     .execute(pool.get_ref())
     .await
@@ -289,8 +612,14 @@ async fn list_contest_candidates(
         .await
         .map_err(|_| AppError::Internal)?;
 
-    let rows = sqlx::query_as::<_, (Uuid, String, Option<String>)>(
-        "SELECT id, name, manifesto FROM candidates WHERE contest_id = $1 ORDER BY created_at ASC LIMIT $2 OFFSET $3",
+    let rows = sqlx::query_as::<_, (Uuid, String, Option<String>, Option<Uuid>, Option<i32>)>(
+        r#"
+        SELECT id, name, manifesto, party_id, ballot_position
+        FROM candidates
+        WHERE contest_id = $1
+        ORDER BY created_at ASC
+        LIMIT $2 OFFSET $3
+        "#,
     )
     .bind(contest_id)
     .bind(per_page)
@@ -301,7 +630,15 @@ async fn list_contest_candidates(
 
     let items: Vec<_> = rows
         .into_iter()
-        .map(|(id, name, manifesto)| serde_json::json!({ "id": id, "name": name, "manifesto": manifesto }))
+        .map(|(id, name, manifesto, party_id, ballot_position)| {
+            serde_json::json!({
+                "id": id,
+                "name": name,
+                "manifesto": manifesto,
+                "party_id": party_id,
+                "ballot_position": ballot_position
+            })
+        })
         .collect();
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
@@ -329,16 +666,36 @@ async fn create_contest_candidate(
     let contest_id = path.into_inner();
     let election_id = ensure_contest_election_draft(pool.get_ref(), contest_id).await?;
 
+    let rules = load_contest_rules(pool.get_ref(), contest_id).await?;
+    if rules.voting_method == VotingMethod::Referendum {
+        return Err(AppError::BadRequest(
+            "referendum contests do not have candidates".to_string(),
+        ));
+    }
+
+    if rules.voting_method == VotingMethod::PartyList && body.party_id.is_none() {
+        return Err(AppError::BadRequest(
+            "party-list candidates require a party_id".to_string(),
+        ));
+    }
+
     let name = body.name.trim();
     if name.is_empty() {
         return Err(AppError::BadRequest("candidate name is required".to_string()));
     }
 
+    if let Some(party_id) = body.party_id {
+        ensure_party_in_election(pool.get_ref(), election_id, party_id).await?;
+    }
+    validate_ballot_position(body.ballot_position)?;
+
     let candidate_id = Uuid::new_v4();
     sqlx::query(
         r#"
-        INSERT INTO candidates (id, election_id, contest_id, name, manifesto)
-        VALUES ($1, $2, $3, $4, $5)
+        INSERT INTO candidates (
+          id, election_id, contest_id, name, manifesto, party_id, ballot_position
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         "#,
     )
     .bind(candidate_id)
@@ -346,6 +703,8 @@ async fn create_contest_candidate(
     .bind(contest_id)
     .bind(name)
     .bind(body.manifesto.clone())
+    .bind(body.party_id)
+    .bind(body.ballot_position)
     .execute(pool.get_ref())
     .await
     .map_err(|_| AppError::Internal)?;
@@ -365,22 +724,35 @@ async fn update_contest_candidate(
     require_roles(&auth, &[UserRole::Admin, UserRole::ElectionOfficer])?;
 
     let (contest_id, candidate_id) = path.into_inner();
-    ensure_contest_election_draft(pool.get_ref(), contest_id).await?;
+    let election_id = ensure_contest_election_draft(pool.get_ref(), contest_id).await?;
 
     let name = body.name.trim();
     if name.is_empty() {
         return Err(AppError::BadRequest("candidate name is required".to_string()));
     }
 
+    let rules = load_contest_rules(pool.get_ref(), contest_id).await?;
+    if rules.voting_method == VotingMethod::PartyList && body.party_id.is_none() {
+        return Err(AppError::BadRequest(
+            "party-list candidates require a party_id".to_string(),
+        ));
+    }
+    if let Some(party_id) = body.party_id {
+        ensure_party_in_election(pool.get_ref(), election_id, party_id).await?;
+    }
+    validate_ballot_position(body.ballot_position)?;
+
     let affected = sqlx::query(
         r#"
         UPDATE candidates
-        SET name = $1, manifesto = $2
-        WHERE id = $3 AND contest_id = $4
+        SET name = $1, manifesto = $2, party_id = $3, ballot_position = $4
+        WHERE id = $5 AND contest_id = $6
         "#,
     )
     .bind(name)
     .bind(body.manifesto.clone())
+    .bind(body.party_id)
+    .bind(body.ballot_position)
     .bind(candidate_id)
     .bind(contest_id)
     .execute(pool.get_ref())
@@ -442,9 +814,9 @@ async fn list_contest_voter_rolls(
         .await
         .map_err(|_| AppError::Internal)?;
 
-    let rows = sqlx::query_as::<_, (Uuid, String, String)>(
+    let rows = sqlx::query_as::<_, (Uuid, String, String, i64)>(
         r#"
-        SELECT u.id, u.email, u.full_name
+        SELECT u.id, u.email, u.full_name, vr.weight
         FROM voter_rolls vr
         JOIN users u ON u.id = vr.user_id
         WHERE vr.contest_id = $1
@@ -461,8 +833,13 @@ async fn list_contest_voter_rolls(
 
     let items: Vec<_> = rows
         .into_iter()
-        .map(|(user_id, email, full_name)| {
-            serde_json::json!({ "user_id": user_id, "email": email, "full_name": full_name })
+        .map(|(user_id, email, full_name, weight)| {
+            serde_json::json!({
+                "user_id": user_id,
+                "email": email,
+                "full_name": full_name,
+                "weight": weight
+            })
         })
         .collect();
 
@@ -491,17 +868,25 @@ async fn add_contest_voter_roll(
     let contest_id = path.into_inner();
     let election_id = ensure_contest_election_draft(pool.get_ref(), contest_id).await?;
 
+    if body.weight.is_some_and(|weight| weight < 1) {
+        return Err(AppError::BadRequest(
+            "weight must be a positive integer".to_string(),
+        ));
+    }
+
     sqlx::query(
         r#"
-        INSERT INTO voter_rolls (id, election_id, contest_id, user_id)
-        VALUES ($1, $2, $3, $4)
-        ON CONFLICT (contest_id, user_id) DO NOTHING
+        INSERT INTO voter_rolls (id, election_id, contest_id, user_id, weight)
+        VALUES ($1, $2, $3, $4, COALESCE($5, 1))
+        ON CONFLICT (contest_id, user_id)
+        DO UPDATE SET weight = COALESCE($5, voter_rolls.weight)
         "#,
     )
     .bind(Uuid::new_v4())
     .bind(election_id)
     .bind(contest_id)
     .bind(body.user_id)
+    .bind(body.weight)
     .execute(pool.get_ref())
     .await
     .map_err(|_| AppError::Internal)?;
@@ -525,13 +910,13 @@ async fn import_contest_voter_rolls(
     let parsed = parse_import_identifiers(&body.format, &body.data)?;
 
     let mut seen_user_ids = HashSet::new();
-    let mut valid_user_ids: Vec<Uuid> = Vec::new();
+    let mut valid_user_ids: Vec<(Uuid, i64)> = Vec::new();
     let mut duplicate_rows = 0usize;
     let mut already_in_roll_rows = 0usize;
     let mut not_found_rows = 0usize;
     let mut issues: Vec<_> = Vec::new();
 
-    for (row, identifier) in parsed {
+    for (row, identifier, weight) in parsed {
         let user_row = resolve_user_by_identifier(pool.get_ref(), &identifier).await?;
         let Some(user_id) = user_row else {
             not_found_rows += 1;
@@ -566,16 +951,16 @@ async fn import_contest_voter_rolls(
             continue;
         }
 
-        valid_user_ids.push(user_id);
+        valid_user_ids.push((user_id, weight));
     }
 
     let mut inserted_rows = 0usize;
     if !dry_run {
-        for user_id in &valid_user_ids {
+        for (user_id, weight) in &valid_user_ids {
             let affected = sqlx::query(
                 r#"
-                INSERT INTO voter_rolls (id, election_id, contest_id, user_id)
-                VALUES ($1, $2, $3, $4)
+                INSERT INTO voter_rolls (id, election_id, contest_id, user_id, weight)
+                VALUES ($1, $2, $3, $4, $5)
                 ON CONFLICT (contest_id, user_id) DO NOTHING
                 "#,
             )
@@ -583,6 +968,7 @@ async fn import_contest_voter_rolls(
             .bind(election_id)
             .bind(contest_id)
             .bind(user_id)
+            .bind(weight)
             .execute(pool.get_ref())
             .await
             .map_err(|_| AppError::Internal)?
@@ -642,55 +1028,90 @@ async fn contest_results(
         ],
     )?;
 
-    let contest_id = path.into_inner();
+    let data = results::contest(pool.get_ref(), path.into_inner()).await?;
 
-    let row = sqlx::query_as::<_, (Uuid, String, String, String)>(
-        r#"
-        SELECT e.id, e.status, e.title, c.title
-        FROM contests c
-        JOIN elections e ON e.id = c.election_id
-        WHERE c.id = $1
-        "#,
-    )
-    .bind(contest_id)
-    .fetch_optional(pool.get_ref())
-    .await
-    .map_err(|_| AppError::Internal)?
-    .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": data })))
+}
 
-    if row.1 != "closed" {
-        return Err(AppError::Forbidden);
-    }
+#[post("/contests/{id}/runoff")]
+async fn create_contest_runoff(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<CreateRunoffRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Admin, UserRole::ElectionOfficer])?;
 
-    let results = sqlx::query_as::<_, (Uuid, String, i64)>(
-        r#"
-        SELECT v.candidate_id, c.name, COUNT(*)::bigint as total
-        FROM votes v
-        JOIN candidates c ON c.id = v.candidate_id
-        WHERE v.contest_id = $1
-        GROUP BY v.candidate_id, c.name
-        ORDER BY total DESC
-        "#,
+    let data = runoff::create(
+        pool.get_ref(),
+        path.into_inner(),
+        auth.user_id,
+        body.into_inner(),
     )
-    .bind(contest_id)
-    .fetch_all(pool.get_ref())
-    .await
-    .map_err(|_| AppError::Internal)?;
+    .await?;
 
-    let items: Vec<_> = results
-        .into_iter()
-        .map(|(candidate_id, name, total)| serde_json::json!({ "candidate_id": candidate_id, "name": name, "total": total }))
-        .collect();
+    Ok(HttpResponse::Created().json(serde_json::json!({ "data": data })))
+}
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "data": {
-            "contest_id": contest_id,
-            "contest_title": row.3,
-            "election_id": row.0,
-            "election_title": row.2,
-            "results": items
-        }
-    })))
+#[get("/contests/{id}/write-ins")]
+async fn list_contest_write_ins(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(
+        &auth,
+        &[
+            UserRole::Admin,
+            UserRole::ElectionOfficer,
+            UserRole::Auditor,
+        ],
+    )?;
+
+    let items = write_in::list(pool.get_ref(), path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": { "write_ins": items } })))
+}
+
+#[get("/contests/{id}/bulletin")]
+async fn contest_bulletin(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(
+        &auth,
+        &[
+            UserRole::Admin,
+            UserRole::ElectionOfficer,
+            UserRole::Auditor,
+            UserRole::Voter,
+        ],
+    )?;
+
+    let data = bulletin::board(pool.get_ref(), path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": data })))
+}
+
+#[post("/contests/{id}/write-ins/adjudicate")]
+async fn adjudicate_contest_write_in(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<AdjudicateWriteInRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Admin, UserRole::ElectionOfficer])?;
+
+    let data = write_in::adjudicate(
+        pool.get_ref(),
+        path.into_inner(),
+        auth.user_id,
+        body.into_inner(),
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": data })))
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -706,6 +1127,10 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(add_contest_voter_roll)
         .service(import_contest_voter_rolls)
         .service(remove_contest_voter_roll)
-        .service(contest_results);
+        .service(contest_results)
+        .service(create_contest_runoff)
+        .service(list_contest_write_ins)
+        .service(contest_bulletin)
+        .service(adjudicate_contest_write_in);
 }
 
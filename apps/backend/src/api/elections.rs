@@ -6,9 +6,11 @@ use uuid::Uuid;
 
 use crate::{
     api::{
+        contests::{ensure_party_in_election, validate_ballot_position},
         pagination::{normalize_pagination, total_pages, PaginationQuery},
         voter_roll_import::{parse_import_identifiers, resolve_user_by_identifier},
     },
+    config::AppConfig,
     domain::{
         AddVoterRollRequest, CreateCandidateRequest, CreateElectionRequest,
         CreateOrganizationRequest, ImportVoterRollRequest, UpdateCandidateRequest,
@@ -16,7 +18,8 @@ use crate::{
     },
     errors::AppError,
     middleware::{require_roles, AuthenticatedUser},
-    services::election,
+    services::{self, election},
+    tally,
 };
 
 async fn resolve_default_contest_id(pool: &PgPool, election_id: Uuid) -> Result<Uuid, AppError> {
@@ -227,9 +230,22 @@ async fn get_election(
             String,
             chrono::DateTime<chrono::Utc>,
             chrono::DateTime<chrono::Utc>,
+            String,
+            Option<String>,
+            bool,
+            bool,
+            Option<i32>,
+            Option<String>,
+            Option<String>,
         ),
     >(
-        "SELECT id, title, description, status, opens_at, closes_at FROM elections WHERE id = $1",
+        r#"
+        SELECT id, title, description, status, opens_at, closes_at, tie_break_policy, tie_break_seed,
+               allow_revote, encrypted_ballots, trustee_threshold, encryption_public_key,
+               tie_break_commitment
+        FROM elections
+        WHERE id = $1
+        "#,
     )
     .bind(id)
     .fetch_optional(pool.get_ref())
@@ -264,7 +280,14 @@ async fn get_election(
             "opens_at": row.4,
             "closes_at": row.5,
             "candidate_count": candidate_count,
-            "voter_count": voter_count
+            "voter_count": voter_count,
+            "tie_break_policy": row.6,
+            "tie_break_commitment": row.12.or_else(|| row.7.as_deref().map(tally::lot::commitment)),
+            "tie_break_seed": if row.3 == "closed" { row.7 } else { None },
+            "allow_revote": row.8,
+            "encrypted_ballots": row.9,
+            "trustee_threshold": row.10,
+            "encryption_public_key": row.11
         }
     })))
 }
@@ -287,17 +310,33 @@ async fn update_election(
         ));
     }
 
+    let tie_break_seed =
+        election::tie_break_seed(input.tie_break_policy, input.tie_break_seed.as_deref())?;
+
+    // Switching to lot drawing keeps an existing seed and only uses the generated one otherwise.
     let affected = sqlx::query(
         r#"
         UPDATE elections
-        SET title = $1, description = $2, opens_at = $3, closes_at = $4
-        WHERE id = $5 AND status = 'draft'
+        SET title = $1, description = $2, opens_at = $3, closes_at = $4,
+            tie_break_policy = COALESCE($5, tie_break_policy),
+            tie_break_seed = CASE
+              WHEN $6::text IS NOT NULL AND $7 THEN $6
+              ELSE COALESCE(tie_break_seed, $6)
+            END,
+            allow_revote = COALESCE($8, allow_revote),
+            encrypted_ballots = COALESCE($9, encrypted_ballots)
+        WHERE id = $10 AND status = 'draft'
         "#,
     )
     .bind(input.title.trim())
     .bind(input.description)
     .bind(input.opens_at)
     .bind(input.closes_at)
+    .bind(input.tie_break_policy.map(|policy| policy.as_str()))
+    .bind(tie_break_seed)
+    .bind(input.tie_break_seed.is_some())
+    .bind(input.allow_revote)
+    .bind(input.encrypted_ballots)
     .bind(election_id)
     .execute(pool.get_ref())
     .await
@@ -322,19 +361,40 @@ async fn publish(
     require_roles(&auth, &[UserRole::Admin, UserRole::ElectionOfficer])?;
 
     let id = path.into_inner();
-    let affected =
-        sqlx::query("UPDATE elections SET status = 'published' WHERE id = $1 AND status = 'draft'")
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+    services::key_ceremony::ensure_ready(&mut tx, id).await?;
+    let seed = sqlx::query_scalar::<_, Option<String>>(
+        r#"
+        UPDATE elections SET status = 'published'
+        WHERE id = $1 AND status = 'draft'
+        RETURNING tie_break_seed
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .ok_or_else(|| AppError::Conflict("election not in draft state".to_string()))?;
+
+    // The seed can no longer change, and its commitment is frozen and logged before any ballot.
+    if let Some(seed) = seed {
+        let commitment = tally::lot::commitment(&seed);
+        sqlx::query("UPDATE elections SET tie_break_commitment = $2 WHERE id = $1")
             .bind(id)
-            .execute(pool.get_ref())
+            .bind(&commitment)
+            .execute(&mut *tx)
             .await
-            .map_err(|_| AppError::Internal)?
-            .rows_affected();
-
-    if affected == 0 {
-        return Err(AppError::Conflict(
-            "election not in draft state".to_string(),
-        ));
+            .map_err(|_| AppError::Internal)?;
+        services::audit::record(
+            &mut tx,
+            "tie_break_committed",
+            auth.user_id,
+            id,
+            serde_json::json!({ "seed_commitment": commitment }),
+        )
+        .await?;
     }
+    tx.commit().await.map_err(|_| AppError::Internal)?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({ "data": { "status": "published" } })))
 }
@@ -342,17 +402,19 @@ async fn publish(
 #[patch("/elections/{id}/close")]
 async fn close(
     pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
     auth: AuthenticatedUser,
     path: web::Path<Uuid>,
 ) -> Result<HttpResponse, AppError> {
     require_roles(&auth, &[UserRole::Admin, UserRole::ElectionOfficer])?;
 
     let id = path.into_inner();
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
     let affected = sqlx::query(
         "UPDATE elections SET status = 'closed' WHERE id = $1 AND status = 'published'",
     )
     .bind(id)
-    .execute(pool.get_ref())
+    .execute(&mut *tx)
     .await
     .map_err(|_| AppError::Internal)?
     .rows_affected();
@@ -363,6 +425,20 @@ async fn close(
         ));
     }
 
+    services::ballot_queue::flush_election(&mut tx, &config.ballot_link_key, id).await?;
+    services::prepared_ballot::discard_election(&mut tx, id).await?;
+    services::bulletin::snapshot(&mut tx, id, auth.user_id).await?;
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+
+    let contest_ids = sqlx::query_scalar::<_, Uuid>("SELECT id FROM contests WHERE election_id = $1")
+        .bind(id)
+        .fetch_all(pool.get_ref())
+        .await
+        .map_err(|_| AppError::Internal)?;
+    for contest_id in contest_ids {
+        services::results::record_tie_break(pool.get_ref(), contest_id, auth.user_id).await?;
+    }
+
     Ok(HttpResponse::Ok().json(serde_json::json!({ "data": { "status": "closed" } })))
 }
 
@@ -394,30 +470,67 @@ async fn results(
         return Err(AppError::Forbidden);
     }
 
-    let rows = sqlx::query_as::<_, (Uuid, String, i64)>(
-        r#"
-        SELECT v.candidate_id, c.name, COUNT(*)::bigint as total
-        FROM votes v
-        JOIN candidates c ON c.id = v.candidate_id
-        WHERE v.election_id = $1
-        GROUP BY v.candidate_id, c.name
-        ORDER BY total DESC
-        "#,
+    let contest_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM contests WHERE election_id = $1 ORDER BY is_default DESC, created_at ASC",
     )
     .bind(id)
     .fetch_all(pool.get_ref())
     .await
     .map_err(|_| AppError::Internal)?;
 
-    let items: Vec<_> = rows
-        .into_iter()
-        .map(|(candidate_id, name, total)| {
-            serde_json::json!({ "candidate_id": candidate_id, "name": name, "total": total })
-        })
-        .collect();
+    // Each contest is declared by its own method, seats and tie-break policy; the flat `results`
+    // list repeats every contest's candidate rows with that contest's outcome.
+    let mut contests = Vec::with_capacity(contest_ids.len());
+    let mut items = Vec::new();
+    for contest_id in contest_ids {
+        let contest = services::results::contest(pool.get_ref(), contest_id).await?;
+        let elected: Vec<&str> = contest["elected"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .chain(std::iter::once(&contest["winner"]))
+            .filter_map(|candidate| candidate["candidate_id"].as_str())
+            .collect();
+        let tied: Vec<&str> = contest["tied"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|candidate_id| candidate_id.as_str())
+            .collect();
+
+        for row in contest["results"].as_array().into_iter().flatten() {
+            let Some(candidate_id) = row["candidate_id"].as_str() else {
+                continue;
+            };
+            items.push(serde_json::json!({
+                "candidate_id": candidate_id,
+                "name": row["name"],
+                "total": row["total"],
+                "contest_id": contest_id,
+                "elected": elected.contains(&candidate_id),
+                "tied": tied.contains(&candidate_id),
+                "valid": contest["valid"]
+            }));
+        }
+        contests.push(contest);
+    }
+    items.sort_by_key(|item| std::cmp::Reverse(item["total"].as_i64().unwrap_or(0)));
 
-    Ok(HttpResponse::Ok()
-        .json(serde_json::json!({ "data": { "election_id": id, "results": items } })))
+    let blank_ballots: i64 = contests
+        .iter()
+        .filter_map(|contest| contest["blank_ballots"].as_i64())
+        .sum();
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "data": {
+            "election_id": id,
+            "results": items,
+            "blank_ballots": blank_ballots,
+            "valid": contests.iter().all(|contest| contest["valid"].as_bool() == Some(true)),
+            "turnout": contests.iter().map(|contest| &contest["turnout"]).collect::<Vec<_>>(),
+            "contests": contests
+        }
+    })))
 }
 
 #[get("/elections/{id}/candidates")]
@@ -442,8 +555,14 @@ async fn list_candidates(
             .await
             .map_err(|_| AppError::Internal)?;
 
-    let rows = sqlx::query_as::<_, (Uuid, String, Option<String>)>(
-        "SELECT id, name, manifesto FROM candidates WHERE contest_id = $1 ORDER BY created_at ASC LIMIT $2 OFFSET $3",
+    let rows = sqlx::query_as::<_, (Uuid, String, Option<String>, Option<Uuid>, Option<i32>)>(
+        r#"
+        SELECT id, name, manifesto, party_id, ballot_position
+        FROM candidates
+        WHERE contest_id = $1
+        ORDER BY created_at ASC
+        LIMIT $2 OFFSET $3
+        "#,
     )
     .bind(contest_id)
     .bind(per_page)
@@ -454,8 +573,14 @@ async fn list_candidates(
 
     let items: Vec<_> = rows
         .into_iter()
-        .map(|(id, name, manifesto)| {
-            serde_json::json!({ "id": id, "name": name, "manifesto": manifesto })
+        .map(|(id, name, manifesto, party_id, ballot_position)| {
+            serde_json::json!({
+                "id": id,
+                "name": name,
+                "manifesto": manifesto,
+                "party_id": party_id,
+                "ballot_position": ballot_position
+            })
         })
         .collect();
 
@@ -483,12 +608,18 @@ async fn create_candidate(
 
     let election_id = path.into_inner();
     let contest_id = resolve_default_contest_id(pool.get_ref(), election_id).await?;
+    if let Some(party_id) = body.party_id {
+        ensure_party_in_election(pool.get_ref(), election_id, party_id).await?;
+    }
+    validate_ballot_position(body.ballot_position)?;
     let candidate_id = Uuid::new_v4();
 
     sqlx::query(
         r#"
-        INSERT INTO candidates (id, election_id, contest_id, name, manifesto)
-        VALUES ($1, $2, $3, $4, $5)
+        INSERT INTO candidates (
+          id, election_id, contest_id, name, manifesto, party_id, ballot_position
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         "#,
     )
     .bind(candidate_id)
@@ -496,6 +627,8 @@ async fn create_candidate(
     .bind(contest_id)
     .bind(body.name.trim())
     .bind(body.manifesto.clone())
+    .bind(body.party_id)
+    .bind(body.ballot_position)
     .execute(pool.get_ref())
     .await
     .map_err(|_| AppError::Internal)?;
@@ -523,15 +656,22 @@ async fn update_candidate(
         ));
     }
 
+    if let Some(party_id) = body.party_id {
+        ensure_party_in_election(pool.get_ref(), election_id, party_id).await?;
+    }
+    validate_ballot_position(body.ballot_position)?;
+
     let affected = sqlx::query(
         r#"
         UPDATE candidates
-        SET name = $1, manifesto = $2
-        WHERE id = $3 AND election_id = $4 AND contest_id = $5
+        SET name = $1, manifesto = $2, party_id = $3, ballot_position = $4
+        WHERE id = $5 AND election_id = $6 AND contest_id = $7
         "#,
     )
     .bind(name)
     .bind(body.manifesto.clone())
+    .bind(body.party_id)
+    .bind(body.ballot_position)
     .bind(candidate_id)
     .bind(election_id)
     .bind(contest_id)
@@ -594,9 +734,9 @@ async fn list_voter_rolls(
             .await
             .map_err(|_| AppError::Internal)?;
 
-    let rows = sqlx::query_as::<_, (Uuid, String, String)>(
+    let rows = sqlx::query_as::<_, (Uuid, String, String, i64)>(
         r#"
-        SELECT u.id, u.email, u.full_name
+        SELECT u.id, u.email, u.full_name, vr.weight
         FROM voter_rolls vr
         JOIN users u ON u.id = vr.user_id
         WHERE vr.contest_id = $1
@@ -613,8 +753,13 @@ async fn list_voter_rolls(
 
     let items: Vec<_> = rows
         .into_iter()
-        .map(|(user_id, email, full_name)| {
-            serde_json::json!({ "user_id": user_id, "email": email, "full_name": full_name })
+        .map(|(user_id, email, full_name, weight)| {
+            serde_json::json!({
+                "user_id": user_id,
+                "email": email,
+                "full_name": full_name,
+                "weight": weight
+            })
         })
         .collect();
 
@@ -644,17 +789,25 @@ async fn add_voter_roll(
     let contest_id = resolve_default_contest_id(pool.get_ref(), election_id).await?;
     let row_id = Uuid::new_v4();
 
+    if body.weight.is_some_and(|weight| weight < 1) {
+        return Err(AppError::BadRequest(
+            "weight must be a positive integer".to_string(),
+        ));
+    }
+
     sqlx::query(
         r#"
-        INSERT INTO voter_rolls (id, election_id, contest_id, user_id)
-        VALUES ($1, $2, $3, $4)
-        ON CONFLICT (contest_id, user_id) DO NOTHING
+        INSERT INTO voter_rolls (id, election_id, contest_id, user_id, weight)
+        VALUES ($1, $2, $3, $4, COALESCE($5, 1))
+        ON CONFLICT (contest_id, user_id)
+        DO UPDATE SET weight = COALESCE($5, voter_rolls.weight)
         "#,
     )
     .bind(row_id)
     .bind(election_id)
     .bind(contest_id)
     .bind(body.user_id)
+    .bind(body.weight)
     .execute(pool.get_ref())
     .await
     .map_err(|_| AppError::Internal)?;
@@ -677,13 +830,13 @@ async fn import_voter_rolls(
     let parsed = parse_import_identifiers(&body.format, &body.data)?;
 
     let mut seen_user_ids = HashSet::new();
-    let mut valid_user_ids: Vec<Uuid> = Vec::new();
+    let mut valid_user_ids: Vec<(Uuid, i64)> = Vec::new();
     let mut duplicate_rows = 0usize;
     let mut already_in_roll_rows = 0usize;
     let mut not_found_rows = 0usize;
     let mut issues: Vec<_> = Vec::new();
 
-    for (row, identifier) in parsed {
+    for (row, identifier, weight) in parsed {
         let user_row = resolve_user_by_identifier(pool.get_ref(), &identifier).await?;
         let Some(user_id) = user_row else {
             not_found_rows += 1;
@@ -718,16 +871,16 @@ async fn import_voter_rolls(
             continue;
         }
 
-        valid_user_ids.push(user_id);
+        valid_user_ids.push((user_id, weight));
     }
 
     let mut inserted_rows = 0usize;
     if !dry_run {
-        for user_id in &valid_user_ids {
+        for (user_id, weight) in &valid_user_ids {
             let affected = sqlx::query(
                 r#"
-                INSERT INTO voter_rolls (id, election_id, contest_id, user_id)
-                VALUES ($1, $2, $3, $4)
+                INSERT INTO voter_rolls (id, election_id, contest_id, user_id, weight)
+                VALUES ($1, $2, $3, $4, $5)
                 ON CONFLICT (contest_id, user_id) DO NOTHING
                 "#,
             )
@@ -735,6 +888,7 @@ async fn import_voter_rolls(
             .bind(election_id)
             .bind(contest_id)
             .bind(user_id)
+            .bind(weight)
             .execute(pool.get_ref())
             .await
             .map_err(|_| AppError::Internal)?
@@ -1,10 +1,13 @@
 use actix_web::web;
 
+pub mod audit;
 pub mod auth;
 pub mod contests;
 pub mod elections;
 pub mod health;
 mod pagination;
+pub mod parties;
+pub mod trustees;
 mod voter_roll_import;
 pub mod votes;
 
@@ -12,9 +15,12 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(web::scope("/health").configure(health::configure))
         .service(
             web::scope("/api/v1")
+                .configure(audit::configure)
                 .configure(auth::configure)
                 .configure(contests::configure)
                 .configure(elections::configure)
+                .configure(parties::configure)
+                .configure(trustees::configure)
                 .configure(votes::configure),
         );
 }
@@ -0,0 +1,181 @@
+use actix_web::{delete, get, patch, post, web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    api::contests::ensure_election_draft,
+    domain::{CreatePartyRequest, UpdatePartyRequest, UserRole},
+    errors::AppError,
+    middleware::{require_roles, AuthenticatedUser},
+};
+
+fn map_party_write_error(err: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(db_err) = &err {
+        if db_err.constraint() == Some("uniq_parties_election_name") {
+            return AppError::Conflict("party name already exists".to_string());
+        }
+    }
+    AppError::Internal
+}
+
+#[get("/elections/{id}/parties")]
+async fn list_parties(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(
+        &auth,
+        &[
+            UserRole::Admin,
+            UserRole::ElectionOfficer,
+            UserRole::Auditor,
+        ],
+    )?;
+
+    let election_id = path.into_inner();
+    let rows = sqlx::query_as::<
+        _,
+        (
+            Uuid,
+            String,
+            Option<String>,
+            chrono::DateTime<chrono::Utc>,
+            i64,
+        ),
+    >(
+        r#"
+        SELECT p.id, p.name, p.abbreviation, p.created_at, COUNT(c.id)::bigint AS candidate_count
+        FROM parties p
+        LEFT JOIN candidates c ON c.party_id = p.id
+        WHERE p.election_id = $1
+        GROUP BY p.id
+        ORDER BY p.created_at ASC, p.id ASC
+        "#,
+    )
+    .bind(election_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let items: Vec<_> = rows
+        .into_iter()
+        .map(|(id, name, abbreviation, created_at, candidate_count)| {
+            serde_json::json!({
+                "id": id,
+                "election_id": election_id,
+                "name": name,
+                "abbreviation": abbreviation,
+                "created_at": created_at,
+                "candidate_count": candidate_count
+            })
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "data": { "parties": items }
+    })))
+}
+
+#[post("/elections/{id}/parties")]
+async fn create_party(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<CreatePartyRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Admin, UserRole::ElectionOfficer])?;
+
+    let election_id = path.into_inner();
+    ensure_election_draft(pool.get_ref(), election_id).await?;
+
+    let name = body.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("party name is required".to_string()));
+    }
+
+    let party_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO parties (id, election_id, name, abbreviation) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(party_id)
+    .bind(election_id)
+    .bind(name)
+    .bind(body.abbreviation.clone())
+    .execute(pool.get_ref())
+    .await
+    .map_err(map_party_write_error)?;
+
+    Ok(HttpResponse::Created().json(serde_json::json!({
+        "data": { "party_id": party_id }
+    })))
+}
+
+#[patch("/elections/{id}/parties/{party_id}")]
+async fn update_party(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<UpdatePartyRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Admin, UserRole::ElectionOfficer])?;
+
+    let (election_id, party_id) = path.into_inner();
+    ensure_election_draft(pool.get_ref(), election_id).await?;
+
+    let name = body.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("party name is required".to_string()));
+    }
+
+    let affected = sqlx::query(
+        "UPDATE parties SET name = $1, abbreviation = $2 WHERE id = $3 AND election_id = $4",
+    )
+    .bind(name)
+    .bind(body.abbreviation.clone())
+    .bind(party_id)
+    .bind(election_id)
+    .execute(pool.get_ref())
+    .await
+    .map_err(map_party_write_error)?
+    .rows_affected();
+
+    if affected == 0 {
+        return Err(AppError::NotFound("party not found".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": { "ok": true } })))
+}
+
+#[delete("/elections/{id}/parties/{party_id}")]
+async fn delete_party(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Admin, UserRole::ElectionOfficer])?;
+
+    let (election_id, party_id) = path.into_inner();
+    ensure_election_draft(pool.get_ref(), election_id).await?;
+
+    let affected = sqlx::query("DELETE FROM parties WHERE id = $1 AND election_id = $2")
+        .bind(party_id)
+        .bind(election_id)
+        .execute(pool.get_ref())
+        .await
+        .map_err(|_| AppError::Internal)?
+        .rows_affected();
+
+    if affected == 0 {
+        return Err(AppError::NotFound("party not found".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": { "ok": true } })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(list_parties)
+        .service(create_party)
+        .service(update_party)
+        .service(delete_party);
+}
@@ -0,0 +1,151 @@
+use actix_web::{get, post, web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        CreateTrusteesRequest, PostKeyCommitmentsRequest, PostKeySharesRequest,
+        SubmitDecryptionSharesRequest, UserRole,
+    },
+    errors::AppError,
+    middleware::{require_roles, AuthenticatedUser},
+    services::{encrypted_tally, key_ceremony},
+};
+
+const ALL_ROLES: &[UserRole] = &[
+    UserRole::Admin,
+    UserRole::ElectionOfficer,
+    UserRole::Auditor,
+    UserRole::Voter,
+];
+
+#[post("/elections/{id}/trustees")]
+async fn appoint_trustees(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<CreateTrusteesRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Admin, UserRole::ElectionOfficer])?;
+
+    let data = key_ceremony::appoint(
+        pool.get_ref(),
+        path.into_inner(),
+        auth.user_id,
+        body.into_inner(),
+    )
+    .await?;
+
+    Ok(HttpResponse::Created().json(serde_json::json!({ "data": data })))
+}
+
+#[get("/elections/{id}/key-ceremony")]
+async fn key_ceremony_status(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, ALL_ROLES)?;
+
+    let data = key_ceremony::status(pool.get_ref(), path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": data })))
+}
+
+// Trustees may hold any role; the services check that the caller is appointed.
+#[post("/elections/{id}/key-ceremony/commitments")]
+async fn post_commitments(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<PostKeyCommitmentsRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, ALL_ROLES)?;
+
+    let data = key_ceremony::post_commitments(
+        pool.get_ref(),
+        path.into_inner(),
+        auth.user_id,
+        body.into_inner(),
+    )
+    .await?;
+
+    Ok(HttpResponse::Created().json(serde_json::json!({ "data": data })))
+}
+
+#[post("/elections/{id}/key-ceremony/shares")]
+async fn post_shares(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<PostKeySharesRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, ALL_ROLES)?;
+
+    let data = key_ceremony::post_shares(
+        pool.get_ref(),
+        path.into_inner(),
+        auth.user_id,
+        body.into_inner(),
+    )
+    .await?;
+
+    Ok(HttpResponse::Created().json(serde_json::json!({ "data": data })))
+}
+
+#[get("/elections/{id}/key-ceremony/shares")]
+async fn received_shares(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, ALL_ROLES)?;
+
+    let data =
+        key_ceremony::received_shares(pool.get_ref(), path.into_inner(), auth.user_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": data })))
+}
+
+#[post("/elections/{id}/decryption-shares")]
+async fn submit_decryption_shares(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<SubmitDecryptionSharesRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, ALL_ROLES)?;
+
+    let data = encrypted_tally::submit_shares(
+        pool.get_ref(),
+        path.into_inner(),
+        auth.user_id,
+        body.into_inner(),
+    )
+    .await?;
+
+    Ok(HttpResponse::Created().json(serde_json::json!({ "data": data })))
+}
+
+#[get("/contests/{id}/encrypted-tally")]
+async fn contest_encrypted_tally(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, ALL_ROLES)?;
+
+    let data = encrypted_tally::contest_tally(pool.get_ref(), path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": data })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(appoint_trustees)
+        .service(key_ceremony_status)
+        .service(post_commitments)
+        .service(post_shares)
+        .service(received_shares)
+        .service(submit_decryption_shares)
+        .service(contest_encrypted_tally);
+}
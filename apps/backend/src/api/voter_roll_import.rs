@@ -1,9 +1,20 @@
+use serde::Deserialize;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::errors::AppError;
 
-pub fn parse_import_identifiers(format: &str, data: &str) -> Result<Vec<(usize, String)>, AppError> {
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonImportRow {
+    Identifier(String),
+    Weighted { identifier: String, weight: Option<i64> },
+}
+
+pub fn parse_import_identifiers(
+    format: &str,
+    data: &str,
+) -> Result<Vec<(usize, String, i64)>, AppError> {
     match format.to_lowercase().as_str() {
         "json" => parse_json_identifiers(data),
         "csv" => parse_csv_identifiers(data),
@@ -13,26 +24,39 @@ pub fn parse_import_identifiers(format: &str, data: &str) -> Result<Vec<(usize,
     }
 }
 
-fn parse_json_identifiers(data: &str) -> Result<Vec<(usize, String)>, AppError> {
-    let values: Vec<String> = serde_json::from_str(data)
-        .map_err(|_| AppError::BadRequest("invalid json, expected string array".to_string()))?;
-
-    let rows: Vec<(usize, String)> = values
-        .into_iter()
-        .enumerate()
-        .filter_map(|(idx, value)| {
-            let trimmed = value.trim().to_string();
-            if trimmed.is_empty() {
-                return None;
-            }
-            Some((idx + 1, trimmed))
-        })
-        .collect();
+fn parse_json_identifiers(data: &str) -> Result<Vec<(usize, String, i64)>, AppError> {
+    let values: Vec<JsonImportRow> = serde_json::from_str(data).map_err(|_| {
+        AppError::BadRequest(
+            "invalid json, expected array of strings or {identifier, weight} objects".to_string(),
+        )
+    })?;
+
+    let mut rows = Vec::new();
+    for (idx, value) in values.into_iter().enumerate() {
+        let (identifier, weight) = match value {
+            JsonImportRow::Identifier(identifier) => (identifier, None),
+            JsonImportRow::Weighted { identifier, weight } => (identifier, weight),
+        };
+
+        let trimmed = identifier.trim().to_string();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let weight = weight.unwrap_or(1);
+        if weight < 1 {
+            return Err(AppError::BadRequest(format!(
+                "invalid json row {}: weight must be a positive integer",
+                idx + 1
+            )));
+        }
+        rows.push((idx + 1, trimmed, weight));
+    }
 
     Ok(rows)
 }
 
-fn parse_csv_identifiers(data: &str) -> Result<Vec<(usize, String)>, AppError> {
+fn parse_csv_identifiers(data: &str) -> Result<Vec<(usize, String, i64)>, AppError> {
     let mut rows = Vec::new();
 
     for (idx, line) in data.lines().enumerate() {
@@ -42,12 +66,9 @@ fn parse_csv_identifiers(data: &str) -> Result<Vec<(usize, String)>, AppError> {
             continue;
         }
 
-        let first_col = trimmed
-            .split(',')
-            .next()
-            .map(str::trim)
-            .unwrap_or_default()
-            .to_string();
+        let mut columns = trimmed.split(',').map(str::trim);
+        let first_col = columns.next().unwrap_or_default().to_string();
+        let weight_col = columns.next().unwrap_or_default();
 
         if line_no == 1 {
             let header = first_col.to_lowercase();
@@ -63,7 +84,21 @@ fn parse_csv_identifiers(data: &str) -> Result<Vec<(usize, String)>, AppError> {
             )));
         }
 
-        rows.push((line_no, first_col));
+        let weight = if weight_col.is_empty() {
+            1
+        } else {
+            match weight_col.parse::<i64>() {
+                Ok(weight) if weight >= 1 => weight,
+                _ => {
+                    return Err(AppError::BadRequest(format!(
+                        "invalid csv row {}: weight must be a positive integer",
+                        line_no
+                    )));
+                }
+            }
+        };
+
+        rows.push((line_no, first_col, weight));
     }
 
     Ok(rows)
@@ -1,12 +1,23 @@
+use std::collections::HashMap;
+
 use actix_web::{get, post, web, HttpResponse};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::{
-    domain::{CastVoteRequest, UserRole},
+    config::AppConfig,
+    domain::{
+        BallotOrder, CastPreparedBallotRequest, CastVoteRequest, PrepareBallotRequest,
+        ReceiptStatus, ReferendumResponse, SubmitBallotRequest, UserRole, VoteReceiptResponse,
+        VotingMethod,
+    },
     errors::AppError,
     middleware::{require_roles, AuthenticatedUser},
-    services::vote,
+    security::{ballot_link, receipt_signing},
+    services::{
+        ballot_order::{self, BallotCandidate},
+        bulletin, prepared_ballot, vote,
+    },
 };
 
 async fn resolve_default_contest_id(pool: &PgPool, election_id: Uuid) -> Result<Uuid, AppError> {
@@ -37,6 +48,7 @@ async fn list_votable_elections(
             String,
             chrono::DateTime<chrono::Utc>,
             chrono::DateTime<chrono::Utc>,
+            bool,
             i64,
             bool,
             bool,
@@ -50,6 +62,7 @@ async fn list_votable_elections(
           e.status,
           e.opens_at,
           e.closes_at,
+          e.allow_revote,
           COUNT(DISTINCT c.id)::bigint AS candidate_count,
           EXISTS(
             SELECT 1
@@ -92,12 +105,15 @@ async fn list_votable_elections(
                 status,
                 opens_at,
                 closes_at,
+                allow_revote,
                 candidate_count,
                 has_voted,
                 has_unvoted_contest,
             )| {
-                let can_vote_now =
-                    status == "published" && now >= opens_at && now <= closes_at && has_unvoted_contest;
+                let can_vote_now = status == "published"
+                    && now >= opens_at
+                    && now <= closes_at
+                    && (has_unvoted_contest || allow_revote);
 
                 serde_json::json!({
                     "id": id,
@@ -144,6 +160,7 @@ async fn list_votable_contests(
             String,
             chrono::DateTime<chrono::Utc>,
             chrono::DateTime<chrono::Utc>,
+            bool,
             i64,
             bool,
         ),
@@ -162,6 +179,7 @@ async fn list_votable_contests(
           e.status,
           e.opens_at,
           e.closes_at,
+          e.allow_revote,
           COUNT(DISTINCT cand.id)::bigint AS candidate_count,
           EXISTS(
             SELECT 1
@@ -200,11 +218,14 @@ async fn list_votable_contests(
                 status,
                 opens_at,
                 closes_at,
+                allow_revote,
                 candidate_count,
                 has_voted,
             )| {
-                let can_vote_now =
-                    status == "published" && now >= opens_at && now <= closes_at && !has_voted;
+                let can_vote_now = status == "published"
+                    && now >= opens_at
+                    && now <= closes_at
+                    && (!has_voted || allow_revote);
 
                 serde_json::json!({
                     "id": contest_id,
@@ -253,8 +274,15 @@ async fn list_my_contests_for_election(
             String,
             chrono::DateTime<chrono::Utc>,
             chrono::DateTime<chrono::Utc>,
+            bool,
         ),
-    >("SELECT title, description, status, opens_at, closes_at FROM elections WHERE id = $1")
+    >(
+        r#"
+        SELECT title, description, status, opens_at, closes_at, allow_revote
+        FROM elections
+        WHERE id = $1
+        "#,
+    )
     .bind(election_id)
     .fetch_optional(pool.get_ref())
     .await
@@ -308,7 +336,7 @@ async fn list_my_contests_for_election(
                 let can_vote_now = election.2 == "published"
                     && now >= election.3
                     && now <= election.4
-                    && !has_voted;
+                    && (!has_voted || election.5);
 
                 serde_json::json!({
                     "id": id,
@@ -340,6 +368,14 @@ async fn list_my_contests_for_election(
     })))
 }
 
+// Voters see a ballot only once its election is published; admins may preview drafts.
+fn ensure_ballot_visible(auth: &AuthenticatedUser, status: &str) -> Result<(), AppError> {
+    if auth.role == UserRole::Voter && status == "draft" {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
 #[get("/elections/{id}/ballot")]
 async fn get_ballot(
     pool: web::Data<PgPool>,
@@ -359,6 +395,8 @@ async fn get_ballot(
             .map_err(|_| AppError::Internal)?
             .ok_or_else(|| AppError::NotFound("election not found".to_string()))?;
 
+    ensure_ballot_visible(&auth, &election.1)?;
+
     if auth.role == UserRole::Voter {
         let eligible = sqlx::query_scalar::<_, i64>(
             "SELECT COUNT(*) FROM voter_rolls WHERE contest_id = $1 AND user_id = $2",
@@ -374,18 +412,8 @@ async fn get_ballot(
         }
     }
 
-    let candidates = sqlx::query_as::<_, (Uuid, String, Option<String>)>(
-        "SELECT id, name, manifesto FROM candidates WHERE contest_id = $1 ORDER BY name ASC",
-    )
-    .bind(contest_id)
-    .fetch_all(pool.get_ref())
-    .await
-    .map_err(|_| AppError::Internal)?;
-
-    let items: Vec<_> = candidates
-        .into_iter()
-        .map(|(id, name, manifesto)| serde_json::json!({ "id": id, "name": name, "manifesto": manifesto }))
-        .collect();
+    let (ballot_order, items) =
+        ballot_order::ballot_candidates(pool.get_ref(), contest_id, auth.user_id).await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "data": {
@@ -393,6 +421,7 @@ async fn get_ballot(
             "contest_id": contest_id,
             "title": election.0,
             "status": election.1,
+            "ballot_order": ballot_order,
             "candidates": items
         }
     })))
@@ -401,13 +430,34 @@ async fn get_ballot(
 #[get("/contests/{id}/ballot")]
 async fn get_contest_ballot(
     pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
     auth: AuthenticatedUser,
     path: web::Path<Uuid>,
 ) -> Result<HttpResponse, AppError> {
     require_roles(&auth, &[UserRole::Voter, UserRole::Admin])?;
 
     let contest_id = path.into_inner();
-    let row = sqlx::query_as::<_, (Uuid, String, Option<String>, i32, String, String)>(
+    let row = sqlx::query_as::<
+        _,
+        (
+            Uuid,
+            String,
+            Option<String>,
+            i32,
+            String,
+            String,
+            String,
+            i32,
+            i32,
+            Option<i32>,
+            Option<i32>,
+            Option<String>,
+            Option<String>,
+            Option<i32>,
+            bool,
+            bool,
+        ),
+    >(
         r#"
         SELECT
           e.id,
@@ -415,7 +465,17 @@ async fn get_contest_ballot(
           e.description,
           c.max_selections,
           e.status,
-          c.title
+          c.title,
+          c.voting_method,
+          c.seats,
+          c.min_selections,
+          c.score_min,
+          c.score_max,
+          c.pass_threshold,
+          c.allocation_method,
+          c.total_points,
+          c.allow_blank,
+          c.allow_write_ins
         FROM contests c
         JOIN elections e ON e.id = c.election_id
         WHERE c.id = $1
@@ -427,6 +487,8 @@ async fn get_contest_ballot(
     .map_err(|_| AppError::Internal)?
     .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
 
+    ensure_ballot_visible(&auth, &row.4)?;
+
     if auth.role == UserRole::Voter {
         let eligible = sqlx::query_scalar::<_, i64>(
             "SELECT COUNT(*) FROM voter_rolls WHERE contest_id = $1 AND user_id = $2",
@@ -442,19 +504,35 @@ async fn get_contest_ballot(
         }
     }
 
-    let candidates = sqlx::query_as::<_, (Uuid, String, Option<String>)>(
-        "SELECT id, name, manifesto FROM candidates WHERE contest_id = $1 ORDER BY name ASC",
+    let (ballot_order, items) =
+        ballot_order::ballot_candidates(pool.get_ref(), contest_id, auth.user_id).await?;
+
+    let parties = sqlx::query_as::<_, (Uuid, String, Option<String>)>(
+        r#"
+        SELECT DISTINCT p.id, p.name, p.abbreviation
+        FROM parties p
+        JOIN candidates c ON c.party_id = p.id
+        WHERE c.contest_id = $1
+        ORDER BY p.name ASC
+        "#,
     )
     .bind(contest_id)
     .fetch_all(pool.get_ref())
     .await
     .map_err(|_| AppError::Internal)?;
 
-    let items: Vec<_> = candidates
+    let party_items: Vec<_> = parties
         .into_iter()
-        .map(|(id, name, manifesto)| serde_json::json!({ "id": id, "name": name, "manifesto": manifesto }))
+        .map(|(id, name, abbreviation)| {
+            serde_json::json!({ "id": id, "name": name, "abbreviation": abbreviation })
+        })
         .collect();
 
+    let responses: Option<Vec<&str>> = (row.6 == VotingMethod::Referendum.as_str())
+        .then(|| ReferendumResponse::ALL.iter().map(|r| r.as_str()).collect());
+    // Encrypted ballots bind their proofs to a nonce issued to the voter.
+    let proof_nonce = ballot_link::proof_nonce(&config.ballot_link_key, contest_id, auth.user_id);
+
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "data": {
             "contest_id": contest_id,
@@ -464,27 +542,262 @@ async fn get_contest_ballot(
             "contest_title": row.5,
             "status": row.4,
             "max_selections": row.3,
-            "candidates": items
+            "min_selections": row.8,
+            "voting_method": row.6,
+            "seats": row.7,
+            "score_min": row.9,
+            "score_max": row.10,
+            "pass_threshold": row.11,
+            "allocation_method": row.12,
+            "total_points": row.13,
+            "allow_blank": row.14,
+            "allow_write_ins": row.15,
+            "responses": responses,
+            "parties": party_items,
+            "ballot_order": ballot_order,
+            "candidates": items,
+            "proof_nonce": proof_nonce
+        }
+    })))
+}
+
+#[derive(sqlx::FromRow)]
+struct FullBallotContestRow {
+    id: Uuid,
+    title: String,
+    description: Option<String>,
+    metadata: serde_json::Value,
+    is_default: bool,
+    voting_method: String,
+    max_selections: i32,
+    min_selections: i32,
+    seats: i32,
+    score_min: Option<i32>,
+    score_max: Option<i32>,
+    pass_threshold: Option<String>,
+    allocation_method: Option<String>,
+    total_points: Option<i32>,
+    allow_blank: bool,
+    allow_write_ins: bool,
+    ballot_order: String,
+    receipt_id: Option<Uuid>,
+}
+
+#[derive(sqlx::FromRow)]
+struct FullBallotCandidateRow {
+    contest_id: Uuid,
+    #[sqlx(flatten)]
+    candidate: BallotCandidate,
+}
+
+// Every contest on the caller's roll in one response, built from a fixed number of queries
+// however many contests the election has.
+#[get("/elections/{id}/full-ballot")]
+async fn get_full_ballot(
+    pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Voter, UserRole::Admin])?;
+
+    let election_id = path.into_inner();
+    let now = chrono::Utc::now();
+
+    let election = sqlx::query_as::<
+        _,
+        (
+            String,
+            Option<String>,
+            String,
+            chrono::DateTime<chrono::Utc>,
+            chrono::DateTime<chrono::Utc>,
+            bool,
+        ),
+    >(
+        r#"
+        SELECT title, description, status, opens_at, closes_at, allow_revote
+        FROM elections
+        WHERE id = $1
+        "#,
+    )
+    .bind(election_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(|_| AppError::Internal)?
+    .ok_or_else(|| AppError::NotFound("election not found".to_string()))?;
+
+    ensure_ballot_visible(&auth, &election.2)?;
+    let is_open = election.2 == "published" && now >= election.3 && now <= election.4;
+
+    let contests = sqlx::query_as::<_, FullBallotContestRow>(
+        r#"
+        SELECT
+          c.id,
+          c.title,
+          c.description,
+          c.metadata,
+          c.is_default,
+          c.voting_method,
+          c.max_selections,
+          c.min_selections,
+          c.seats,
+          c.score_min,
+          c.score_max,
+          c.pass_threshold,
+          c.allocation_method,
+          c.total_points,
+          c.allow_blank,
+          c.allow_write_ins,
+          c.ballot_order,
+          r.id AS receipt_id
+        FROM voter_rolls vr
+        JOIN contests c ON c.id = vr.contest_id
+        LEFT JOIN vote_receipts r
+          ON r.contest_id = c.id AND r.voter_id = vr.user_id AND r.superseded_at IS NULL
+        WHERE vr.user_id = $2 AND c.election_id = $1
+        ORDER BY c.is_default DESC, c.created_at ASC
+        "#,
+    )
+    .bind(election_id)
+    .bind(auth.user_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|_| AppError::Internal)?;
+    let contest_ids: Vec<Uuid> = contests.iter().map(|c| c.id).collect();
+
+    let mut candidates: HashMap<Uuid, Vec<BallotCandidate>> = HashMap::new();
+    for row in sqlx::query_as::<_, FullBallotCandidateRow>(
+        r#"
+        SELECT contest_id, id, name, manifesto, party_id, ballot_position
+        FROM candidates
+        WHERE contest_id = ANY($1)
+        ORDER BY name ASC, id ASC
+        "#,
+    )
+    .bind(&contest_ids)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|_| AppError::Internal)?
+    {
+        candidates.entry(row.contest_id).or_default().push(row.candidate);
+    }
+
+    let mut parties: HashMap<Uuid, Vec<serde_json::Value>> = HashMap::new();
+    for (contest_id, id, name, abbreviation) in
+        sqlx::query_as::<_, (Uuid, Uuid, String, Option<String>)>(
+            r#"
+            SELECT DISTINCT c.contest_id, p.id, p.name, p.abbreviation
+            FROM parties p
+            JOIN candidates c ON c.party_id = p.id
+            WHERE c.contest_id = ANY($1)
+            ORDER BY c.contest_id, p.name ASC
+            "#,
+        )
+        .bind(&contest_ids)
+        .fetch_all(pool.get_ref())
+        .await
+        .map_err(|_| AppError::Internal)?
+    {
+        parties.entry(contest_id).or_default().push(
+            serde_json::json!({ "id": id, "name": name, "abbreviation": abbreviation }),
+        );
+    }
+
+    let mut items = Vec::with_capacity(contests.len());
+    for contest in contests {
+        let order = BallotOrder::from_db(&contest.ballot_order).ok_or(AppError::Internal)?;
+        let contest_candidates: Vec<_> = ballot_order::arrange(
+            order,
+            auth.user_id,
+            contest.id,
+            candidates.remove(&contest.id).unwrap_or_default(),
+        )
+        .into_iter()
+        .map(BallotCandidate::into_json)
+        .collect();
+        let is_referendum = contest.voting_method == VotingMethod::Referendum.as_str();
+        let responses: Option<Vec<&str>> =
+            is_referendum.then(|| ReferendumResponse::ALL.iter().map(|r| r.as_str()).collect());
+        let has_voted = contest.receipt_id.is_some();
+        let proof_nonce =
+            ballot_link::proof_nonce(&config.ballot_link_key, contest.id, auth.user_id);
+
+        items.push(serde_json::json!({
+            "id": contest.id,
+            "title": contest.title,
+            "description": contest.description,
+            "metadata": contest.metadata,
+            "is_default": contest.is_default,
+            "voting_method": contest.voting_method,
+            "max_selections": contest.max_selections,
+            "min_selections": contest.min_selections,
+            "seats": contest.seats,
+            "score_min": contest.score_min,
+            "score_max": contest.score_max,
+            "pass_threshold": contest.pass_threshold,
+            "allocation_method": contest.allocation_method,
+            "total_points": contest.total_points,
+            "allow_blank": contest.allow_blank,
+            "allow_write_ins": contest.allow_write_ins,
+            "responses": responses,
+            "parties": parties.remove(&contest.id).unwrap_or_default(),
+            "ballot_order": order,
+            "candidates": contest_candidates,
+            "proof_nonce": proof_nonce,
+            "has_voted": has_voted,
+            "receipt_id": contest.receipt_id,
+            "can_vote_now": is_open && (!has_voted || election.5)
+        }));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "data": {
+            "election": {
+                "id": election_id,
+                "title": election.0,
+                "description": election.1,
+                "status": election.2,
+                "opens_at": election.3,
+                "closes_at": election.4,
+                "allow_revote": election.5
+            },
+            "contests": items
         }
     })))
 }
 
+// Every endpoint that returns a receipt signs it here, so the signed fields are the ones shown.
+fn sign_receipt(config: &AppConfig, receipt: &mut VoteReceiptResponse) {
+    receipt.signature = Some(receipt_signing::sign(
+        &config.receipt_signing_key,
+        receipt.election_id,
+        receipt.receipt_id,
+        receipt.contest_id,
+        receipt.submitted_at,
+        receipt.ballot_commitment.as_deref(),
+    ));
+}
+
 #[post("/elections/{id}/vote")]
 async fn cast_vote(
     pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
     auth: AuthenticatedUser,
     path: web::Path<Uuid>,
     body: web::Json<CastVoteRequest>,
 ) -> Result<HttpResponse, AppError> {
     require_roles(&auth, &[UserRole::Voter, UserRole::Admin])?;
 
-    let vote_receipt = vote::cast(
+    let mut vote_receipt = vote::cast(
         pool.get_ref(),
+        &config.ballot_link_key,
         path.into_inner(),
         auth.user_id,
         body.into_inner(),
     )
     .await?;
+    sign_receipt(&config, &mut vote_receipt);
 
     Ok(HttpResponse::Created().json(serde_json::json!({ "data": vote_receipt })))
 }
@@ -492,22 +805,170 @@ async fn cast_vote(
 #[post("/contests/{id}/vote")]
 async fn cast_contest_vote(
     pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
     auth: AuthenticatedUser,
     path: web::Path<Uuid>,
     body: web::Json<CastVoteRequest>,
 ) -> Result<HttpResponse, AppError> {
     require_roles(&auth, &[UserRole::Voter, UserRole::Admin])?;
 
-    let vote_receipt =
-        vote::cast_contest(pool.get_ref(), path.into_inner(), auth.user_id, body.into_inner())
-            .await?;
+    let mut vote_receipt = vote::cast_contest(
+        pool.get_ref(),
+        &config.ballot_link_key,
+        path.into_inner(),
+        auth.user_id,
+        body.into_inner(),
+    )
+    .await?;
+    sign_receipt(&config, &mut vote_receipt);
+
+    Ok(HttpResponse::Created().json(serde_json::json!({ "data": vote_receipt })))
+}
+
+#[post("/contests/{id}/prepared-ballots")]
+async fn prepare_ballot(
+    pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<PrepareBallotRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Voter, UserRole::Admin])?;
+
+    let data = prepared_ballot::prepare(
+        pool.get_ref(),
+        &config.ballot_link_key,
+        path.into_inner(),
+        auth.user_id,
+        body.into_inner(),
+    )
+    .await?;
+
+    Ok(HttpResponse::Created().json(serde_json::json!({ "data": data })))
+}
+
+#[post("/contests/{id}/prepared-ballots/{prepared_ballot_id}/cast")]
+async fn cast_prepared_ballot(
+    pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
+    auth: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<CastPreparedBallotRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Voter, UserRole::Admin])?;
+
+    let (contest_id, prepared_ballot_id) = path.into_inner();
+    let mut vote_receipt = vote::cast_prepared(
+        pool.get_ref(),
+        &config.ballot_link_key,
+        contest_id,
+        auth.user_id,
+        prepared_ballot_id,
+        body.into_inner().idempotency_key,
+    )
+    .await?;
+    sign_receipt(&config, &mut vote_receipt);
 
     Ok(HttpResponse::Created().json(serde_json::json!({ "data": vote_receipt })))
 }
 
+#[post("/contests/{id}/prepared-ballots/{prepared_ballot_id}/challenge")]
+async fn challenge_prepared_ballot(
+    pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
+    auth: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Voter, UserRole::Admin])?;
+
+    let (contest_id, prepared_ballot_id) = path.into_inner();
+    let data = prepared_ballot::challenge(
+        pool.get_ref(),
+        &config.ballot_link_key,
+        contest_id,
+        auth.user_id,
+        prepared_ballot_id,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": data })))
+}
+
+#[get("/contests/{id}/spoiled-ballots")]
+async fn list_spoiled_ballots(
+    pool: web::Data<PgPool>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(
+        &auth,
+        &[
+            UserRole::Admin,
+            UserRole::ElectionOfficer,
+            UserRole::Auditor,
+            UserRole::Voter,
+        ],
+    )?;
+
+    let data = prepared_ballot::spoiled(pool.get_ref(), path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": data })))
+}
+
+#[post("/elections/{id}/ballots")]
+async fn submit_ballot(
+    pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<SubmitBallotRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Voter, UserRole::Admin])?;
+
+    let mut receipt = vote::cast_ballot(
+        pool.get_ref(),
+        &config.ballot_link_key,
+        path.into_inner(),
+        auth.user_id,
+        body.into_inner(),
+    )
+    .await?;
+    for contest_receipt in &mut receipt.contests {
+        sign_receipt(&config, contest_receipt);
+    }
+
+    Ok(HttpResponse::Created().json(serde_json::json!({ "data": receipt })))
+}
+
+#[get("/elections/{id}/ballots/{ballot_receipt_id}")]
+async fn get_ballot_receipt(
+    pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
+    auth: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(&auth, &[UserRole::Voter, UserRole::Admin])?;
+
+    let (election_id, ballot_receipt_id) = path.into_inner();
+    let mut receipt = vote::ballot_receipt(
+        pool.get_ref(),
+        &config.ballot_link_key,
+        election_id,
+        auth.user_id,
+        ballot_receipt_id,
+    )
+    .await?;
+    for contest_receipt in &mut receipt.contests {
+        sign_receipt(&config, contest_receipt);
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": receipt })))
+}
+
 #[get("/elections/{id}/receipt/{receipt_id}")]
 async fn get_receipt(
     pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
     auth: AuthenticatedUser,
     path: web::Path<(Uuid, Uuid)>,
 ) -> Result<HttpResponse, AppError> {
@@ -515,8 +976,25 @@ async fn get_receipt(
 
     let (election_id, receipt_id) = path.into_inner();
 
-    let row = sqlx::query_as::<_, (Uuid, Uuid, Uuid, chrono::DateTime<chrono::Utc>)>(
-        "SELECT id, election_id, contest_id, created_at FROM vote_receipts WHERE id = $1 AND election_id = $2 AND voter_id = $3",
+    let row = sqlx::query_as::<
+        _,
+        (
+            Uuid,
+            Uuid,
+            Uuid,
+            chrono::DateTime<chrono::Utc>,
+            Option<chrono::DateTime<chrono::Utc>>,
+            Option<String>,
+            Option<String>,
+        ),
+    >(
+        r#"
+        SELECT
+          id, election_id, contest_id, created_at, superseded_at, ballot_commitment,
+          sealed_commitment
+        FROM vote_receipts
+        WHERE id = $1 AND election_id = $2 AND voter_id = $3
+        "#,
     )
     .bind(receipt_id)
     .bind(election_id)
@@ -525,13 +1003,40 @@ async fn get_receipt(
     .await
     .map_err(|_| AppError::Internal)?
     .ok_or_else(|| AppError::NotFound("receipt not found".to_string()))?;
+    let ballot_commitment = bulletin::receipt_commitment(
+        &config.ballot_link_key,
+        row.0,
+        row.5,
+        row.6.as_deref(),
+    );
+
+    let signature = receipt_signing::sign(
+        &config.receipt_signing_key,
+        row.1,
+        row.0,
+        row.2,
+        row.3,
+        ballot_commitment.as_deref(),
+    );
+    let inclusion_proof = match ballot_commitment.as_deref() {
+        Some(commitment) => {
+            let mut conn = pool.acquire().await.map_err(|_| AppError::Internal)?;
+            bulletin::inclusion_proof(&mut conn, row.2, commitment).await?
+        }
+        None => None,
+    };
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "data": {
             "receipt_id": row.0,
             "election_id": row.1,
             "contest_id": row.2,
-            "submitted_at": row.3
+            "submitted_at": row.3,
+            "status": ReceiptStatus::from_superseded_at(row.4),
+            "superseded_at": row.4,
+            "ballot_commitment": ballot_commitment,
+            "inclusion_proof": inclusion_proof,
+            "signature": signature
         }
     })))
 }
@@ -539,6 +1044,7 @@ async fn get_receipt(
 #[get("/contests/{id}/receipt/{receipt_id}")]
 async fn get_contest_receipt(
     pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
     auth: AuthenticatedUser,
     path: web::Path<(Uuid, Uuid)>,
 ) -> Result<HttpResponse, AppError> {
@@ -546,8 +1052,25 @@ async fn get_contest_receipt(
 
     let (contest_id, receipt_id) = path.into_inner();
 
-    let row = sqlx::query_as::<_, (Uuid, Uuid, Uuid, chrono::DateTime<chrono::Utc>)>(
-        "SELECT id, election_id, contest_id, created_at FROM vote_receipts WHERE id = $1 AND contest_id = $2 AND voter_id = $3",
+    let row = sqlx::query_as::<
+        _,
+        (
+            Uuid,
+            Uuid,
+            Uuid,
+            chrono::DateTime<chrono::Utc>,
+            Option<chrono::DateTime<chrono::Utc>>,
+            Option<String>,
+            Option<String>,
+        ),
+    >(
+        r#"
+        SELECT
+          id, election_id, contest_id, created_at, superseded_at, ballot_commitment,
+          sealed_commitment
+        FROM vote_receipts
+        WHERE id = $1 AND contest_id = $2 AND voter_id = $3
+        "#,
     )
     .bind(receipt_id)
     .bind(contest_id)
@@ -556,13 +1079,74 @@ async fn get_contest_receipt(
     .await
     .map_err(|_| AppError::Internal)?
     .ok_or_else(|| AppError::NotFound("receipt not found".to_string()))?;
+    let ballot_commitment = bulletin::receipt_commitment(
+        &config.ballot_link_key,
+        row.0,
+        row.5,
+        row.6.as_deref(),
+    );
+
+    let signature = receipt_signing::sign(
+        &config.receipt_signing_key,
+        row.1,
+        row.0,
+        row.2,
+        row.3,
+        ballot_commitment.as_deref(),
+    );
+    let inclusion_proof = match ballot_commitment.as_deref() {
+        Some(commitment) => {
+            let mut conn = pool.acquire().await.map_err(|_| AppError::Internal)?;
+            bulletin::inclusion_proof(&mut conn, row.2, commitment).await?
+        }
+        None => None,
+    };
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "data": {
             "receipt_id": row.0,
             "election_id": row.1,
             "contest_id": row.2,
-            "submitted_at": row.3
+            "submitted_at": row.3,
+            "status": ReceiptStatus::from_superseded_at(row.4),
+            "superseded_at": row.4,
+            "ballot_commitment": ballot_commitment,
+            "inclusion_proof": inclusion_proof,
+            "signature": signature
+        }
+    })))
+}
+
+#[get("/elections/{id}/receipt-key")]
+async fn get_receipt_key(
+    pool: web::Data<PgPool>,
+    config: web::Data<AppConfig>,
+    auth: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    require_roles(
+        &auth,
+        &[
+            UserRole::Admin,
+            UserRole::ElectionOfficer,
+            UserRole::Auditor,
+            UserRole::Voter,
+        ],
+    )?;
+
+    let election_id = path.into_inner();
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM elections WHERE id = $1")
+        .bind(election_id)
+        .fetch_optional(pool.get_ref())
+        .await
+        .map_err(|_| AppError::Internal)?
+        .ok_or_else(|| AppError::NotFound("election not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "data": {
+            "election_id": election_id,
+            "algorithm": receipt_signing::ALGORITHM,
+            "public_key": receipt_signing::public_key(&config.receipt_signing_key, election_id)
         }
     })))
 }
@@ -573,8 +1157,16 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(list_my_contests_for_election)
         .service(get_ballot)
         .service(get_contest_ballot)
+        .service(get_full_ballot)
         .service(cast_vote)
         .service(cast_contest_vote)
+        .service(prepare_ballot)
+        .service(cast_prepared_ballot)
+        .service(challenge_prepared_ballot)
+        .service(list_spoiled_ballots)
+        .service(submit_ballot)
+        .service(get_ballot_receipt)
         .service(get_receipt)
-        .service(get_contest_receipt);
+        .service(get_contest_receipt)
+        .service(get_receipt_key);
 }
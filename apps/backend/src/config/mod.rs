@@ -6,6 +6,8 @@ pub struct AppConfig {
     pub port: u16,
     pub database_url: String,
     pub jwt_secret: String,
+    pub ballot_link_key: String,
+    pub receipt_signing_key: String,
     pub access_token_ttl_minutes: i64,
     pub refresh_token_ttl_days: i64,
     pub cors_allowed_origins: Vec<String>,
@@ -20,6 +22,9 @@ impl AppConfig {
             .unwrap_or(8080);
         let database_url = env::var("DATABASE_URL").expect("DATABASE_URL is required");
         let jwt_secret = env::var("JWT_SECRET").expect("JWT_SECRET is required");
+        let ballot_link_key = env::var("BALLOT_LINK_KEY").expect("BALLOT_LINK_KEY is required");
+        let receipt_signing_key =
+            env::var("RECEIPT_SIGNING_KEY").expect("RECEIPT_SIGNING_KEY is required");
         let access_token_ttl_minutes = env::var("ACCESS_TOKEN_TTL_MINUTES")
             .ok()
             .and_then(|v| v.parse().ok())
@@ -44,6 +49,8 @@ impl AppConfig {
             port,
             database_url,
             jwt_secret,
+            ballot_link_key,
+            receipt_signing_key,
             access_token_ttl_minutes,
             refresh_token_ttl_days,
             cors_allowed_origins,
@@ -32,6 +32,211 @@ impl UserRole {
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VotingMethod {
+    Plurality,
+    Ranked,
+    Schulze,
+    Score,
+    Approval,
+    Referendum,
+    PartyList,
+    Cumulative,
+    Quadratic,
+}
+
+impl VotingMethod {
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "plurality" => Some(Self::Plurality),
+            "ranked" => Some(Self::Ranked),
+            "schulze" => Some(Self::Schulze),
+            "score" => Some(Self::Score),
+            "approval" => Some(Self::Approval),
+            "referendum" => Some(Self::Referendum),
+            "party_list" => Some(Self::PartyList),
+            "cumulative" => Some(Self::Cumulative),
+            "quadratic" => Some(Self::Quadratic),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Plurality => "plurality",
+            Self::Ranked => "ranked",
+            Self::Schulze => "schulze",
+            Self::Score => "score",
+            Self::Approval => "approval",
+            Self::Referendum => "referendum",
+            Self::PartyList => "party_list",
+            Self::Cumulative => "cumulative",
+            Self::Quadratic => "quadratic",
+        }
+    }
+
+    pub fn is_ranked(&self) -> bool {
+        matches!(self, Self::Ranked | Self::Schulze)
+    }
+
+    pub fn uses_points(&self) -> bool {
+        matches!(self, Self::Cumulative | Self::Quadratic)
+    }
+
+    // Budget spent by a selection carrying `points` votes.
+    pub fn points_cost(&self, points: i32) -> i64 {
+        match self {
+            Self::Quadratic => points as i64 * points as i64,
+            _ => points as i64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PassThreshold {
+    SimpleMajority,
+    TwoThirds,
+    // Named `majority_of_eligible` before it became a turnout quorum.
+    #[serde(alias = "majority_of_eligible")]
+    QuorumOfEligible,
+}
+
+impl PassThreshold {
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "simple_majority" => Some(Self::SimpleMajority),
+            "two_thirds" => Some(Self::TwoThirds),
+            "quorum_of_eligible" => Some(Self::QuorumOfEligible),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SimpleMajority => "simple_majority",
+            Self::TwoThirds => "two_thirds",
+            Self::QuorumOfEligible => "quorum_of_eligible",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AllocationMethod {
+    Dhondt,
+    SainteLague,
+}
+
+impl AllocationMethod {
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "dhondt" => Some(Self::Dhondt),
+            "sainte_lague" => Some(Self::SainteLague),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dhondt => "dhondt",
+            Self::SainteLague => "sainte_lague",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreakPolicy {
+    Lot,
+    RegistrationOrder,
+    Manual,
+}
+
+impl TieBreakPolicy {
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "lot" => Some(Self::Lot),
+            "registration_order" => Some(Self::RegistrationOrder),
+            "manual" => Some(Self::Manual),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lot => "lot",
+            Self::RegistrationOrder => "registration_order",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BallotOrder {
+    Alphabetical,
+    Fixed,
+    Random,
+}
+
+impl BallotOrder {
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "alphabetical" => Some(Self::Alphabetical),
+            "fixed" => Some(Self::Fixed),
+            "random" => Some(Self::Random),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Alphabetical => "alphabetical",
+            Self::Fixed => "fixed",
+            Self::Random => "random",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptStatus {
+    Current,
+    Superseded,
+}
+
+impl ReceiptStatus {
+    pub fn from_superseded_at(superseded_at: Option<DateTime<Utc>>) -> Self {
+        if superseded_at.is_some() {
+            Self::Superseded
+        } else {
+            Self::Current
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferendumResponse {
+    Yes,
+    No,
+    Abstain,
+}
+
+impl ReferendumResponse {
+    pub const ALL: [Self; 3] = [Self::Yes, Self::No, Self::Abstain];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Yes => "yes",
+            Self::No => "no",
+            Self::Abstain => "abstain",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RegisterRequest {
     pub email: String,
@@ -69,6 +274,10 @@ pub struct CreateElectionRequest {
     pub description: Option<String>,
     pub opens_at: DateTime<Utc>,
     pub closes_at: DateTime<Utc>,
+    pub tie_break_policy: Option<TieBreakPolicy>,
+    pub tie_break_seed: Option<String>,
+    pub allow_revote: Option<bool>,
+    pub encrypted_ballots: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -77,6 +286,10 @@ pub struct UpdateElectionRequest {
     pub description: Option<String>,
     pub opens_at: DateTime<Utc>,
     pub closes_at: DateTime<Utc>,
+    pub tie_break_policy: Option<TieBreakPolicy>,
+    pub tie_break_seed: Option<String>,
+    pub allow_revote: Option<bool>,
+    pub encrypted_ballots: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -88,12 +301,28 @@ pub struct CreateOrganizationRequest {
 pub struct CreateCandidateRequest {
     pub name: String,
     pub manifesto: Option<String>,
+    pub party_id: Option<Uuid>,
+    pub ballot_position: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateCandidateRequest {
     pub name: String,
     pub manifesto: Option<String>,
+    pub party_id: Option<Uuid>,
+    pub ballot_position: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePartyRequest {
+    pub name: String,
+    pub abbreviation: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdatePartyRequest {
+    pub name: String,
+    pub abbreviation: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -101,6 +330,19 @@ pub struct CreateContestRequest {
     pub title: String,
     pub description: Option<String>,
     pub max_selections: Option<i32>,
+    pub min_selections: Option<i32>,
+    pub voting_method: Option<VotingMethod>,
+    pub seats: Option<i32>,
+    pub score_min: Option<i32>,
+    pub score_max: Option<i32>,
+    pub pass_threshold: Option<PassThreshold>,
+    pub allocation_method: Option<AllocationMethod>,
+    pub total_points: Option<i32>,
+    pub quorum_ballots: Option<i32>,
+    pub quorum_percent: Option<f64>,
+    pub allow_blank: Option<bool>,
+    pub allow_write_ins: Option<bool>,
+    pub ballot_order: Option<BallotOrder>,
     pub metadata: Option<serde_json::Value>,
 }
 
@@ -109,12 +351,41 @@ pub struct UpdateContestRequest {
     pub title: String,
     pub description: Option<String>,
     pub max_selections: i32,
+    pub min_selections: Option<i32>,
+    pub voting_method: Option<VotingMethod>,
+    pub seats: Option<i32>,
+    pub score_min: Option<i32>,
+    pub score_max: Option<i32>,
+    pub pass_threshold: Option<PassThreshold>,
+    pub allocation_method: Option<AllocationMethod>,
+    pub total_points: Option<i32>,
+    pub quorum_ballots: Option<i32>,
+    pub quorum_percent: Option<f64>,
+    pub allow_blank: Option<bool>,
+    pub allow_write_ins: Option<bool>,
+    pub ballot_order: Option<BallotOrder>,
     pub metadata: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateRunoffRequest {
+    pub title: Option<String>,
+    pub opens_at: DateTime<Utc>,
+    pub closes_at: DateTime<Utc>,
+    pub candidates: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdjudicateWriteInRequest {
+    pub name: String,
+    pub candidate_id: Option<Uuid>,
+    pub new_candidate_name: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AddVoterRollRequest {
     pub user_id: Uuid,
+    pub weight: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -126,13 +397,110 @@ pub struct ImportVoterRollRequest {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BallotOptionInput {
+    pub candidate_id: Option<Uuid>,
+    pub write_in: Option<String>,
+    pub score: Option<i32>,
+    pub points: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofInput {
+    pub challenge: String,
+    pub response: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CiphertextInput {
+    pub a: String,
+    pub b: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncryptedSelectionInput {
     pub candidate_id: Uuid,
+    pub ciphertext: CiphertextInput,
+    pub proof: Vec<ProofInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncryptedBallotInput {
+    pub proof_nonce: String,
+    pub selections: Vec<EncryptedSelectionInput>,
+    pub selection_count_proof: Vec<ProofInput>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CastVoteRequest {
     pub idempotency_key: String,
+    #[serde(default)]
     pub selections: Vec<BallotOptionInput>,
+    pub response: Option<ReferendumResponse>,
+    pub party_id: Option<Uuid>,
+    pub encrypted: Option<EncryptedBallotInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContestBallotInput {
+    pub contest_id: Uuid,
+    #[serde(default)]
+    pub selections: Vec<BallotOptionInput>,
+    pub response: Option<ReferendumResponse>,
+    pub party_id: Option<Uuid>,
+    pub encrypted: Option<EncryptedBallotInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmitBallotRequest {
+    pub idempotency_key: String,
+    pub contests: Vec<ContestBallotInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrepareBallotRequest {
+    #[serde(default)]
+    pub selections: Vec<BallotOptionInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CastPreparedBallotRequest {
+    pub idempotency_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTrusteesRequest {
+    pub user_ids: Vec<Uuid>,
+    pub threshold: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostKeyCommitmentsRequest {
+    pub transport_key: String,
+    pub commitments: Vec<String>,
+    pub proof: ProofInput,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrusteeShareInput {
+    pub recipient_index: i32,
+    pub encrypted_share: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostKeySharesRequest {
+    pub shares: Vec<TrusteeShareInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DecryptionShareInput {
+    pub contest_id: Uuid,
+    pub candidate_id: Uuid,
+    pub share: String,
+    pub proof: ProofInput,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmitDecryptionSharesRequest {
+    pub shares: Vec<DecryptionShareInput>,
 }
 
 #[derive(Debug, Serialize)]
@@ -141,6 +509,29 @@ pub struct VoteReceiptResponse {
     pub election_id: Uuid,
     pub contest_id: Uuid,
     pub submitted_at: DateTime<Utc>,
+    pub points_used: Option<i32>,
+    pub status: ReceiptStatus,
+    pub superseded_at: Option<DateTime<Utc>>,
+    pub ballot_commitment: Option<String>,
+    pub commitment_nonce: Option<String>,
+    pub inclusion_proof: Option<serde_json::Value>,
+    pub signature: Option<ReceiptSignature>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReceiptSignature {
+    pub algorithm: &'static str,
+    pub public_key: String,
+    pub message: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BallotReceiptResponse {
+    pub ballot_receipt_id: Uuid,
+    pub election_id: Uuid,
+    pub submitted_at: DateTime<Utc>,
+    pub contests: Vec<VoteReceiptResponse>,
 }
 
 #[derive(Debug, Serialize)]
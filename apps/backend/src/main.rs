@@ -7,6 +7,7 @@ mod middleware;
 mod security;
 mod services;
 mod state;
+mod tally;
 
 use actix_cors::Cors;
 use actix_web::{middleware::Logger, web, App, HttpServer};
@@ -0,0 +1,185 @@
+use chacha20poly1305::{
+    aead::{Aead, Payload},
+    ChaCha20Poly1305, Key, KeyInit, Nonce,
+};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use uuid::Uuid;
+
+const NONCE_LEN: usize = 12;
+const PROOF_NONCE_LEN: usize = 16;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn parse_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(value.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+// Ties an anonymous ballot to its receipt only for someone holding `key`, so a revote can find
+// and withdraw the ballot it replaces while database access alone cannot link voter and ballot.
+// A weighted ballot is stored in several parts, each tagged with its index; part 0 keeps the
+// original tag so ballots stored before weights were split can still be withdrawn.
+pub fn revote_tag(key: &str, receipt_id: Uuid, part: u32) -> String {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(receipt_id.as_bytes());
+    if part > 0 {
+        mac.update(&part.to_be_bytes());
+    }
+    hex(&mac.finalize().into_bytes())
+}
+
+// Splits a roll weight into distinct powers of two, smallest first. Each part is stored as a
+// ballot of its own, so a stored weight is one that every voter with that bit set shares rather
+// than the voter's exact roll weight.
+pub fn weight_parts(weight: i64) -> Vec<i64> {
+    (0..63)
+        .map(|bit| 1i64 << bit)
+        .filter(|part| weight & part != 0)
+        .collect()
+}
+
+fn proof_nonce_mac(key: &str, contest_id: Uuid, voter_id: Uuid, random: &[u8]) -> Hmac<Sha256> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(b"proof-nonce");
+    mac.update(contest_id.as_bytes());
+    mac.update(voter_id.as_bytes());
+    mac.update(random);
+    mac
+}
+
+// A fresh nonce for the proofs of one encrypted ballot: random bytes followed by a truncated
+// HMAC of the contest, the voter and those bytes. Only the voter it was issued to can cast proofs
+// made with it, and no two ballots share one, so nonces do not tie ballots to each other.
+pub fn proof_nonce(key: &str, contest_id: Uuid, voter_id: Uuid) -> String {
+    let mut random = [0u8; PROOF_NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut random);
+    let tag = proof_nonce_mac(key, contest_id, voter_id, &random).finalize().into_bytes();
+    hex(&[&random[..], &tag[..PROOF_NONCE_LEN]].concat())
+}
+
+pub fn check_proof_nonce(key: &str, contest_id: Uuid, voter_id: Uuid, nonce: &str) -> bool {
+    let Some(nonce) = parse_hex(nonce).filter(|nonce| nonce.len() == 2 * PROOF_NONCE_LEN) else {
+        return false;
+    };
+    let (random, tag) = nonce.split_at(PROOF_NONCE_LEN);
+    proof_nonce_mac(key, contest_id, voter_id, random)
+        .verify_truncated_left(tag)
+        .is_ok()
+}
+
+fn sealing_key(key: &str) -> ChaCha20Poly1305 {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(b"ballot-queue");
+    <ChaCha20Poly1305 as KeyInit>::new(Key::from_slice(&mac.finalize().into_bytes()))
+}
+
+// Encrypts a queued ballot (or a prepared ballot's randomness) under a key derived from `key`,
+// bound to its receipt (or prepared ballot id) so a sealed value cannot be moved to another row.
+// The result is the hex of nonce and ciphertext.
+pub fn seal(key: &str, receipt_id: Uuid, plaintext: &[u8]) -> String {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = sealing_key(key)
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: receipt_id.as_bytes(),
+            },
+        )
+        .expect("ChaCha20-Poly1305 encrypts messages of any practical length");
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    hex(&sealed)
+}
+
+pub fn open(key: &str, receipt_id: Uuid, sealed: &str) -> Option<Vec<u8>> {
+    let sealed = parse_hex(sealed)?;
+    if sealed.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    sealing_key(key)
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: receipt_id.as_bytes(),
+            },
+        )
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{check_proof_nonce, open, proof_nonce, revote_tag, seal, weight_parts};
+
+    #[test]
+    fn tag_depends_on_key_receipt_and_part() {
+        let receipt = Uuid::new_v4();
+
+        assert_eq!(revote_tag("key", receipt, 0), revote_tag("key", receipt, 0));
+        assert_ne!(revote_tag("key", receipt, 0), revote_tag("other-key", receipt, 0));
+        assert_ne!(revote_tag("key", receipt, 0), revote_tag("key", Uuid::new_v4(), 0));
+        assert_ne!(revote_tag("key", receipt, 0), revote_tag("key", receipt, 1));
+        assert_eq!(revote_tag("key", receipt, 0).len(), 64);
+    }
+
+    #[test]
+    fn weight_parts_are_distinct_powers_of_two_summing_to_the_weight() {
+        assert_eq!(weight_parts(1), vec![1]);
+        assert_eq!(weight_parts(6), vec![2, 4]);
+        assert_eq!(weight_parts(37), vec![1, 4, 32]);
+
+        for weight in [1, 2, 3, 255, 1_000_000, i64::MAX] {
+            let parts = weight_parts(weight);
+            assert_eq!(parts.iter().sum::<i64>(), weight);
+            assert!(parts.iter().all(|part| part.count_ones() == 1));
+        }
+    }
+
+    #[test]
+    fn proof_nonces_are_fresh_and_accepted_only_for_their_voter_and_contest() {
+        let (contest, voter) = (Uuid::new_v4(), Uuid::new_v4());
+        let nonce = proof_nonce("key", contest, voter);
+
+        assert_eq!(nonce.len(), 64);
+        assert_ne!(proof_nonce("key", contest, voter), nonce);
+        assert!(check_proof_nonce("key", contest, voter, &nonce));
+        assert!(!check_proof_nonce("key", contest, Uuid::new_v4(), &nonce));
+        assert!(!check_proof_nonce("key", Uuid::new_v4(), voter, &nonce));
+        assert!(!check_proof_nonce("other-key", contest, voter, &nonce));
+        assert!(!check_proof_nonce("key", contest, voter, &nonce[..62]));
+        assert!(!check_proof_nonce("key", contest, voter, "not hex"));
+    }
+
+    #[test]
+    fn sealed_ballots_open_only_with_their_key_and_receipt() {
+        let receipt = Uuid::new_v4();
+        let sealed = seal("key", receipt, b"ballot");
+
+        assert_eq!(open("key", receipt, &sealed).as_deref(), Some(&b"ballot"[..]));
+        assert_eq!(open("other-key", receipt, &sealed), None);
+        assert_eq!(open("key", Uuid::new_v4(), &sealed), None);
+        assert_ne!(seal("key", receipt, b"ballot"), sealed);
+
+        let mut tampered = sealed.clone();
+        tampered.replace_range(30..32, if &sealed[30..32] == "00" { "01" } else { "00" });
+        assert_eq!(open("key", receipt, &tampered), None);
+    }
+}
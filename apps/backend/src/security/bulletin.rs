@@ -0,0 +1,167 @@
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Left,
+    Right,
+}
+
+// One level of an inclusion path: the sibling hash and which side of the running hash it sits on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProofStep {
+    pub sibling: String,
+    pub side: Side,
+}
+
+pub fn generate_nonce() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+// SHA-256 of "{nonce}:{contest_id}:{ballot}". Without the nonce, which only the voter receives,
+// the commitment reveals nothing about the ballot.
+pub fn commitment(nonce: &str, contest_id: Uuid, ballot: &serde_json::Value) -> String {
+    format!(
+        "{:x}",
+        Sha256::digest(format!("{}:{}:{}", nonce, contest_id, ballot).as_bytes())
+    )
+}
+
+// Leaves and inner nodes are domain-separated (0x00 / 0x01 prefixes) so an inner node can never
+// be passed off as a leaf.
+pub fn leaf_hash(commitment: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0u8]);
+    hasher.update(commitment.as_bytes());
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([1u8]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn hex(hash: &[u8; 32]) -> String {
+    hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn parse_hex(value: &str) -> Option<[u8; 32]> {
+    if value.len() != 64 {
+        return None;
+    }
+    let mut hash = [0u8; 32];
+    for (index, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(value.get(index * 2..index * 2 + 2)?, 16).ok()?;
+    }
+    Some(hash)
+}
+
+// Hashes pairs level by level; an odd node at the end of a level is carried up unchanged rather
+// than paired with itself.
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+// The root of an empty board is SHA-256 of the empty string.
+pub fn merkle_root(commitments: &[String]) -> String {
+    if commitments.is_empty() {
+        return format!("{:x}", Sha256::digest(b""));
+    }
+    let mut level: Vec<[u8; 32]> = commitments.iter().map(|c| leaf_hash(c)).collect();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    hex(&level[0])
+}
+
+pub fn inclusion_proof(commitments: &[String], index: usize) -> Vec<ProofStep> {
+    let mut level: Vec<[u8; 32]> = commitments.iter().map(|c| leaf_hash(c)).collect();
+    let mut index = index;
+    let mut path = Vec::new();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            path.push(ProofStep {
+                sibling: hex(&level[sibling]),
+                side: if sibling < index { Side::Left } else { Side::Right },
+            });
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    path
+}
+
+pub fn verify_inclusion(commitment: &str, path: &[ProofStep], root: &str) -> bool {
+    let mut hash = leaf_hash(commitment);
+    for step in path {
+        let Some(sibling) = parse_hex(&step.sibling) else {
+            return false;
+        };
+        hash = match step.side {
+            Side::Left => node_hash(&sibling, &hash),
+            Side::Right => node_hash(&hash, &sibling),
+        };
+    }
+    hex(&hash) == root
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{commitment, inclusion_proof, merkle_root, verify_inclusion};
+
+    #[test]
+    fn every_commitment_has_a_valid_inclusion_proof() {
+        for size in 1..=9 {
+            let commitments: Vec<String> = (0..size).map(|i| format!("commitment-{i}")).collect();
+            let root = merkle_root(&commitments);
+
+            for (index, commitment) in commitments.iter().enumerate() {
+                let path = inclusion_proof(&commitments, index);
+                assert!(verify_inclusion(commitment, &path, &root), "size {size} index {index}");
+                assert!(!verify_inclusion("forged", &path, &root));
+            }
+        }
+    }
+
+    #[test]
+    fn root_changes_when_a_commitment_is_altered_or_dropped() {
+        let commitments: Vec<String> = (0..5).map(|i| format!("commitment-{i}")).collect();
+        let root = merkle_root(&commitments);
+
+        let mut altered = commitments.clone();
+        altered[3] = "commitment-x".to_string();
+        assert_ne!(merkle_root(&altered), root);
+        assert_ne!(merkle_root(&commitments[..4]), root);
+    }
+
+    #[test]
+    fn commitment_depends_on_nonce_and_ballot() {
+        let contest = Uuid::from_u128(1);
+        let ballot = serde_json::json!({ "selections": [{ "candidate_id": Uuid::from_u128(2) }] });
+        let other = serde_json::json!({ "selections": [{ "candidate_id": Uuid::from_u128(3) }] });
+
+        assert_eq!(commitment("n", contest, &ballot), commitment("n", contest, &ballot));
+        assert_ne!(commitment("n", contest, &ballot), commitment("m", contest, &ballot));
+        assert_ne!(commitment("n", contest, &ballot), commitment("n", contest, &other));
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT,
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::Identity,
+};
+use rand::RngCore;
+use sha2::{Digest, Sha512};
+
+// Exponential ElGamal over Ristretto255: a vote m encrypted under public key Y with randomness r
+// is (r·G, m·G + r·Y). Ciphertexts add component-wise, so summing ballots sums the votes.
+const MEMBERSHIP_TAG: &str = "vote-api/elgamal-membership/v1";
+const MAX_BABY_STEPS: u64 = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ciphertext {
+    pub a: RistrettoPoint,
+    pub b: RistrettoPoint,
+}
+
+impl Ciphertext {
+    pub fn zero() -> Self {
+        Self {
+            a: RistrettoPoint::identity(),
+            b: RistrettoPoint::identity(),
+        }
+    }
+
+    pub fn add(&self, other: &Ciphertext) -> Ciphertext {
+        Ciphertext {
+            a: self.a + other.a,
+            b: self.b + other.b,
+        }
+    }
+
+    // Multiplies the encrypted value by `factor`, used to apply voter weights.
+    pub fn scale(&self, factor: u64) -> Ciphertext {
+        let factor = Scalar::from(factor);
+        Ciphertext {
+            a: self.a * factor,
+            b: self.b * factor,
+        }
+    }
+}
+
+// One branch of a disjunctive (Cramer–Damgård–Schoenmakers) Chaum–Pedersen proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofBranch {
+    pub challenge: Scalar,
+    pub response: Scalar,
+}
+
+pub fn encode_point(point: &RistrettoPoint) -> String {
+    encode(point.compress().as_bytes())
+}
+
+pub fn decode_point(value: &str) -> Option<RistrettoPoint> {
+    CompressedRistretto(decode(value)?).decompress()
+}
+
+pub fn encode_scalar(scalar: &Scalar) -> String {
+    encode(scalar.as_bytes())
+}
+
+pub fn decode_scalar(value: &str) -> Option<Scalar> {
+    Option::from(Scalar::from_canonical_bytes(decode(value)?))
+}
+
+fn encode(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode(value: &str) -> Option<[u8; 32]> {
+    if value.len() != 64 {
+        return None;
+    }
+    let mut bytes = [0u8; 32];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(value.get(index * 2..index * 2 + 2)?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+// Fiat–Shamir challenge: SHA-512 over the tag and length-prefixed parts, reduced mod ℓ.
+pub fn challenge(tag: &str, parts: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update(tag.as_bytes());
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+}
+
+// Checks that `ciphertext` encrypts one of `allowed` under `public_key` without learning which.
+// Branch i proves (a, b - allowed[i]·G) is a Diffie–Hellman tuple for (G, Y), and the branch
+// challenges must add up to the hash of `context` and every branch commitment.
+pub fn verify_membership(
+    public_key: &RistrettoPoint,
+    ciphertext: &Ciphertext,
+    allowed: &[u64],
+    context: &[u8],
+    proof: &[ProofBranch],
+) -> bool {
+    if allowed.is_empty() || proof.len() != allowed.len() {
+        return false;
+    }
+
+    let mut commitments = Vec::with_capacity(allowed.len() * 2);
+    let mut total = Scalar::ZERO;
+    for (value, branch) in allowed.iter().zip(proof) {
+        let shifted = ciphertext.b - RISTRETTO_BASEPOINT_POINT * Scalar::from(*value);
+        commitments.push(
+            (RISTRETTO_BASEPOINT_POINT * branch.response - ciphertext.a * branch.challenge)
+                .compress(),
+        );
+        commitments.push((public_key * branch.response - shifted * branch.challenge).compress());
+        total += branch.challenge;
+    }
+
+    let statement = [
+        public_key.compress(),
+        ciphertext.a.compress(),
+        ciphertext.b.compress(),
+    ];
+    let mut parts: Vec<&[u8]> = vec![context];
+    parts.extend(statement.iter().map(|point| point.as_bytes().as_slice()));
+    parts.extend(commitments.iter().map(|point| point.as_bytes().as_slice()));
+
+    challenge(MEMBERSHIP_TAG, &parts) == total
+}
+
+pub fn random_scalar() -> Scalar {
+    let mut bytes = [0u8; 64];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+pub fn encrypt(public_key: &RistrettoPoint, value: u64, r: Scalar) -> Ciphertext {
+    Ciphertext {
+        a: RISTRETTO_BASEPOINT_POINT * r,
+        b: RISTRETTO_BASEPOINT_POINT * Scalar::from(value) + public_key * r,
+    }
+}
+
+// Proves `ciphertext` encrypts `value` (one of `allowed`) under randomness `r`: every other branch
+// is simulated, then the real branch is closed so the challenges add up.
+pub fn prove_membership(
+    public_key: &RistrettoPoint,
+    ciphertext: &Ciphertext,
+    value: u64,
+    r: Scalar,
+    allowed: &[u64],
+    context: &[u8],
+) -> Vec<ProofBranch> {
+    let w = random_scalar();
+    let mut branches = Vec::new();
+    let mut commitments = Vec::new();
+    for candidate in allowed {
+        if *candidate == value {
+            branches.push(None);
+            commitments.push((RISTRETTO_BASEPOINT_POINT * w).compress());
+            commitments.push((public_key * w).compress());
+        } else {
+            let branch = ProofBranch {
+                challenge: random_scalar(),
+                response: random_scalar(),
+            };
+            let shifted = ciphertext.b - RISTRETTO_BASEPOINT_POINT * Scalar::from(*candidate);
+            commitments.push(
+                (RISTRETTO_BASEPOINT_POINT * branch.response - ciphertext.a * branch.challenge)
+                    .compress(),
+            );
+            commitments
+                .push((public_key * branch.response - shifted * branch.challenge).compress());
+            branches.push(Some(branch));
+        }
+    }
+
+    let statement = [
+        public_key.compress(),
+        ciphertext.a.compress(),
+        ciphertext.b.compress(),
+    ];
+    let mut parts: Vec<&[u8]> = vec![context];
+    parts.extend(statement.iter().map(|point| point.as_bytes().as_slice()));
+    parts.extend(commitments.iter().map(|point| point.as_bytes().as_slice()));
+    let total = challenge(MEMBERSHIP_TAG, &parts);
+
+    let simulated: Scalar = branches.iter().flatten().map(|b| b.challenge).sum();
+    branches
+        .into_iter()
+        .map(|branch| {
+            branch.unwrap_or_else(|| {
+                let real = total - simulated;
+                ProofBranch {
+                    challenge: real,
+                    response: w + real * r,
+                }
+            })
+        })
+        .collect()
+}
+
+// Opens a ciphertext with the randomness it was made with, as a voter does when auditing a
+// challenged ballot: `a` must be r·G, and b - r·Y must be m·G for some m up to `bound`.
+pub fn open(
+    public_key: &RistrettoPoint,
+    ciphertext: &Ciphertext,
+    r: &Scalar,
+    bound: u64,
+) -> Option<u64> {
+    if ciphertext.a != RISTRETTO_BASEPOINT_POINT * r {
+        return None;
+    }
+    discrete_log(&(ciphertext.b - public_key * r), bound)
+}
+
+// Recovers m from m·G for m up to `bound` (tallies are bounded by the total voter weight) by
+// baby-step giant-step: about 2·sqrt(bound) point operations instead of `bound`. The baby-step
+// table is capped at `MAX_BABY_STEPS`; larger bounds take more giant steps instead.
+pub fn discrete_log(target: &RistrettoPoint, bound: u64) -> Option<u64> {
+    let baby_steps = (bound.isqrt() + 1).min(MAX_BABY_STEPS);
+    let mut table = HashMap::with_capacity(baby_steps as usize);
+    let mut giant_step = RistrettoPoint::identity();
+    for value in 0..baby_steps {
+        table.insert(giant_step.compress(), value);
+        giant_step += RISTRETTO_BASEPOINT_POINT;
+    }
+
+    let mut current = *target;
+    for giant in 0..=bound / baby_steps {
+        if let Some(baby) = table.get(&current.compress()) {
+            let value = giant * baby_steps + baby;
+            return (value <= bound).then_some(value);
+        }
+        current -= giant_step;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_POINT as G, scalar::Scalar};
+
+    use super::{
+        decode_point, discrete_log, encode_point, encrypt, open, prove_membership, random_scalar,
+        verify_membership, Ciphertext,
+    };
+
+    #[test]
+    fn membership_proofs_accept_allowed_values_only() {
+        let secret = random_scalar();
+        let public_key = G * secret;
+        let context = b"contest";
+
+        for value in [0, 1] {
+            let r = random_scalar();
+            let ciphertext = encrypt(&public_key, value, r);
+            let proof = prove_membership(&public_key, &ciphertext, value, r, &[0, 1], context);
+            assert!(verify_membership(&public_key, &ciphertext, &[0, 1], context, &proof));
+            assert!(!verify_membership(&public_key, &ciphertext, &[0, 1], b"other", &proof));
+        }
+
+        // A proof for 2 cannot be made to pass as a 0/1 proof.
+        let r = random_scalar();
+        let ciphertext = encrypt(&public_key, 2, r);
+        let proof = prove_membership(&public_key, &ciphertext, 2, r, &[0, 2], context);
+        assert!(verify_membership(&public_key, &ciphertext, &[0, 2], context, &proof));
+        assert!(!verify_membership(&public_key, &ciphertext, &[0, 1], context, &proof));
+        assert_eq!(open(&public_key, &ciphertext, &r, 2), Some(2));
+        assert_eq!(open(&public_key, &ciphertext, &random_scalar(), 2), None);
+    }
+
+    #[test]
+    fn ciphertexts_add_and_scale_homomorphically() {
+        let secret = random_scalar();
+        let public_key = G * secret;
+        let total = [(1, 3), (0, 5), (1, 2), (1, 1)]
+            .iter()
+            .map(|(vote, weight)| encrypt(&public_key, *vote, random_scalar()).scale(*weight))
+            .fold(Ciphertext::zero(), |sum, c| sum.add(&c));
+
+        let decrypted = total.b - total.a * secret;
+        assert_eq!(discrete_log(&decrypted, 11), Some(6));
+        assert_eq!(discrete_log(&decrypted, 5), None);
+        assert_eq!(decode_point(&encode_point(&total.a)), Some(total.a));
+    }
+
+    #[test]
+    fn discrete_log_finds_every_value_up_to_the_bound() {
+        for bound in [0, 1, 2, 15, 16, 17, 100] {
+            for value in 0..=bound + 3 {
+                let point = G * Scalar::from(value);
+                assert_eq!(discrete_log(&point, bound), (value <= bound).then_some(value));
+            }
+        }
+
+        let point = G * Scalar::from(12_345_678u64);
+        assert_eq!(discrete_log(&point, 20_000_000), Some(12_345_678));
+        assert_eq!(discrete_log(&point, 12_345_677), None);
+    }
+}
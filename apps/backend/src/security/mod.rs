@@ -1,2 +1,7 @@
+pub mod ballot_link;
+pub mod bulletin;
+pub mod elgamal;
 pub mod jwt;
 pub mod rate_limit;
+pub mod receipt_signing;
+pub mod trustee;
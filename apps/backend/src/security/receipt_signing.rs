@@ -0,0 +1,109 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use ed25519_dalek::{Signer, SigningKey};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::domain::ReceiptSignature;
+
+pub const ALGORITHM: &str = "ed25519";
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Each election signs with its own key, derived from the configured secret so that only the
+// secret has to be kept.
+fn election_key(key: &str, election_id: Uuid) -> SigningKey {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(b"receipt-signing:");
+    mac.update(election_id.as_bytes());
+    SigningKey::from_bytes(&mac.finalize().into_bytes().into())
+}
+
+pub fn public_key(key: &str, election_id: Uuid) -> String {
+    hex(election_key(key, election_id).verifying_key().as_bytes())
+}
+
+// The signed text is "vote-receipt-v1|{receipt_id}|{contest_id}|{submitted_at}|{commitment}",
+// with `submitted_at` in RFC 3339 UTC to the microsecond and an empty commitment for receipts
+// that predate the bulletin board.
+pub fn message(
+    receipt_id: Uuid,
+    contest_id: Uuid,
+    submitted_at: DateTime<Utc>,
+    ballot_commitment: Option<&str>,
+) -> String {
+    format!(
+        "vote-receipt-v1|{}|{}|{}|{}",
+        receipt_id,
+        contest_id,
+        submitted_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+        ballot_commitment.unwrap_or("")
+    )
+}
+
+pub fn sign(
+    key: &str,
+    election_id: Uuid,
+    receipt_id: Uuid,
+    contest_id: Uuid,
+    submitted_at: DateTime<Utc>,
+    ballot_commitment: Option<&str>,
+) -> ReceiptSignature {
+    let signing_key = election_key(key, election_id);
+    let message = message(receipt_id, contest_id, submitted_at, ballot_commitment);
+    let signature = signing_key.sign(message.as_bytes());
+
+    ReceiptSignature {
+        algorithm: ALGORITHM,
+        public_key: hex(signing_key.verifying_key().as_bytes()),
+        signature: hex(&signature.to_bytes()),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+    use uuid::Uuid;
+
+    use super::{public_key, sign};
+
+    fn decode(value: &str) -> Vec<u8> {
+        (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn signature_verifies_with_the_published_election_key() {
+        let election = Uuid::from_u128(1);
+        let submitted_at = Utc.timestamp_opt(1_700_000_000, 123_456_000).unwrap();
+        let signed = sign(
+            "secret",
+            election,
+            Uuid::from_u128(2),
+            Uuid::from_u128(3),
+            submitted_at,
+            Some("abc"),
+        );
+
+        assert_eq!(signed.public_key, public_key("secret", election));
+        assert_ne!(signed.public_key, public_key("secret", Uuid::from_u128(9)));
+        assert!(signed.message.ends_with("|2023-11-14T22:13:20.123456Z|abc"));
+
+        let key_bytes: [u8; 32] = decode(&signed.public_key).try_into().unwrap();
+        let signature_bytes: [u8; 64] = decode(&signed.signature).try_into().unwrap();
+        let verifying_key = VerifyingKey::from_bytes(&key_bytes).unwrap();
+        let signature = Signature::from_bytes(&signature_bytes);
+
+        assert!(verifying_key.verify(signed.message.as_bytes(), &signature).is_ok());
+        assert!(verifying_key
+            .verify(signed.message.replace("abc", "abd").as_bytes(), &signature)
+            .is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT, ristretto::RistrettoPoint, scalar::Scalar,
+    traits::Identity,
+};
+
+use crate::security::elgamal::{challenge, ProofBranch};
+
+// Threshold key generation follows Feldman's verifiable secret sharing run by every trustee:
+// trustee i picks a polynomial f_i of degree threshold - 1, publishes C_ik = a_ik·G and hands
+// f_i(j) to trustee j outside the API. The election key is Y = Σ C_i0 and trustee j's key share
+// s_j = Σ f_i(j) has the public counterpart Y_j = Σ_i Σ_k j^k·C_ik, so the server can check
+// decryption shares without ever holding a secret.
+const KEY_PROOF_TAG: &str = "vote-api/trustee-key/v1";
+const DECRYPTION_TAG: &str = "vote-api/decryption-share/v1";
+
+pub fn joint_public_key(commitments: &[Vec<RistrettoPoint>]) -> RistrettoPoint {
+    commitments
+        .iter()
+        .filter_map(|coefficients| coefficients.first())
+        .sum()
+}
+
+pub fn public_share(commitments: &[Vec<RistrettoPoint>], index: u64) -> RistrettoPoint {
+    let x = Scalar::from(index);
+    commitments
+        .iter()
+        .map(|coefficients| {
+            let mut power = Scalar::ONE;
+            let mut total = RistrettoPoint::identity();
+            for coefficient in coefficients {
+                total += coefficient * power;
+                power *= x;
+            }
+            total
+        })
+        .sum()
+}
+
+// Schnorr proof that the trustee knows the secret behind C_i0, bound to `context`.
+pub fn verify_key_proof(context: &[u8], commitment: &RistrettoPoint, proof: &ProofBranch) -> bool {
+    let t = RISTRETTO_BASEPOINT_POINT * proof.response - commitment * proof.challenge;
+    challenge(
+        KEY_PROOF_TAG,
+        &[
+            context,
+            commitment.compress().as_bytes(),
+            t.compress().as_bytes(),
+        ],
+    ) == proof.challenge
+}
+
+// Chaum–Pedersen proof that `share` = s_j·a for the same s_j behind `public_share` = s_j·G.
+pub fn verify_decryption_share(
+    context: &[u8],
+    public_share: &RistrettoPoint,
+    a: &RistrettoPoint,
+    share: &RistrettoPoint,
+    proof: &ProofBranch,
+) -> bool {
+    let t1 = RISTRETTO_BASEPOINT_POINT * proof.response - public_share * proof.challenge;
+    let t2 = a * proof.response - share * proof.challenge;
+    challenge(
+        DECRYPTION_TAG,
+        &[
+            context,
+            public_share.compress().as_bytes(),
+            a.compress().as_bytes(),
+            share.compress().as_bytes(),
+            t1.compress().as_bytes(),
+            t2.compress().as_bytes(),
+        ],
+    ) == proof.challenge
+}
+
+// Lagrange interpolation at zero over the trustee indices present: Σ λ_j·D_j = x·a for the
+// election secret x, whichever `threshold` trustees took part.
+pub fn combine(shares: &[(u64, RistrettoPoint)]) -> RistrettoPoint {
+    shares
+        .iter()
+        .map(|(index, share)| {
+            let lambda = shares
+                .iter()
+                .filter(|(other, _)| other != index)
+                .fold(Scalar::ONE, |lambda, (other, _)| {
+                    let other = Scalar::from(*other);
+                    lambda * other * (other - Scalar::from(*index)).invert()
+                });
+            share * lambda
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_POINT as G, scalar::Scalar};
+
+    use super::{
+        combine, joint_public_key, public_share, verify_decryption_share, verify_key_proof,
+        DECRYPTION_TAG, KEY_PROOF_TAG,
+    };
+    use crate::security::elgamal::{
+        challenge, discrete_log, encrypt, random_scalar, Ciphertext, ProofBranch,
+    };
+
+    fn evaluate(coefficients: &[Scalar], index: u64) -> Scalar {
+        let x = Scalar::from(index);
+        coefficients
+            .iter()
+            .rev()
+            .fold(Scalar::ZERO, |total, coefficient| total * x + coefficient)
+    }
+
+    #[test]
+    fn any_threshold_of_trustees_decrypts_the_tally() {
+        let (trustees, threshold) = (3u64, 2usize);
+        let polynomials: Vec<Vec<Scalar>> = (0..trustees)
+            .map(|_| (0..threshold).map(|_| random_scalar()).collect())
+            .collect();
+        let commitments: Vec<Vec<_>> = polynomials
+            .iter()
+            .map(|coefficients| coefficients.iter().map(|a| G * a).collect())
+            .collect();
+
+        // Key proof for trustee 1's constant term.
+        let w = random_scalar();
+        let c = challenge(
+            KEY_PROOF_TAG,
+            &[b"ctx", commitments[0][0].compress().as_bytes(), (G * w).compress().as_bytes()],
+        );
+        let proof = ProofBranch {
+            challenge: c,
+            response: w + c * polynomials[0][0],
+        };
+        assert!(verify_key_proof(b"ctx", &commitments[0][0], &proof));
+        assert!(!verify_key_proof(b"ctx", &commitments[1][0], &proof));
+
+        let public_key = joint_public_key(&commitments);
+        let tally = [1, 0, 1, 1, 0]
+            .iter()
+            .map(|vote| encrypt(&public_key, *vote, random_scalar()))
+            .fold(Ciphertext::zero(), |sum, c| sum.add(&c));
+
+        let shares: Vec<(u64, _)> = (1..=trustees)
+            .map(|index| {
+                let secret: Scalar = polynomials.iter().map(|f| evaluate(f, index)).sum();
+                let share = tally.a * secret;
+                let y = public_share(&commitments, index);
+                assert_eq!(y, G * secret);
+
+                let w = random_scalar();
+                let c = challenge(
+                    DECRYPTION_TAG,
+                    &[
+                        b"ctx",
+                        y.compress().as_bytes(),
+                        tally.a.compress().as_bytes(),
+                        share.compress().as_bytes(),
+                        (G * w).compress().as_bytes(),
+                        (tally.a * w).compress().as_bytes(),
+                    ],
+                );
+                let proof = ProofBranch {
+                    challenge: c,
+                    response: w + c * secret,
+                };
+                assert!(verify_decryption_share(b"ctx", &y, &tally.a, &share, &proof));
+                assert!(!verify_decryption_share(b"ctx", &y, &tally.a, &(share + G), &proof));
+                (index, share)
+            })
+            .collect();
+
+        for pair in [[0, 1], [0, 2], [1, 2]] {
+            let chosen: Vec<_> = pair.iter().map(|i| shares[*i]).collect();
+            let decrypted = tally.b - combine(&chosen);
+            assert_eq!(discrete_log(&decrypted, 5), Some(3));
+        }
+        assert_eq!(discrete_log(&(tally.b - combine(&shares[..1])), 5), None);
+    }
+}
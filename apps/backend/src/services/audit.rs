@@ -0,0 +1,418 @@
+use chrono::{DateTime, SecondsFormat, SubsecRound, Utc};
+use futures_util::TryStreamExt;
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+
+// Events are hashed over `HASH_VERSION`'s encoding; events written before it keep version 1.
+const HASH_VERSION: i16 = 2;
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct AuditEvent {
+    pub seq: i64,
+    pub id: Uuid,
+    pub event_type: String,
+    pub actor_id: Option<Uuid>,
+    pub election_id: Option<Uuid>,
+    pub metadata: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub prev_hash: Option<String>,
+    pub election_prev_hash: Option<String>,
+    pub hash: Option<String>,
+    pub hash_version: i16,
+}
+
+impl AuditEvent {
+    pub fn compute_hash(&self) -> String {
+        if self.hash_version == 1 {
+            return self.legacy_hash();
+        }
+
+        let metadata = canonical_json(&self.metadata);
+        let created_at = self.created_at.to_rfc3339_opts(SecondsFormat::Micros, true);
+        let fields: [&[u8]; 10] = [
+            &self.hash_version.to_be_bytes(),
+            &self.seq.to_be_bytes(),
+            self.id.as_bytes(),
+            self.event_type.as_bytes(),
+            &optional(self.actor_id.as_ref().map(|id| &id.as_bytes()[..])),
+            &optional(self.election_id.as_ref().map(|id| &id.as_bytes()[..])),
+            metadata.as_bytes(),
+            created_at.as_bytes(),
+            &optional(self.prev_hash.as_deref().map(str::as_bytes)),
+            &optional(self.election_prev_hash.as_deref().map(str::as_bytes)),
+        ];
+
+        let mut hasher = Sha256::new();
+        hasher.update(b"vote-api/audit-event");
+        for field in fields {
+            hasher.update((field.len() as u64).to_be_bytes());
+            hasher.update(field);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    // Version 1 hashed serde_json's rendering of the fields, which is only stable while
+    // serde_json keeps object keys sorted.
+    fn legacy_hash(&self) -> String {
+        let canonical = serde_json::json!({
+            "seq": self.seq,
+            "id": self.id,
+            "event_type": self.event_type,
+            "actor_id": self.actor_id,
+            "election_id": self.election_id,
+            "metadata": self.metadata,
+            "created_at": self.created_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+            "prev_hash": self.prev_hash,
+            "election_prev_hash": self.election_prev_hash
+        });
+        format!("{:x}", Sha256::digest(canonical.to_string().as_bytes()))
+    }
+}
+
+// A leading tag keeps an absent value apart from an empty one.
+fn optional(value: Option<&[u8]>) -> Vec<u8> {
+    match value {
+        Some(bytes) => [&[1u8][..], bytes].concat(),
+        None => vec![0],
+    }
+}
+
+// Compact JSON with object keys sorted by their bytes at every level, whatever order the value
+// holds them in.
+pub(crate) fn canonical_json(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let members: Vec<String> = keys
+                .into_iter()
+                .map(|key| {
+                    format!(
+                        "{}:{}",
+                        serde_json::Value::String(key.clone()),
+                        canonical_json(&map[key])
+                    )
+                })
+                .collect();
+            format!("{{{}}}", members.join(","))
+        }
+        serde_json::Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        scalar => scalar.to_string(),
+    }
+}
+
+// Appends an event inside the caller's transaction, linked to the current global and election
+// chain heads. Locking the single `audit_chain_head` row keeps concurrent writers from forking a
+// chain and is held until the caller commits; reads of `audit_events` are not blocked.
+pub async fn record(
+    tx: &mut Transaction<'_, Postgres>,
+    event_type: &str,
+    actor_id: Uuid,
+    election_id: Uuid,
+    metadata: serde_json::Value,
+) -> Result<(), AppError> {
+    let (head_seq, prev_hash) = sqlx::query_as::<_, (i64, Option<String>)>(
+        "SELECT seq, hash FROM audit_chain_head WHERE id FOR UPDATE",
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    let seq = head_seq + 1;
+
+    let election_prev_hash = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT hash FROM audit_events WHERE election_id = $1 ORDER BY seq DESC LIMIT 1",
+    )
+    .bind(election_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .flatten();
+
+    // Postgres keeps microseconds, so the hashed timestamp must not carry more.
+    let mut event = AuditEvent {
+        seq,
+        id: Uuid::new_v4(),
+        event_type: event_type.to_string(),
+        actor_id: Some(actor_id),
+        election_id: Some(election_id),
+        metadata,
+        created_at: Utc::now().trunc_subsecs(6),
+        prev_hash,
+        election_prev_hash,
+        hash: None,
+        hash_version: HASH_VERSION,
+    };
+    event.hash = Some(event.compute_hash());
+
+    sqlx::query(
+        r#"
+        INSERT INTO audit_events (
+          id, seq, event_type, actor_id, election_id, metadata, created_at, prev_hash,
+          election_prev_hash, hash, hash_version
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        "#,
+    )
+    .bind(event.id)
+    .bind(event.seq)
+    .bind(&event.event_type)
+    .bind(event.actor_id)
+    .bind(event.election_id)
+    .bind(&event.metadata)
+    .bind(event.created_at)
+    .bind(&event.prev_hash)
+    .bind(&event.election_prev_hash)
+    .bind(&event.hash)
+    .bind(event.hash_version)
+    .execute(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    sqlx::query("UPDATE audit_chain_head SET seq = $1, hash = $2 WHERE id")
+        .bind(event.seq)
+        .bind(&event.hash)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Chain {
+    Global,
+    Election,
+}
+
+// Checks events one at a time in `seq` order. Events from before the chain existed have no hash
+// and may only appear ahead of the first sealed event.
+#[derive(Debug)]
+pub struct ChainWalk {
+    chain: Chain,
+    head: Option<String>,
+    pub checked: i64,
+    pub unsealed: i64,
+}
+
+impl ChainWalk {
+    pub fn new(chain: Chain) -> Self {
+        Self {
+            chain,
+            head: None,
+            checked: 0,
+            unsealed: 0,
+        }
+    }
+
+    pub fn check(&mut self, event: &AuditEvent) -> Result<(), &'static str> {
+        let Some(hash) = event.hash.as_deref() else {
+            if self.checked > 0 {
+                return Err("missing_hash");
+            }
+            self.unsealed += 1;
+            return Ok(());
+        };
+
+        let previous = match self.chain {
+            Chain::Global => &event.prev_hash,
+            Chain::Election => &event.election_prev_hash,
+        };
+        if *previous != self.head {
+            return Err("previous_hash_mismatch");
+        }
+        if event.compute_hash() != hash {
+            return Err("hash_mismatch");
+        }
+
+        self.head = Some(hash.to_string());
+        self.checked += 1;
+        Ok(())
+    }
+}
+
+// Walks the global chain, or one election's chain, and reports the first event that does not
+// link to the one before it. An edited event fails its own hash; a deleted one breaks the link
+// of the event after it. Dropping the newest events is only visible against a published head.
+pub async fn verify(
+    pool: &PgPool,
+    election_id: Option<Uuid>,
+) -> Result<serde_json::Value, AppError> {
+    let chain = if election_id.is_some() {
+        Chain::Election
+    } else {
+        Chain::Global
+    };
+    let mut walk = ChainWalk::new(chain);
+    let mut first_broken = None;
+
+    let mut events = sqlx::query_as::<_, AuditEvent>(
+        r#"
+        SELECT seq, id, event_type, actor_id, election_id, metadata, created_at, prev_hash,
+               election_prev_hash, hash, hash_version
+        FROM audit_events
+        WHERE $1::uuid IS NULL OR election_id = $1
+        ORDER BY seq ASC
+        "#,
+    )
+    .bind(election_id)
+    .fetch(pool);
+
+    while let Some(event) = events.try_next().await.map_err(|_| AppError::Internal)? {
+        if let Err(reason) = walk.check(&event) {
+            first_broken = Some(serde_json::json!({
+                "seq": event.seq,
+                "event_id": event.id,
+                "event_type": event.event_type,
+                "reason": reason
+            }));
+            break;
+        }
+    }
+
+    Ok(serde_json::json!({
+        "scope": if chain == Chain::Global { "global" } else { "election" },
+        "election_id": election_id,
+        "valid": first_broken.is_none(),
+        "events_checked": walk.checked,
+        "unsealed_events": walk.unsealed,
+        "head_hash": walk.head,
+        "first_broken": first_broken
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    use super::{canonical_json, AuditEvent, Chain, ChainWalk, HASH_VERSION};
+
+    fn sealed_chain(elections: &[u128]) -> Vec<AuditEvent> {
+        let mut events: Vec<AuditEvent> = Vec::new();
+        for (index, election) in elections.iter().enumerate() {
+            let election_id = Uuid::from_u128(*election);
+            let mut event = AuditEvent {
+                seq: index as i64 + 1,
+                id: Uuid::from_u128(1000 + index as u128),
+                event_type: "vote_cast".to_string(),
+                actor_id: Some(Uuid::from_u128(7)),
+                election_id: Some(election_id),
+                metadata: serde_json::json!({ "contest_id": Uuid::from_u128(9), "n": index }),
+                created_at: Utc.timestamp_opt(1_700_000_000 + index as i64, 0).unwrap(),
+                prev_hash: events.last().and_then(|e| e.hash.clone()),
+                election_prev_hash: events
+                    .iter()
+                    .rev()
+                    .find(|e| e.election_id == Some(election_id))
+                    .and_then(|e| e.hash.clone()),
+                hash: None,
+                hash_version: HASH_VERSION,
+            };
+            event.hash = Some(event.compute_hash());
+            events.push(event);
+        }
+        events
+    }
+
+    fn walk<'a>(
+        chain: Chain,
+        events: impl IntoIterator<Item = &'a AuditEvent>,
+    ) -> Result<i64, (i64, &'static str)> {
+        let mut walk = ChainWalk::new(chain);
+        for event in events {
+            walk.check(event).map_err(|reason| (event.seq, reason))?;
+        }
+        Ok(walk.checked)
+    }
+
+    #[test]
+    fn intact_chains_verify_globally_and_per_election() {
+        let events = sealed_chain(&[1, 2, 1, 1, 2]);
+        let election = Some(Uuid::from_u128(2));
+
+        assert_eq!(walk(Chain::Global, &events), Ok(5));
+        assert_eq!(
+            walk(Chain::Election, events.iter().filter(|e| e.election_id == election)),
+            Ok(2)
+        );
+    }
+
+    #[test]
+    fn edited_and_deleted_events_break_the_chain() {
+        let mut events = sealed_chain(&[1, 1, 1, 1]);
+        events[1].metadata = serde_json::json!({ "contest_id": Uuid::from_u128(9), "n": 99 });
+        assert_eq!(walk(Chain::Global, &events), Err((2, "hash_mismatch")));
+
+        let mut events = sealed_chain(&[1, 1, 1, 1]);
+        events.remove(2);
+        assert_eq!(walk(Chain::Global, &events), Err((4, "previous_hash_mismatch")));
+        assert_eq!(walk(Chain::Election, &events), Err((4, "previous_hash_mismatch")));
+    }
+
+    #[test]
+    fn unsealed_events_are_only_allowed_before_the_chain_starts() {
+        let mut events = sealed_chain(&[1, 1, 1]);
+        events[0].hash = None;
+        events[1].prev_hash = None;
+        events[1].election_prev_hash = None;
+        events[1].hash = Some(events[1].compute_hash());
+        events[2].prev_hash = events[1].hash.clone();
+        events[2].election_prev_hash = events[1].hash.clone();
+        events[2].hash = Some(events[2].compute_hash());
+        assert_eq!(walk(Chain::Global, &events), Ok(2));
+
+        let mut events = sealed_chain(&[1, 1, 1]);
+        events[1].hash = None;
+        assert_eq!(walk(Chain::Global, &events), Err((2, "missing_hash")));
+    }
+
+    #[test]
+    fn metadata_is_encoded_with_sorted_keys_at_every_level() {
+        let metadata = serde_json::json!({
+            "b": 1,
+            "a": { "d": [2, { "y": null, "x": "é" }], "c": true }
+        });
+        assert_eq!(
+            canonical_json(&metadata),
+            r#"{"a":{"c":true,"d":[2,{"x":"é","y":null}]},"b":1}"#
+        );
+    }
+
+    #[test]
+    fn hashes_cover_every_field_and_the_version() {
+        let events = sealed_chain(&[1]);
+        let hash = events[0].compute_hash();
+
+        let mut event = sealed_chain(&[1]).remove(0);
+        event.actor_id = None;
+        assert_ne!(event.compute_hash(), hash);
+
+        let mut event = sealed_chain(&[1]).remove(0);
+        event.prev_hash = Some(String::new());
+        assert_ne!(event.compute_hash(), hash);
+
+        let mut event = sealed_chain(&[1]).remove(0);
+        event.hash_version = 1;
+        assert_ne!(event.compute_hash(), hash);
+    }
+
+    #[test]
+    fn version_one_events_still_verify() {
+        let mut events = sealed_chain(&[1, 1, 1]);
+        events[0].hash_version = 1;
+        events[0].hash = Some(events[0].compute_hash());
+        events[1].prev_hash = events[0].hash.clone();
+        events[1].election_prev_hash = events[0].hash.clone();
+        events[1].hash = Some(events[1].compute_hash());
+        events[2].prev_hash = events[1].hash.clone();
+        events[2].election_prev_hash = events[1].hash.clone();
+        events[2].hash = Some(events[2].compute_hash());
+        assert_eq!(walk(Chain::Global, &events), Ok(3));
+    }
+}
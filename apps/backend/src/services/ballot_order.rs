@@ -0,0 +1,138 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{domain::BallotOrder, errors::AppError, tally};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct BallotCandidate {
+    pub id: Uuid,
+    pub name: String,
+    pub manifesto: Option<String>,
+    pub party_id: Option<Uuid>,
+    pub ballot_position: Option<i32>,
+}
+
+impl BallotCandidate {
+    pub fn into_json(self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "manifesto": self.manifesto,
+            "party_id": self.party_id
+        })
+    }
+}
+
+// `candidates` arrive in alphabetical order. Random ballots reuse the lot draw with the voter id
+// as seed and the contest id as scope, so an auditor can rebuild any voter's ballot exactly.
+pub fn arrange(
+    order: BallotOrder,
+    voter_id: Uuid,
+    contest_id: Uuid,
+    mut candidates: Vec<BallotCandidate>,
+) -> Vec<BallotCandidate> {
+    match order {
+        BallotOrder::Alphabetical => {}
+        BallotOrder::Fixed => {
+            candidates.sort_by_key(|c| (c.ballot_position.is_none(), c.ballot_position));
+        }
+        BallotOrder::Random => {
+            let ids: Vec<Uuid> = candidates.iter().map(|c| c.id).collect();
+            let drawn = tally::lot::draw(&voter_id.to_string(), contest_id, &ids);
+            candidates.sort_by_key(|c| drawn.iter().position(|id| *id == c.id));
+        }
+    }
+    candidates
+}
+
+pub async fn ballot_candidates(
+    pool: &PgPool,
+    contest_id: Uuid,
+    voter_id: Uuid,
+) -> Result<(BallotOrder, Vec<serde_json::Value>), AppError> {
+    let order = sqlx::query_scalar::<_, String>("SELECT ballot_order FROM contests WHERE id = $1")
+        .bind(contest_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| AppError::Internal)?
+        .and_then(|order| BallotOrder::from_db(&order))
+        .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
+
+    let candidates = sqlx::query_as::<_, BallotCandidate>(
+        r#"
+        SELECT id, name, manifesto, party_id, ballot_position
+        FROM candidates
+        WHERE contest_id = $1
+        ORDER BY name ASC, id ASC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let items = arrange(order, voter_id, contest_id, candidates)
+        .into_iter()
+        .map(BallotCandidate::into_json)
+        .collect();
+
+    Ok((order, items))
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{arrange, BallotCandidate};
+    use crate::domain::BallotOrder;
+
+    fn candidates(positions: &[Option<i32>]) -> Vec<BallotCandidate> {
+        positions
+            .iter()
+            .enumerate()
+            .map(|(i, position)| BallotCandidate {
+                id: Uuid::from_u128(i as u128 + 1),
+                name: format!("candidate {i}"),
+                manifesto: None,
+                party_id: None,
+                ballot_position: *position,
+            })
+            .collect()
+    }
+
+    fn ids(arranged: &[BallotCandidate]) -> Vec<u128> {
+        arranged.iter().map(|c| c.id.as_u128()).collect()
+    }
+
+    #[test]
+    fn fixed_order_follows_ballot_position_with_unpositioned_last() {
+        let arranged = arrange(
+            BallotOrder::Fixed,
+            Uuid::nil(),
+            Uuid::nil(),
+            candidates(&[None, Some(3), Some(1), None, Some(2)]),
+        );
+
+        assert_eq!(ids(&arranged), vec![3, 5, 2, 1, 4]);
+    }
+
+    #[test]
+    fn random_order_is_reproducible_per_voter() {
+        let contest = Uuid::from_u128(100);
+        let voter = Uuid::from_u128(200);
+        let positions = [None; 8];
+
+        let first = ids(&arrange(BallotOrder::Random, voter, contest, candidates(&positions)));
+        let again = ids(&arrange(BallotOrder::Random, voter, contest, candidates(&positions)));
+        let other = ids(&arrange(
+            BallotOrder::Random,
+            Uuid::from_u128(201),
+            contest,
+            candidates(&positions),
+        ));
+
+        assert_eq!(first, again);
+        assert_ne!(first, other);
+        assert_eq!(first.len(), 8);
+    }
+}
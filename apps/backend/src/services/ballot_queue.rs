@@ -0,0 +1,374 @@
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    security::{ballot_link, elgamal},
+    services::{bulletin, encrypted_tally::VerifiedSelection, write_in},
+};
+
+// How many ballots of a contest wait in the queue before they are moved into `ballots` together.
+// Whatever is left when the election closes is moved then, however few.
+const BATCH_SIZE: usize = 10;
+
+// Revote tags are tried for every part a weight can be split into.
+const MAX_PARTS: u32 = 63;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct QueuedMark {
+    pub candidate_id: Option<Uuid>,
+    pub write_in: Option<String>,
+    pub rank: Option<i32>,
+    pub score: Option<i32>,
+    pub points: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct QueuedCiphertext {
+    pub candidate_id: Uuid,
+    pub a: String,
+    pub b: String,
+    pub proof: serde_json::Value,
+}
+
+impl From<&VerifiedSelection> for QueuedCiphertext {
+    fn from(selection: &VerifiedSelection) -> Self {
+        Self {
+            candidate_id: selection.candidate_id,
+            a: elgamal::encode_point(&selection.ciphertext.a),
+            b: elgamal::encode_point(&selection.ciphertext.b),
+            proof: selection.proof.clone(),
+        }
+    }
+}
+
+// Everything needed to store a ballot once it leaves the queue.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct QueuedBallot {
+    pub election_id: Uuid,
+    pub weight: i64,
+    pub allow_revote: bool,
+    pub points_used: Option<i32>,
+    pub blank: bool,
+    pub response: Option<String>,
+    pub party_id: Option<Uuid>,
+    pub marks: Vec<QueuedMark>,
+    pub selection_count_proof: Option<serde_json::Value>,
+    // Ballots queued before encrypted proofs carried a nonce have none.
+    #[serde(default)]
+    pub proof_nonce: Option<String>,
+    pub ciphertexts: Vec<QueuedCiphertext>,
+    // Ballots queued before commitments moved onto ballots posted theirs when they were cast.
+    #[serde(default)]
+    pub commitment: Option<String>,
+}
+
+// Queues a ballot inside the transaction that writes its receipt. The row is linked to the
+// receipt, but its content is sealed under a key derived from BALLOT_LINK_KEY.
+pub(crate) async fn enqueue(
+    tx: &mut Transaction<'_, Postgres>,
+    link_key: &str,
+    contest_id: Uuid,
+    receipt_id: Uuid,
+    ballot: &QueuedBallot,
+) -> Result<(), AppError> {
+    let plaintext = serde_json::to_vec(ballot).map_err(|_| AppError::Internal)?;
+    sqlx::query(
+        "INSERT INTO pending_ballots (id, contest_id, receipt_id, sealed) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(contest_id)
+    .bind(receipt_id)
+    .bind(ballot_link::seal(link_key, receipt_id, &plaintext))
+    .execute(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    Ok(())
+}
+
+// Moves the contest's queued ballots into `ballots` once a full batch is waiting. Runs in a
+// transaction of its own, after the receipts that queued them have committed.
+pub async fn flush(pool: &PgPool, link_key: &str, contest_id: Uuid) -> Result<(), AppError> {
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+    move_batch(&mut tx, link_key, contest_id, BATCH_SIZE).await?;
+    tx.commit().await.map_err(|_| AppError::Internal)
+}
+
+// Moves every queued ballot of the election. Called when the election closes.
+pub async fn flush_election(
+    tx: &mut Transaction<'_, Postgres>,
+    link_key: &str,
+    election_id: Uuid,
+) -> Result<(), AppError> {
+    let contest_ids =
+        sqlx::query_scalar::<_, Uuid>("SELECT id FROM contests WHERE election_id = $1")
+            .bind(election_id)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(|_| AppError::Internal)?;
+
+    for contest_id in contest_ids {
+        move_batch(tx, link_key, contest_id, 1).await?;
+    }
+    Ok(())
+}
+
+// Ballots of one batch are written in random order and all share this transaction's id, so
+// neither row order nor `xmin` points back at a receipt within the batch.
+async fn move_batch(
+    tx: &mut Transaction<'_, Postgres>,
+    link_key: &str,
+    contest_id: Uuid,
+    min_size: usize,
+) -> Result<(), AppError> {
+    let mut rows = sqlx::query_as::<_, (Uuid, Uuid, String)>(
+        r#"
+        SELECT id, receipt_id, sealed
+        FROM pending_ballots
+        WHERE contest_id = $1
+        FOR UPDATE SKIP LOCKED
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if rows.is_empty() || rows.len() < min_size {
+        return Ok(());
+    }
+    rows.shuffle(&mut rand::thread_rng());
+
+    for (_, receipt_id, sealed) in &rows {
+        let ballot: QueuedBallot = ballot_link::open(link_key, *receipt_id, sealed)
+            .and_then(|plaintext| serde_json::from_slice(&plaintext).ok())
+            .ok_or(AppError::Internal)?;
+        store(tx, link_key, contest_id, *receipt_id, &ballot).await?;
+    }
+
+    let ids: Vec<Uuid> = rows.iter().map(|(id, _, _)| *id).collect();
+    sqlx::query("DELETE FROM pending_ballots WHERE id = ANY($1)")
+        .bind(&ids)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    Ok(())
+}
+
+// Withdraws the ballot cast under `receipt_id`, whether it is still queued or already stored.
+// Returns false if there was nothing to withdraw.
+pub(crate) async fn withdraw(
+    tx: &mut Transaction<'_, Postgres>,
+    link_key: &str,
+    receipt_id: Uuid,
+) -> Result<bool, AppError> {
+    let queued = sqlx::query("DELETE FROM pending_ballots WHERE receipt_id = $1")
+        .bind(receipt_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?
+        .rows_affected();
+    if queued > 0 {
+        return Ok(true);
+    }
+
+    let tags: Vec<String> = (0..MAX_PARTS)
+        .map(|part| ballot_link::revote_tag(link_key, receipt_id, part))
+        .collect();
+    let stored = sqlx::query("DELETE FROM ballots WHERE revote_tag = ANY($1)")
+        .bind(&tags)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?
+        .rows_affected();
+    Ok(stored > 0)
+}
+
+// Stores one ballot per weight part and posts its commitment to the board. Encrypted ballots stay
+// whole: their marks are ciphertexts that are only ever decrypted in aggregate, and copies would
+// repeat the same ciphertexts. The commitment is kept on the first part only, so it counts each
+// ballot once without tying the other parts to it.
+async fn store(
+    tx: &mut Transaction<'_, Postgres>,
+    link_key: &str,
+    contest_id: Uuid,
+    receipt_id: Uuid,
+    ballot: &QueuedBallot,
+) -> Result<(), AppError> {
+    let parts = if ballot.selection_count_proof.is_some() {
+        vec![ballot.weight]
+    } else {
+        ballot_link::weight_parts(ballot.weight)
+    };
+
+    for (part, weight) in parts.into_iter().enumerate() {
+        let ballot_id = Uuid::new_v4();
+        let revote_tag = ballot
+            .allow_revote
+            .then(|| ballot_link::revote_tag(link_key, receipt_id, part as u32));
+        let commitment = ballot.commitment.as_deref().filter(|_| part == 0);
+        sqlx::query(
+            r#"
+            INSERT INTO ballots (
+              id, election_id, contest_id, points_used, revote_tag, selection_count_proof,
+              proof_nonce, commitment
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(ballot_id)
+        .bind(ballot.election_id)
+        .bind(contest_id)
+        .bind(ballot.points_used)
+        .bind(revote_tag)
+        .bind(&ballot.selection_count_proof)
+        .bind(&ballot.proof_nonce)
+        .bind(commitment)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+
+        store_marks(tx, ballot_id, contest_id, ballot, weight).await?;
+    }
+
+    if let Some(commitment) = &ballot.commitment {
+        bulletin::post(tx, contest_id, commitment).await?;
+    }
+    Ok(())
+}
+
+async fn store_marks(
+    tx: &mut Transaction<'_, Postgres>,
+    ballot_id: Uuid,
+    contest_id: Uuid,
+    ballot: &QueuedBallot,
+    weight: i64,
+) -> Result<(), AppError> {
+    let election_id = ballot.election_id;
+
+    if ballot.blank {
+        sqlx::query(
+            r#"
+            INSERT INTO votes (id, ballot_id, election_id, contest_id, is_blank, weight)
+            VALUES ($1, $2, $3, $4, true, $5)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(ballot_id)
+        .bind(election_id)
+        .bind(contest_id)
+        .bind(weight)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    }
+
+    if let Some(response) = &ballot.response {
+        sqlx::query(
+            r#"
+            INSERT INTO votes (id, ballot_id, election_id, contest_id, response, weight)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(ballot_id)
+        .bind(election_id)
+        .bind(contest_id)
+        .bind(response)
+        .bind(weight)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    }
+
+    if let Some(party_id) = ballot.party_id {
+        sqlx::query(
+            r#"
+            INSERT INTO votes (id, ballot_id, election_id, contest_id, party_id, weight)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(ballot_id)
+        .bind(election_id)
+        .bind(contest_id)
+        .bind(party_id)
+        .bind(weight)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    }
+
+    for ciphertext in &ballot.ciphertexts {
+        sqlx::query(
+            r#"
+            INSERT INTO encrypted_votes (
+              id, ballot_id, contest_id, candidate_id, ciphertext_a, ciphertext_b, proof, weight
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(ballot_id)
+        .bind(contest_id)
+        .bind(ciphertext.candidate_id)
+        .bind(&ciphertext.a)
+        .bind(&ciphertext.b)
+        .bind(&ciphertext.proof)
+        .bind(weight)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    }
+
+    for mark in &ballot.marks {
+        if let Some(name) = mark.write_in.as_deref() {
+            sqlx::query(
+                r#"
+                INSERT INTO write_ins (
+                  id, ballot_id, election_id, contest_id, name, normalized_name, rank, score,
+                  weight, points
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(ballot_id)
+            .bind(election_id)
+            .bind(contest_id)
+            .bind(name.trim())
+            .bind(write_in::normalize(name))
+            .bind(mark.rank)
+            .bind(mark.score)
+            .bind(weight)
+            .bind(mark.points)
+            .execute(&mut **tx)
+            .await
+            .map_err(|_| AppError::Internal)?;
+            continue;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO votes (
+              id, ballot_id, election_id, contest_id, candidate_id, rank, score, weight, points
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(ballot_id)
+        .bind(election_id)
+        .bind(contest_id)
+        .bind(mark.candidate_id)
+        .bind(mark.rank)
+        .bind(mark.score)
+        .bind(weight)
+        .bind(mark.points)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    }
+    Ok(())
+}
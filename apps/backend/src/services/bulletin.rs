@@ -0,0 +1,248 @@
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::CastVoteRequest,
+    errors::AppError,
+    security::{ballot_link, bulletin},
+    services::audit,
+};
+
+// Commits to the ballot as submitted. The nonce goes back to the voter only, so neither the
+// receipt nor the public board reveals what the commitment is over.
+pub fn commit(contest_id: Uuid, input: &CastVoteRequest) -> (String, String) {
+    let nonce = bulletin::generate_nonce();
+    let mut ballot = serde_json::json!({
+        "selections": input.selections,
+        "response": input.response,
+        "party_id": input.party_id
+    });
+    if let Some(encrypted) = &input.encrypted {
+        ballot["encrypted"] = serde_json::json!(encrypted);
+    }
+    let commitment = bulletin::commitment(&nonce, contest_id, &ballot);
+    (nonce, commitment)
+}
+
+// Receipts keep their commitment sealed to their own id, so it cannot be matched against the
+// commitment stored with the ballot.
+pub(crate) fn seal_commitment(link_key: &str, receipt_id: Uuid, commitment: &str) -> String {
+    ballot_link::seal(link_key, receipt_id, commitment.as_bytes())
+}
+
+// The commitment a receipt was cast with. Receipts cast before it was sealed keep it in
+// plaintext.
+pub(crate) fn receipt_commitment(
+    link_key: &str,
+    receipt_id: Uuid,
+    plaintext: Option<String>,
+    sealed: Option<&str>,
+) -> Option<String> {
+    plaintext.or_else(|| {
+        ballot_link::open(link_key, receipt_id, sealed?)
+            .and_then(|commitment| String::from_utf8(commitment).ok())
+    })
+}
+
+// Appends a commitment to the contest's board inside the caller's transaction, which stores the
+// ballot it commits to. The per-contest advisory lock hands out positions in commit order
+// without holding up other contests' boards.
+pub async fn post(
+    tx: &mut Transaction<'_, Postgres>,
+    contest_id: Uuid,
+    commitment: &str,
+) -> Result<i64, AppError> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1::text, 0))")
+        .bind(format!("bulletin:{}", contest_id))
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+
+    sqlx::query_scalar::<_, i64>(
+        r#"
+        INSERT INTO bulletin_entries (contest_id, position, commitment)
+        SELECT $1, COALESCE(MAX(position), 0) + 1, $2
+        FROM bulletin_entries
+        WHERE contest_id = $1
+        RETURNING position
+        "#,
+    )
+    .bind(contest_id)
+    .bind(commitment)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)
+}
+
+// The Merkle leaves of a contest: its counted commitments in posting order. An entry is counted
+// while the ballot it was posted for is stored; entries of ballots cast before ballots carried
+// their commitment are counted while their receipt is current. Commitments of ballots withdrawn
+// by a revote stay on the board but are not leaves.
+async fn commitments(conn: &mut PgConnection, contest_id: Uuid) -> Result<Vec<String>, AppError> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT b.commitment
+        FROM bulletin_entries b
+        WHERE b.contest_id = $1
+          AND (
+            EXISTS (SELECT 1 FROM ballots x WHERE x.commitment = b.commitment)
+            OR EXISTS (
+              SELECT 1 FROM vote_receipts r
+              WHERE r.ballot_commitment = b.commitment AND r.superseded_at IS NULL
+            )
+          )
+        ORDER BY b.position ASC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(conn)
+    .await
+    .map_err(|_| AppError::Internal)
+}
+
+// Freezes the Merkle root of every contest board in the election and anchors the roots in the
+// audit chain. Called when the election closes.
+pub async fn snapshot(
+    tx: &mut Transaction<'_, Postgres>,
+    election_id: Uuid,
+    actor_id: Uuid,
+) -> Result<(), AppError> {
+    let contest_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM contests WHERE election_id = $1 ORDER BY is_default DESC, created_at ASC",
+    )
+    .bind(election_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let mut roots = Vec::with_capacity(contest_ids.len());
+    for contest_id in contest_ids {
+        let commitments = commitments(tx, contest_id).await?;
+        let merkle_root = bulletin::merkle_root(&commitments);
+        sqlx::query(
+            r#"
+            INSERT INTO bulletin_snapshots (contest_id, merkle_root, leaf_count)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (contest_id) DO NOTHING
+            "#,
+        )
+        .bind(contest_id)
+        .bind(&merkle_root)
+        .bind(commitments.len() as i64)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+
+        roots.push(serde_json::json!({
+            "contest_id": contest_id,
+            "merkle_root": merkle_root,
+            "leaf_count": commitments.len()
+        }));
+    }
+
+    audit::record(
+        tx,
+        "bulletin_snapshot",
+        actor_id,
+        election_id,
+        serde_json::json!({ "contests": roots }),
+    )
+    .await
+}
+
+// The published board: every commitment in posting order, whether a revote superseded it, and
+// the closing snapshot once there is one. Superseded commitments are not part of the snapshot.
+pub async fn board(pool: &PgPool, contest_id: Uuid) -> Result<serde_json::Value, AppError> {
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM contests WHERE id = $1")
+        .bind(contest_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| AppError::Internal)?
+        .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
+
+    let entries = sqlx::query_as::<_, (i64, String, bool)>(
+        r#"
+        SELECT b.position, b.commitment, NOT (
+          EXISTS (SELECT 1 FROM ballots x WHERE x.commitment = b.commitment)
+          OR EXISTS (
+            SELECT 1 FROM vote_receipts r
+            WHERE r.ballot_commitment = b.commitment AND r.superseded_at IS NULL
+          )
+        )
+        FROM bulletin_entries b
+        WHERE b.contest_id = $1
+        ORDER BY b.position ASC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let snapshot = sqlx::query_as::<_, (String, i64, chrono::DateTime<chrono::Utc>)>(
+        "SELECT merkle_root, leaf_count, created_at FROM bulletin_snapshots WHERE contest_id = $1",
+    )
+    .bind(contest_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok(serde_json::json!({
+        "contest_id": contest_id,
+        "entries": entries
+            .into_iter()
+            .map(|(position, commitment, superseded)| {
+                serde_json::json!({
+                    "position": position,
+                    "commitment": commitment,
+                    "superseded": superseded
+                })
+            })
+            .collect::<Vec<_>>(),
+        "snapshot": snapshot.map(|(merkle_root, leaf_count, created_at)| {
+            serde_json::json!({
+                "merkle_root": merkle_root,
+                "leaf_count": leaf_count,
+                "created_at": created_at
+            })
+        })
+    }))
+}
+
+// The path from `commitment` to the contest's snapshot root, or `None` before the snapshot is
+// taken. The path is built from the board as stored now, so `verified` is false if any entry
+// changed since close, and a commitment that is not a leaf, because it is missing from the board
+// or was superseded, is reported with `included: false`. `position` counts leaves only.
+pub async fn inclusion_proof(
+    conn: &mut PgConnection,
+    contest_id: Uuid,
+    commitment: &str,
+) -> Result<Option<serde_json::Value>, AppError> {
+    let Some((merkle_root, leaf_count)) = sqlx::query_as::<_, (String, i64)>(
+        "SELECT merkle_root, leaf_count FROM bulletin_snapshots WHERE contest_id = $1",
+    )
+    .bind(contest_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|_| AppError::Internal)?
+    else {
+        return Ok(None);
+    };
+
+    let mut commitments = commitments(conn, contest_id).await?;
+    commitments.truncate(leaf_count as usize);
+    let index = commitments.iter().position(|c| c == commitment);
+    let path = index.map(|index| bulletin::inclusion_proof(&commitments, index));
+    let verified = path
+        .as_deref()
+        .is_some_and(|path| bulletin::verify_inclusion(commitment, path, &merkle_root));
+
+    Ok(Some(serde_json::json!({
+        "included": index.is_some(),
+        "verified": verified,
+        "position": index.map(|index| index + 1),
+        "leaf_count": leaf_count,
+        "merkle_root": merkle_root,
+        "path": path
+    })))
+}
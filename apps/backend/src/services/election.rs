@@ -1,21 +1,53 @@
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
-use crate::{domain::CreateElectionRequest, errors::AppError};
+use crate::{
+    domain::{CreateElectionRequest, TieBreakPolicy},
+    errors::AppError,
+    tally,
+};
+
+// Lot drawing needs a seed fixed before voting; one is generated if the officer gives none.
+pub fn tie_break_seed(
+    policy: Option<TieBreakPolicy>,
+    seed: Option<&str>,
+) -> Result<Option<String>, AppError> {
+    match seed.map(str::trim) {
+        Some("") => Err(AppError::BadRequest(
+            "tie_break_seed cannot be empty".to_string(),
+        )),
+        Some(seed) => Ok(Some(seed.to_string())),
+        None if policy == Some(TieBreakPolicy::Lot) => Ok(Some(tally::lot::generate_seed())),
+        None => Ok(None),
+    }
+}
 
 pub async fn create(pool: &PgPool, input: CreateElectionRequest) -> Result<Uuid, AppError> {
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+    let (election_id, _) = insert(&mut tx, input).await?;
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+
+    Ok(election_id)
+}
+
+// Creates a draft election and its default contest, returning both ids.
+pub async fn insert(
+    tx: &mut Transaction<'_, Postgres>,
+    input: CreateElectionRequest,
+) -> Result<(Uuid, Uuid), AppError> {
     if input.opens_at >= input.closes_at {
         return Err(AppError::BadRequest(
             "opens_at must be earlier than closes_at".to_string(),
         ));
     }
 
-    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+    let tie_break_policy = input.tie_break_policy.unwrap_or(TieBreakPolicy::Manual);
+    let tie_break_seed = tie_break_seed(Some(tie_break_policy), input.tie_break_seed.as_deref())?;
 
     let org_exists =
         sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM organizations WHERE id = $1")
             .bind(input.organization_id)
-            .fetch_one(&mut *tx)
+            .fetch_one(&mut **tx)
             .await
             .map_err(|_| AppError::Internal)?;
 
@@ -28,8 +60,11 @@ pub async fn create(pool: &PgPool, input: CreateElectionRequest) -> Result<Uuid,
     let election_id = Uuid::new_v4();
     sqlx::query(
         r#"
-        INSERT INTO elections (id, organization_id, title, description, opens_at, closes_at, status)
-        VALUES ($1, $2, $3, $4, $5, $6, 'draft')
+        INSERT INTO elections (
+          id, organization_id, title, description, opens_at, closes_at, status, tie_break_policy,
+          tie_break_seed, allow_revote, encrypted_ballots
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, 'draft', $7, $8, $9, $10)
         "#,
     )
     .bind(election_id)
@@ -38,7 +73,11 @@ pub async fn create(pool: &PgPool, input: CreateElectionRequest) -> Result<Uuid,
     .bind(input.description.clone())
     .bind(input.opens_at)
     .bind(input.closes_at)
-    .execute(&mut *tx)
+    .bind(tie_break_policy.as_str())
+    .bind(tie_break_seed)
+    .bind(input.allow_revote.unwrap_or(false))
+    .bind(input.encrypted_ballots.unwrap_or(false))
+    .execute(&mut **tx)
     .await
     .map_err(|_| AppError::BadRequest("invalid election payload".to_string()))?;
 
@@ -54,11 +93,9 @@ pub async fn create(pool: &PgPool, input: CreateElectionRequest) -> Result<Uuid,
     .bind(election_id)
     .bind(input.title)
     .bind(input.description)
-    .execute(&mut *tx)
+    .execute(&mut **tx)
     .await
     .map_err(|_| AppError::Internal)?;
 
-    tx.commit().await.map_err(|_| AppError::Internal)?;
-
-    Ok(election_id)
+    Ok((election_id, contest_id))
 }
@@ -0,0 +1,681 @@
+use std::collections::HashMap;
+
+use curve25519_dalek::ristretto::RistrettoPoint;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::{EncryptedBallotInput, ProofInput, SubmitDecryptionSharesRequest},
+    errors::AppError,
+    security::{
+        ballot_link,
+        elgamal::{self, Ciphertext, ProofBranch},
+        trustee,
+    },
+    services::{audit, key_ceremony, results},
+};
+
+pub struct VerifiedSelection {
+    pub candidate_id: Uuid,
+    pub ciphertext: Ciphertext,
+    pub proof: serde_json::Value,
+}
+
+// Proof contexts bind every proof to its contest and candidate; the selection-count proof uses
+// the nil candidate id.
+pub fn proof_context(contest_id: Uuid, candidate_id: Uuid) -> Vec<u8> {
+    [contest_id.as_bytes().as_slice(), candidate_id.as_bytes().as_slice()].concat()
+}
+
+// Ballot proofs are also bound to the ballot's proof nonce, which only its voter can cast, so
+// another voter cannot resubmit them.
+pub fn ballot_proof_context(contest_id: Uuid, candidate_id: Uuid, proof_nonce: &str) -> Vec<u8> {
+    [proof_context(contest_id, candidate_id), proof_nonce.as_bytes().to_vec()].concat()
+}
+
+pub fn parse_proof(proof: &[ProofInput]) -> Option<Vec<ProofBranch>> {
+    proof
+        .iter()
+        .map(|branch| {
+            Some(ProofBranch {
+                challenge: elgamal::decode_scalar(&branch.challenge)?,
+                response: elgamal::decode_scalar(&branch.response)?,
+            })
+        })
+        .collect()
+}
+
+// Checks an encrypted ballot: one ciphertext per candidate, each proven to hold 0 or 1, and
+// their sum proven to be one of `allowed_counts`, all under a proof nonce issued to `voter_id`.
+// Ciphertexts already cast in the contest are refused. Nothing about the choices is learned.
+pub async fn verify_ballot(
+    tx: &mut Transaction<'_, Postgres>,
+    link_key: &str,
+    contest_id: Uuid,
+    voter_id: Uuid,
+    public_key: &str,
+    allowed_counts: &[u64],
+    input: &EncryptedBallotInput,
+) -> Result<Vec<VerifiedSelection>, AppError> {
+    let public_key = elgamal::decode_point(public_key).ok_or(AppError::Internal)?;
+    if !ballot_link::check_proof_nonce(link_key, contest_id, voter_id, &input.proof_nonce) {
+        return Err(AppError::BadRequest(
+            "proof_nonce was not issued to this voter for this contest".to_string(),
+        ));
+    }
+
+    let mut candidate_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM candidates WHERE contest_id = $1",
+    )
+    .bind(contest_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    candidate_ids.sort();
+
+    let mut submitted: Vec<Uuid> = input.selections.iter().map(|s| s.candidate_id).collect();
+    submitted.sort();
+    if submitted != candidate_ids {
+        return Err(AppError::BadRequest(
+            "encrypted selections must cover every candidate exactly once".to_string(),
+        ));
+    }
+
+    let mut selections = Vec::with_capacity(input.selections.len());
+    let mut count = Ciphertext::zero();
+    for selection in &input.selections {
+        let ciphertext = match (
+            elgamal::decode_point(&selection.ciphertext.a),
+            elgamal::decode_point(&selection.ciphertext.b),
+        ) {
+            (Some(a), Some(b)) => Ciphertext { a, b },
+            _ => {
+                return Err(AppError::BadRequest(format!(
+                    "invalid ciphertext for candidate {}",
+                    selection.candidate_id
+                )))
+            }
+        };
+        let proof = parse_proof(&selection.proof).ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "invalid proof encoding for candidate {}",
+                selection.candidate_id
+            ))
+        })?;
+        let context = ballot_proof_context(contest_id, selection.candidate_id, &input.proof_nonce);
+        if !elgamal::verify_membership(&public_key, &ciphertext, &[0, 1], &context, &proof) {
+            return Err(AppError::BadRequest(format!(
+                "validity proof failed for candidate {}",
+                selection.candidate_id
+            )));
+        }
+
+        count = count.add(&ciphertext);
+        selections.push(VerifiedSelection {
+            candidate_id: selection.candidate_id,
+            ciphertext,
+            proof: serde_json::json!(selection.proof),
+        });
+    }
+
+    let count_proof = parse_proof(&input.selection_count_proof).ok_or_else(|| {
+        AppError::BadRequest("invalid selection_count_proof encoding".to_string())
+    })?;
+    let context = ballot_proof_context(contest_id, Uuid::nil(), &input.proof_nonce);
+    if !elgamal::verify_membership(&public_key, &count, allowed_counts, &context, &count_proof) {
+        return Err(AppError::BadRequest(
+            "selection_count_proof failed".to_string(),
+        ));
+    }
+
+    let mut ciphertexts: Vec<String> = selections
+        .iter()
+        .map(|selection| elgamal::encode_point(&selection.ciphertext.a))
+        .collect();
+    ciphertexts.sort();
+    ciphertexts.dedup();
+    if ciphertexts.len() != selections.len() {
+        return Err(AppError::BadRequest(
+            "encrypted selections must not repeat a ciphertext".to_string(),
+        ));
+    }
+    let duplicate = sqlx::query_scalar::<_, Uuid>(
+        "SELECT candidate_id FROM encrypted_votes WHERE contest_id = $1 AND ciphertext_a = ANY($2)",
+    )
+    .bind(contest_id)
+    .bind(&ciphertexts)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if let Some(candidate_id) = duplicate {
+        return Err(AppError::BadRequest(format!(
+            "ciphertext for candidate {} has already been cast",
+            candidate_id
+        )));
+    }
+
+    Ok(selections)
+}
+
+struct Aggregate {
+    candidate_id: Uuid,
+    name: String,
+    ciphertext: Ciphertext,
+    // Upper bound for the decrypted total: the summed weight of every ballot for this candidate.
+    weight: u64,
+}
+
+async fn aggregates(conn: &mut PgConnection, contest_id: Uuid) -> Result<Vec<Aggregate>, AppError> {
+    let candidates = sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT id, name FROM candidates WHERE contest_id = $1 ORDER BY id ASC",
+    )
+    .bind(contest_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let rows = sqlx::query_as::<_, (Uuid, String, String, i64)>(
+        r#"
+        SELECT candidate_id, ciphertext_a, ciphertext_b, weight
+        FROM encrypted_votes
+        WHERE contest_id = $1
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let mut sums: HashMap<Uuid, (Ciphertext, u64)> = HashMap::new();
+    for (candidate_id, a, b, weight) in rows {
+        let ciphertext = match (elgamal::decode_point(&a), elgamal::decode_point(&b)) {
+            (Some(a), Some(b)) => Ciphertext { a, b },
+            _ => return Err(AppError::Internal),
+        };
+        let entry = sums
+            .entry(candidate_id)
+            .or_insert_with(|| (Ciphertext::zero(), 0));
+        entry.0 = entry.0.add(&ciphertext.scale(weight as u64));
+        entry.1 += weight as u64;
+    }
+
+    Ok(candidates
+        .into_iter()
+        .map(|(candidate_id, name)| {
+            let (ciphertext, weight) = sums
+                .remove(&candidate_id)
+                .unwrap_or_else(|| (Ciphertext::zero(), 0));
+            Aggregate {
+                candidate_id,
+                name,
+                ciphertext,
+                weight,
+            }
+        })
+        .collect())
+}
+
+// Everything needed to check an encrypted contest independently: the election key, every
+// ballot's ciphertexts and proofs, the weighted per-candidate sums, the trustees' decryption
+// shares and, once enough shares are in, the decrypted totals.
+pub async fn contest_tally(pool: &PgPool, contest_id: Uuid) -> Result<serde_json::Value, AppError> {
+    let (election_id, encrypted, threshold, public_key) =
+        sqlx::query_as::<_, (Uuid, bool, Option<i32>, Option<String>)>(
+            r#"
+            SELECT e.id, e.encrypted_ballots, e.trustee_threshold, e.encryption_public_key
+            FROM contests c
+            JOIN elections e ON e.id = c.election_id
+            WHERE c.id = $1
+            "#,
+        )
+        .bind(contest_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| AppError::Internal)?
+        .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
+    if !encrypted {
+        return Err(AppError::BadRequest(
+            "contest does not use encrypted ballots".to_string(),
+        ));
+    }
+
+    let ballots = sqlx::query_as::<
+        _,
+        (Uuid, Option<String>, Option<serde_json::Value>, serde_json::Value),
+    >(
+        r#"
+        SELECT b.id, b.proof_nonce, b.selection_count_proof,
+               jsonb_agg(
+                 jsonb_build_object(
+                   'candidate_id', v.candidate_id, 'weight', v.weight,
+                   'ciphertext', jsonb_build_object('a', v.ciphertext_a, 'b', v.ciphertext_b),
+                   'proof', v.proof
+                 )
+                 ORDER BY v.candidate_id
+               )
+        FROM ballots b
+        JOIN encrypted_votes v ON v.ballot_id = b.id
+        WHERE b.contest_id = $1
+        GROUP BY b.id
+        ORDER BY b.id
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let shares = sqlx::query_as::<_, (i32, Uuid, String, serde_json::Value)>(
+        r#"
+        SELECT t.trustee_index, d.candidate_id, d.share, d.proof
+        FROM decryption_shares d
+        JOIN trustees t ON t.id = d.trustee_id
+        WHERE d.contest_id = $1
+        ORDER BY t.trustee_index, d.candidate_id
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let totals: HashMap<Uuid, i64> = sqlx::query_as::<_, (Uuid, i64)>(
+        "SELECT candidate_id, total FROM encrypted_tallies WHERE contest_id = $1",
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .into_iter()
+    .collect();
+
+    let mut conn = pool.acquire().await.map_err(|_| AppError::Internal)?;
+    let aggregates = aggregates(&mut conn, contest_id).await?;
+
+    Ok(serde_json::json!({
+        "contest_id": contest_id,
+        "election_id": election_id,
+        "public_key": public_key,
+        "trustee_threshold": threshold,
+        "status": if totals.is_empty() { "pending" } else { "decrypted" },
+        "ballots": ballots
+            .into_iter()
+            .map(|(ballot_id, proof_nonce, selection_count_proof, selections)| {
+                serde_json::json!({
+                    "ballot_id": ballot_id,
+                    "proof_nonce": proof_nonce,
+                    "selections": selections,
+                    "selection_count_proof": selection_count_proof
+                })
+            })
+            .collect::<Vec<_>>(),
+        "aggregates": aggregates
+            .iter()
+            .map(|aggregate| {
+                serde_json::json!({
+                    "candidate_id": aggregate.candidate_id,
+                    "name": aggregate.name,
+                    "ciphertext": {
+                        "a": elgamal::encode_point(&aggregate.ciphertext.a),
+                        "b": elgamal::encode_point(&aggregate.ciphertext.b)
+                    },
+                    "total": totals.get(&aggregate.candidate_id)
+                })
+            })
+            .collect::<Vec<_>>(),
+        "decryption_shares": shares
+            .into_iter()
+            .map(|(trustee_index, candidate_id, share, proof)| {
+                serde_json::json!({
+                    "trustee_index": trustee_index,
+                    "candidate_id": candidate_id,
+                    "share": share,
+                    "proof": proof
+                })
+            })
+            .collect::<Vec<_>>()
+    }))
+}
+
+// Decrypted totals in the shape of the plaintext plurality totals: candidates with votes, highest
+// first. Empty until the trustees have decrypted the contest.
+pub async fn totals(pool: &PgPool, contest_id: Uuid) -> Result<Vec<serde_json::Value>, AppError> {
+    let rows = sqlx::query_as::<_, (Uuid, String, i64)>(
+        r#"
+        SELECT t.candidate_id, c.name, t.total
+        FROM encrypted_tallies t
+        JOIN candidates c ON c.id = t.candidate_id
+        WHERE t.contest_id = $1 AND t.total > 0
+        ORDER BY t.total DESC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(candidate_id, name, total)| {
+            serde_json::json!({ "candidate_id": candidate_id, "name": name, "total": total })
+        })
+        .collect())
+}
+
+// Accepts one trustee's decryption shares for every candidate of every contest in a closed
+// election, each with a proof checked against the trustee's public key share. Contests that
+// reach the threshold are decrypted straight away.
+pub async fn submit_shares(
+    pool: &PgPool,
+    election_id: Uuid,
+    user_id: Uuid,
+    input: SubmitDecryptionSharesRequest,
+) -> Result<serde_json::Value, AppError> {
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+
+    let (status, encrypted, threshold) = sqlx::query_as::<_, (String, bool, Option<i32>)>(
+        r#"
+        SELECT status, encrypted_ballots, trustee_threshold
+        FROM elections
+        WHERE id = $1
+        FOR UPDATE
+        "#,
+    )
+    .bind(election_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .ok_or_else(|| AppError::NotFound("election not found".to_string()))?;
+    let threshold = match (encrypted, threshold) {
+        (true, Some(threshold)) => threshold as usize,
+        _ => {
+            return Err(AppError::BadRequest(
+                "election does not use encrypted ballots".to_string(),
+            ))
+        }
+    };
+    if status != "closed" {
+        return Err(AppError::Conflict(
+            "decryption shares are accepted only after close".to_string(),
+        ));
+    }
+
+    let trustees = key_ceremony::load_trustees(&mut tx, election_id).await?;
+    let me = trustees
+        .iter()
+        .find(|t| t.user_id == user_id)
+        .ok_or(AppError::Forbidden)?;
+    let public_share = trustee::public_share(&key_ceremony::commitments(&trustees)?, me.index);
+
+    let already = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM decryption_shares WHERE trustee_id = $1",
+    )
+    .bind(me.id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if already > 0 {
+        return Err(AppError::Conflict(
+            "decryption shares already submitted".to_string(),
+        ));
+    }
+
+    let contest_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM contests WHERE election_id = $1 ORDER BY is_default DESC, created_at ASC",
+    )
+    .bind(election_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let mut submitted: HashMap<(Uuid, Uuid), &_> = input
+        .shares
+        .iter()
+        .map(|share| ((share.contest_id, share.candidate_id), share))
+        .collect();
+    if submitted.len() != input.shares.len() {
+        return Err(AppError::BadRequest(
+            "each candidate may have only one decryption share".to_string(),
+        ));
+    }
+
+    let mut contests = Vec::with_capacity(contest_ids.len());
+    for contest_id in &contest_ids {
+        for aggregate in aggregates(&mut tx, *contest_id).await? {
+            let input = submitted
+                .remove(&(*contest_id, aggregate.candidate_id))
+                .ok_or_else(|| {
+                    AppError::BadRequest(format!(
+                        "missing decryption share for candidate {}",
+                        aggregate.candidate_id
+                    ))
+                })?;
+            let share = elgamal::decode_point(&input.share);
+            let proof = parse_proof(std::slice::from_ref(&input.proof));
+            let valid = match (share, proof.as_deref()) {
+                (Some(share), Some([proof])) => trustee::verify_decryption_share(
+                    &proof_context(*contest_id, aggregate.candidate_id),
+                    &public_share,
+                    &aggregate.ciphertext.a,
+                    &share,
+                    proof,
+                ),
+                _ => false,
+            };
+            if !valid {
+                return Err(AppError::BadRequest(format!(
+                    "decryption share proof failed for candidate {}",
+                    aggregate.candidate_id
+                )));
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO decryption_shares (trustee_id, contest_id, candidate_id, share, proof)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(me.id)
+            .bind(contest_id)
+            .bind(aggregate.candidate_id)
+            .bind(&input.share)
+            .bind(serde_json::json!(input.proof))
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| AppError::Internal)?;
+        }
+    }
+    if let Some((contest_id, candidate_id)) = submitted.into_keys().next() {
+        return Err(AppError::BadRequest(format!(
+            "candidate {} is not in contest {} of this election",
+            candidate_id, contest_id
+        )));
+    }
+
+    audit::record(
+        &mut tx,
+        "decryption_shares_submitted",
+        user_id,
+        election_id,
+        serde_json::json!({ "trustee_index": me.index }),
+    )
+    .await?;
+
+    // The shares are committed before any discrete log is searched for, so the search holds no
+    // locks, and a total that cannot be recovered does not discard the trustee's shares.
+    let mut ready = Vec::new();
+    for contest_id in &contest_ids {
+        if let Some(decryption) = combine_shares(&mut tx, *contest_id, threshold).await? {
+            ready.push(decryption);
+        }
+    }
+
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+
+    let mut decrypted = Vec::new();
+    for decryption in ready {
+        // Recovering totals is CPU-bound, so it runs off the async workers.
+        let (decryption, totals) = tokio::task::spawn_blocking(move || {
+            let totals = decryption.totals();
+            (decryption, totals)
+        })
+        .await
+        .map_err(|_| AppError::Internal)?;
+        let totals = totals?;
+        store_totals(pool, election_id, &decryption, &totals, user_id).await?;
+        results::record_tie_break(pool, decryption.contest_id, user_id).await?;
+        decrypted.push(decryption.contest_id);
+    }
+    for contest_id in contest_ids {
+        let done = decrypted.contains(&contest_id) || is_decrypted(pool, contest_id).await?;
+        contests.push(serde_json::json!({ "contest_id": contest_id, "decrypted": done }));
+    }
+
+    Ok(serde_json::json!({ "trustee_index": me.index, "contests": contests }))
+}
+
+async fn is_decrypted(
+    conn: impl sqlx::PgExecutor<'_>,
+    contest_id: Uuid,
+) -> Result<bool, AppError> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM encrypted_tallies WHERE contest_id = $1)",
+    )
+    .bind(contest_id)
+    .fetch_one(conn)
+    .await
+    .map_err(|_| AppError::Internal)
+}
+
+// A contest whose shares have been combined: each candidate's total as a point m·G, with the
+// upper bound for m.
+struct Decryption {
+    contest_id: Uuid,
+    trustee_indices: Vec<i32>,
+    candidates: Vec<(Uuid, RistrettoPoint, u64)>,
+}
+
+impl Decryption {
+    fn totals(&self) -> Result<Vec<(Uuid, i64)>, AppError> {
+        self.candidates
+            .iter()
+            .map(|(candidate_id, point, bound)| {
+                let total = elgamal::discrete_log(point, *bound).ok_or_else(|| {
+                    AppError::Conflict(format!(
+                        "decryption shares were recorded, but the total for candidate {} in \
+                         contest {} is not between 0 and {}",
+                        candidate_id, self.contest_id, bound
+                    ))
+                })?;
+                Ok((*candidate_id, total as i64))
+            })
+            .collect()
+    }
+}
+
+// Combines the shares of the first `threshold` trustees (by index), or returns `None` if the
+// contest is already decrypted or still short of shares.
+async fn combine_shares(
+    tx: &mut Transaction<'_, Postgres>,
+    contest_id: Uuid,
+    threshold: usize,
+) -> Result<Option<Decryption>, AppError> {
+    if is_decrypted(&mut **tx, contest_id).await? {
+        return Ok(None);
+    }
+
+    let rows = sqlx::query_as::<_, (i32, Uuid, String)>(
+        r#"
+        SELECT t.trustee_index, d.candidate_id, d.share
+        FROM decryption_shares d
+        JOIN trustees t ON t.id = d.trustee_id
+        WHERE d.contest_id = $1
+        ORDER BY t.trustee_index
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let mut indices: Vec<i32> = rows.iter().map(|(index, _, _)| *index).collect();
+    indices.dedup();
+    if indices.len() < threshold {
+        return Ok(None);
+    }
+    indices.truncate(threshold);
+
+    let mut candidates = Vec::new();
+    for aggregate in aggregates(tx, contest_id).await? {
+        let shares: Vec<(u64, _)> = rows
+            .iter()
+            .filter(|(index, candidate_id, _)| {
+                indices.contains(index) && *candidate_id == aggregate.candidate_id
+            })
+            .map(|(index, _, share)| {
+                elgamal::decode_point(share)
+                    .map(|share| (*index as u64, share))
+                    .ok_or(AppError::Internal)
+            })
+            .collect::<Result<_, _>>()?;
+        let decrypted = aggregate.ciphertext.b - trustee::combine(&shares);
+        candidates.push((aggregate.candidate_id, decrypted, aggregate.weight));
+    }
+
+    Ok(Some(Decryption {
+        contest_id,
+        trustee_indices: indices,
+        candidates,
+    }))
+}
+
+// Stores the decrypted totals unless a trustee submitting at the same time stored them first.
+async fn store_totals(
+    pool: &PgPool,
+    election_id: Uuid,
+    decryption: &Decryption,
+    totals: &[(Uuid, i64)],
+    actor_id: Uuid,
+) -> Result<(), AppError> {
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+    sqlx::query("SELECT id FROM elections WHERE id = $1 FOR UPDATE")
+        .bind(election_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    if is_decrypted(&mut *tx, decryption.contest_id).await? {
+        return Ok(());
+    }
+
+    for (candidate_id, total) in totals {
+        sqlx::query(
+            "INSERT INTO encrypted_tallies (contest_id, candidate_id, total) VALUES ($1, $2, $3)",
+        )
+        .bind(decryption.contest_id)
+        .bind(candidate_id)
+        .bind(total)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    }
+
+    audit::record(
+        &mut tx,
+        "tally_decrypted",
+        actor_id,
+        election_id,
+        serde_json::json!({
+            "contest_id": decryption.contest_id,
+            "trustee_indices": decryption.trustee_indices,
+            "totals": totals
+                .iter()
+                .map(|(candidate_id, total)| {
+                    serde_json::json!({ "candidate_id": candidate_id, "total": total })
+                })
+                .collect::<Vec<_>>()
+        }),
+    )
+    .await?;
+
+    tx.commit().await.map_err(|_| AppError::Internal)
+}
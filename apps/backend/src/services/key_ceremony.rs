@@ -0,0 +1,561 @@
+use curve25519_dalek::ristretto::RistrettoPoint;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    domain::{CreateTrusteesRequest, PostKeyCommitmentsRequest, PostKeySharesRequest},
+    errors::AppError,
+    security::{elgamal, trustee},
+    services::{audit, encrypted_tally},
+};
+
+pub struct Trustee {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub index: u64,
+    pub transport_key: Option<String>,
+    pub commitments: Option<serde_json::Value>,
+    pub commitment_proof: Option<serde_json::Value>,
+}
+
+struct CeremonyElection {
+    status: String,
+    threshold: Option<i32>,
+    public_key: Option<String>,
+}
+
+// Key proofs are bound to the election and the trustee's index so they cannot be replayed.
+pub fn key_context(election_id: Uuid, index: u64) -> Vec<u8> {
+    [election_id.as_bytes().as_slice(), index.to_le_bytes().as_slice()].concat()
+}
+
+async fn lock_election(
+    conn: &mut PgConnection,
+    election_id: Uuid,
+) -> Result<CeremonyElection, AppError> {
+    let (status, encrypted_ballots, threshold, public_key) =
+        sqlx::query_as::<_, (String, bool, Option<i32>, Option<String>)>(
+            r#"
+            SELECT status, encrypted_ballots, trustee_threshold, encryption_public_key
+            FROM elections
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(election_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|_| AppError::Internal)?
+        .ok_or_else(|| AppError::NotFound("election not found".to_string()))?;
+
+    if !encrypted_ballots {
+        return Err(AppError::BadRequest(
+            "election does not use encrypted ballots".to_string(),
+        ));
+    }
+
+    Ok(CeremonyElection {
+        status,
+        threshold,
+        public_key,
+    })
+}
+
+fn ensure_draft(election: &CeremonyElection) -> Result<(), AppError> {
+    if election.status != "draft" {
+        return Err(AppError::Conflict(
+            "the key ceremony runs only while the election is a draft".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn load_trustees(
+    conn: &mut PgConnection,
+    election_id: Uuid,
+) -> Result<Vec<Trustee>, AppError> {
+    let rows = sqlx::query_as::<
+        _,
+        (
+            Uuid,
+            Uuid,
+            i32,
+            Option<String>,
+            Option<serde_json::Value>,
+            Option<serde_json::Value>,
+        ),
+    >(
+        r#"
+        SELECT id, user_id, trustee_index, transport_key, commitments, commitment_proof
+        FROM trustees
+        WHERE election_id = $1
+        ORDER BY trustee_index ASC
+        "#,
+    )
+    .bind(election_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(id, user_id, index, transport_key, commitments, commitment_proof)| Trustee {
+                id,
+                user_id,
+                index: index as u64,
+                transport_key,
+                commitments,
+                commitment_proof,
+            },
+        )
+        .collect())
+}
+
+// Every trustee's published coefficient commitments, in trustee order.
+pub fn commitments(trustees: &[Trustee]) -> Result<Vec<Vec<RistrettoPoint>>, AppError> {
+    trustees
+        .iter()
+        .map(|trustee| {
+            let encoded: Vec<String> = trustee
+                .commitments
+                .clone()
+                .and_then(|value| serde_json::from_value(value).ok())
+                .ok_or_else(|| AppError::Conflict("key ceremony is not complete".to_string()))?;
+            encoded
+                .iter()
+                .map(|point| elgamal::decode_point(point).ok_or(AppError::Internal))
+                .collect()
+        })
+        .collect()
+}
+
+async fn shares_dealt(conn: &mut PgConnection, election_id: Uuid) -> Result<i64, AppError> {
+    sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM trustee_shares s
+        JOIN trustees t ON t.id = s.dealer_id
+        WHERE t.election_id = $1
+        "#,
+    )
+    .bind(election_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|_| AppError::Internal)
+}
+
+pub async fn appoint(
+    pool: &PgPool,
+    election_id: Uuid,
+    actor_id: Uuid,
+    input: CreateTrusteesRequest,
+) -> Result<serde_json::Value, AppError> {
+    let mut user_ids = input.user_ids.clone();
+    user_ids.sort();
+    user_ids.dedup();
+    if user_ids.is_empty() || user_ids.len() != input.user_ids.len() {
+        return Err(AppError::BadRequest(
+            "user_ids must list distinct trustees".to_string(),
+        ));
+    }
+    if input.threshold < 1 || input.threshold as usize > user_ids.len() {
+        return Err(AppError::BadRequest(
+            "threshold must be between 1 and the number of trustees".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+    let election = lock_election(&mut tx, election_id).await?;
+    ensure_draft(&election)?;
+
+    if !load_trustees(&mut tx, election_id).await?.is_empty() {
+        return Err(AppError::Conflict(
+            "trustees are already appointed".to_string(),
+        ));
+    }
+
+    let known = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users WHERE id = ANY($1)")
+        .bind(&user_ids)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    if known != user_ids.len() as i64 {
+        return Err(AppError::BadRequest(
+            "one or more user_ids are invalid".to_string(),
+        ));
+    }
+
+    for (position, user_id) in input.user_ids.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO trustees (id, election_id, user_id, trustee_index)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(election_id)
+        .bind(user_id)
+        .bind(position as i32 + 1)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    }
+
+    sqlx::query("UPDATE elections SET trustee_threshold = $1 WHERE id = $2")
+        .bind(input.threshold)
+        .bind(election_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+
+    audit::record(
+        &mut tx,
+        "trustees_appointed",
+        actor_id,
+        election_id,
+        serde_json::json!({ "user_ids": input.user_ids, "threshold": input.threshold }),
+    )
+    .await?;
+
+    let status = status_in(&mut tx, election_id).await?;
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+
+    Ok(status)
+}
+
+pub async fn status(pool: &PgPool, election_id: Uuid) -> Result<serde_json::Value, AppError> {
+    let mut conn = pool.acquire().await.map_err(|_| AppError::Internal)?;
+    status_in(&mut conn, election_id).await
+}
+
+async fn status_in(
+    conn: &mut PgConnection,
+    election_id: Uuid,
+) -> Result<serde_json::Value, AppError> {
+    let (encrypted, threshold, public_key) =
+        sqlx::query_as::<_, (bool, Option<i32>, Option<String>)>(
+            r#"
+            SELECT encrypted_ballots, trustee_threshold, encryption_public_key
+            FROM elections
+            WHERE id = $1
+            "#,
+        )
+        .bind(election_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|_| AppError::Internal)?
+        .ok_or_else(|| AppError::NotFound("election not found".to_string()))?;
+    if !encrypted {
+        return Err(AppError::BadRequest(
+            "election does not use encrypted ballots".to_string(),
+        ));
+    }
+
+    let trustees = load_trustees(conn, election_id).await?;
+    let dealt = shares_dealt(conn, election_id).await?;
+    let n = trustees.len() as i64;
+    let phase = if trustees.is_empty() {
+        "appointing"
+    } else if public_key.is_none() {
+        "commitments"
+    } else if dealt < n * (n - 1) {
+        "shares"
+    } else {
+        "complete"
+    };
+
+    Ok(serde_json::json!({
+        "election_id": election_id,
+        "phase": phase,
+        "threshold": threshold,
+        "public_key": public_key,
+        "shares_dealt": dealt,
+        "trustees": trustees
+            .iter()
+            .map(|trustee| {
+                serde_json::json!({
+                    "trustee_index": trustee.index,
+                    "user_id": trustee.user_id,
+                    "transport_key": trustee.transport_key,
+                    "commitments": trustee.commitments,
+                    "commitment_proof": trustee.commitment_proof
+                })
+            })
+            .collect::<Vec<_>>()
+    }))
+}
+
+// Takes one trustee's Feldman commitments and transport key. Once every trustee has posted, the
+// joint election key is fixed.
+pub async fn post_commitments(
+    pool: &PgPool,
+    election_id: Uuid,
+    user_id: Uuid,
+    input: PostKeyCommitmentsRequest,
+) -> Result<serde_json::Value, AppError> {
+    let transport_key = input.transport_key.trim();
+    if transport_key.is_empty() {
+        return Err(AppError::BadRequest(
+            "transport_key cannot be empty".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+    let election = lock_election(&mut tx, election_id).await?;
+    ensure_draft(&election)?;
+
+    let trustees = load_trustees(&mut tx, election_id).await?;
+    let me = trustees
+        .iter()
+        .find(|t| t.user_id == user_id)
+        .ok_or(AppError::Forbidden)?;
+    if me.commitments.is_some() {
+        return Err(AppError::Conflict(
+            "commitments already posted".to_string(),
+        ));
+    }
+
+    let threshold = election.threshold.ok_or(AppError::Internal)? as usize;
+    if input.commitments.len() != threshold {
+        return Err(AppError::BadRequest(format!(
+            "exactly {} commitments are required",
+            threshold
+        )));
+    }
+    let points: Vec<RistrettoPoint> = input
+        .commitments
+        .iter()
+        .map(|point| elgamal::decode_point(point))
+        .collect::<Option<_>>()
+        .ok_or_else(|| AppError::BadRequest("invalid commitment encoding".to_string()))?;
+    let proof = encrypted_tally::parse_proof(std::slice::from_ref(&input.proof))
+        .and_then(|branches| branches.into_iter().next())
+        .ok_or_else(|| AppError::BadRequest("invalid proof encoding".to_string()))?;
+    if !trustee::verify_key_proof(&key_context(election_id, me.index), &points[0], &proof) {
+        return Err(AppError::BadRequest("key proof failed".to_string()));
+    }
+
+    // Re-encoding stores the canonical form whatever casing the client used.
+    let encoded: Vec<String> = points.iter().map(elgamal::encode_point).collect();
+    sqlx::query(
+        r#"
+        UPDATE trustees
+        SET transport_key = $1, commitments = $2, commitment_proof = $3
+        WHERE id = $4
+        "#,
+    )
+    .bind(transport_key)
+    .bind(serde_json::json!(encoded))
+    .bind(serde_json::json!(input.proof))
+    .bind(me.id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let index = me.index;
+    let trustees = load_trustees(&mut tx, election_id).await?;
+    let public_key = if trustees.iter().all(|t| t.commitments.is_some()) {
+        let public_key = elgamal::encode_point(&trustee::joint_public_key(&commitments(
+            &trustees,
+        )?));
+        sqlx::query("UPDATE elections SET encryption_public_key = $1 WHERE id = $2")
+            .bind(&public_key)
+            .bind(election_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| AppError::Internal)?;
+        Some(public_key)
+    } else {
+        None
+    };
+
+    audit::record(
+        &mut tx,
+        "key_commitments_posted",
+        user_id,
+        election_id,
+        serde_json::json!({ "trustee_index": index, "public_key": public_key }),
+    )
+    .await?;
+
+    let status = status_in(&mut tx, election_id).await?;
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+
+    Ok(status)
+}
+
+// Relays a dealer's key shares, each encrypted for its recipient's transport key. Shares can only
+// be dealt once the election key is fixed and must cover every other trustee exactly once.
+pub async fn post_shares(
+    pool: &PgPool,
+    election_id: Uuid,
+    user_id: Uuid,
+    input: PostKeySharesRequest,
+) -> Result<serde_json::Value, AppError> {
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+    let election = lock_election(&mut tx, election_id).await?;
+    ensure_draft(&election)?;
+    if election.public_key.is_none() {
+        return Err(AppError::Conflict(
+            "every trustee must post commitments first".to_string(),
+        ));
+    }
+
+    let trustees = load_trustees(&mut tx, election_id).await?;
+    let me = trustees
+        .iter()
+        .find(|t| t.user_id == user_id)
+        .ok_or(AppError::Forbidden)?;
+
+    let dealt = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM trustee_shares WHERE dealer_id = $1",
+    )
+    .bind(me.id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if dealt > 0 {
+        return Err(AppError::Conflict("shares already posted".to_string()));
+    }
+
+    let mut recipients: Vec<i32> = input.shares.iter().map(|s| s.recipient_index).collect();
+    recipients.sort();
+    let expected: Vec<i32> = trustees
+        .iter()
+        .filter(|t| t.index != me.index)
+        .map(|t| t.index as i32)
+        .collect();
+    if recipients != expected {
+        return Err(AppError::BadRequest(
+            "shares must cover every other trustee exactly once".to_string(),
+        ));
+    }
+
+    for share in &input.shares {
+        if share.encrypted_share.trim().is_empty() {
+            return Err(AppError::BadRequest(
+                "encrypted_share cannot be empty".to_string(),
+            ));
+        }
+        let recipient = trustees
+            .iter()
+            .find(|t| t.index == share.recipient_index as u64)
+            .ok_or(AppError::Internal)?;
+        sqlx::query(
+            r#"
+            INSERT INTO trustee_shares (dealer_id, recipient_id, encrypted_share)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(me.id)
+        .bind(recipient.id)
+        .bind(share.encrypted_share.trim())
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    }
+
+    audit::record(
+        &mut tx,
+        "key_shares_posted",
+        user_id,
+        election_id,
+        serde_json::json!({ "trustee_index": me.index }),
+    )
+    .await?;
+
+    let status = status_in(&mut tx, election_id).await?;
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+
+    Ok(status)
+}
+
+// The shares dealt to the calling trustee, with each dealer's commitments so the trustee can
+// check f_i(j)·G against them before relying on the share.
+pub async fn received_shares(
+    pool: &PgPool,
+    election_id: Uuid,
+    user_id: Uuid,
+) -> Result<serde_json::Value, AppError> {
+    let mut conn = pool.acquire().await.map_err(|_| AppError::Internal)?;
+    let trustees = load_trustees(&mut conn, election_id).await?;
+    let me = trustees
+        .iter()
+        .find(|t| t.user_id == user_id)
+        .ok_or(AppError::Forbidden)?;
+
+    let rows = sqlx::query_as::<_, (i32, Option<serde_json::Value>, String)>(
+        r#"
+        SELECT d.trustee_index, d.commitments, s.encrypted_share
+        FROM trustee_shares s
+        JOIN trustees d ON d.id = s.dealer_id
+        WHERE s.recipient_id = $1
+        ORDER BY d.trustee_index ASC
+        "#,
+    )
+    .bind(me.id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok(serde_json::json!({
+        "trustee_index": me.index,
+        "shares": rows
+            .into_iter()
+            .map(|(dealer_index, commitments, encrypted_share)| {
+                serde_json::json!({
+                    "dealer_index": dealer_index,
+                    "commitments": commitments,
+                    "encrypted_share": encrypted_share
+                })
+            })
+            .collect::<Vec<_>>()
+    }))
+}
+
+// Publishing an encrypted election needs a finished ceremony and contests the encrypted tally can
+// count: plurality or approval, without write-ins.
+pub async fn ensure_ready(conn: &mut PgConnection, election_id: Uuid) -> Result<(), AppError> {
+    let encrypted = sqlx::query_scalar::<_, bool>(
+        "SELECT encrypted_ballots FROM elections WHERE id = $1 FOR UPDATE",
+    )
+    .bind(election_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .ok_or_else(|| AppError::NotFound("election not found".to_string()))?;
+    if !encrypted {
+        return Ok(());
+    }
+
+    let election = lock_election(conn, election_id).await?;
+    let trustees = load_trustees(conn, election_id).await?;
+    let n = trustees.len() as i64;
+    if election.public_key.is_none() || shares_dealt(conn, election_id).await? < n * (n - 1) {
+        return Err(AppError::Conflict(
+            "key ceremony is not complete".to_string(),
+        ));
+    }
+
+    let unsupported = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM contests
+        WHERE election_id = $1
+          AND (voting_method NOT IN ('plurality', 'approval') OR allow_write_ins)
+        "#,
+    )
+    .bind(election_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if unsupported > 0 {
+        return Err(AppError::BadRequest(
+            "encrypted ballots support only plurality and approval contests without write-ins"
+                .to_string(),
+        ));
+    }
+
+    Ok(())
+}
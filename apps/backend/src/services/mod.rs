@@ -1,3 +1,14 @@
+pub mod audit;
 pub mod auth;
+pub mod ballot_order;
+pub mod ballot_queue;
+pub mod bulletin;
 pub mod election;
+pub mod encrypted_tally;
+pub mod key_ceremony;
+pub mod prepared_ballot;
+pub mod results;
+pub mod runoff;
+pub mod tie_break;
 pub mod vote;
+pub mod write_in;
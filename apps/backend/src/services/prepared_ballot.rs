@@ -0,0 +1,322 @@
+use curve25519_dalek::scalar::Scalar;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        CiphertextInput, EncryptedBallotInput, EncryptedSelectionInput, PrepareBallotRequest,
+        ProofInput,
+    },
+    errors::AppError,
+    security::{
+        ballot_link,
+        elgamal::{self, Ciphertext, ProofBranch},
+    },
+    services::{audit, encrypted_tally, vote},
+};
+
+// The randomness behind one candidate's ciphertext; revealed only when the ballot is challenged.
+#[derive(Serialize, Deserialize)]
+struct Randomness {
+    candidate_id: Uuid,
+    randomness: String,
+}
+
+fn proof_inputs(proof: &[ProofBranch]) -> Vec<ProofInput> {
+    proof
+        .iter()
+        .map(|branch| ProofInput {
+            challenge: elgamal::encode_scalar(&branch.challenge),
+            response: elgamal::encode_scalar(&branch.response),
+        })
+        .collect()
+}
+
+// The hash a voter notes down before choosing to cast or challenge: SHA-256 of the ballot's
+// compact JSON with keys sorted.
+fn ballot_hash(ballot: &serde_json::Value) -> String {
+    format!("{:x}", Sha256::digest(audit::canonical_json(ballot).as_bytes()))
+}
+
+// Encrypts the voter's choices exactly as a voting client would, with every proof, and holds the
+// randomness, sealed with the ballot link key, until the voter either casts the ballot or
+// challenges it.
+pub async fn prepare(
+    pool: &PgPool,
+    link_key: &str,
+    contest_id: Uuid,
+    voter_id: Uuid,
+    input: PrepareBallotRequest,
+) -> Result<serde_json::Value, AppError> {
+    let selected: Vec<Uuid> = input.selections.iter().filter_map(|s| s.candidate_id).collect();
+
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+    let target = vote::encryption_target(&mut tx, contest_id, voter_id, input.selections).await?;
+    let public_key = elgamal::decode_point(&target.public_key).ok_or(AppError::Internal)?;
+
+    let candidate_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM candidates WHERE contest_id = $1 ORDER BY id ASC",
+    )
+    .bind(contest_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if selected.iter().any(|id| !candidate_ids.contains(id)) {
+        return Err(AppError::BadRequest(
+            "one or more candidate_id values are invalid".to_string(),
+        ));
+    }
+
+    let proof_nonce = ballot_link::proof_nonce(link_key, contest_id, voter_id);
+    let mut selections = Vec::with_capacity(candidate_ids.len());
+    let mut randomness = Vec::with_capacity(candidate_ids.len());
+    let mut count = Ciphertext::zero();
+    let mut count_randomness = Scalar::ZERO;
+    for candidate_id in candidate_ids {
+        let value = u64::from(selected.contains(&candidate_id));
+        let r = elgamal::random_scalar();
+        let ciphertext = elgamal::encrypt(&public_key, value, r);
+        let proof = elgamal::prove_membership(
+            &public_key,
+            &ciphertext,
+            value,
+            r,
+            &[0, 1],
+            &encrypted_tally::ballot_proof_context(contest_id, candidate_id, &proof_nonce),
+        );
+
+        count = count.add(&ciphertext);
+        count_randomness += r;
+        selections.push(EncryptedSelectionInput {
+            candidate_id,
+            ciphertext: CiphertextInput {
+                a: elgamal::encode_point(&ciphertext.a),
+                b: elgamal::encode_point(&ciphertext.b),
+            },
+            proof: proof_inputs(&proof),
+        });
+        randomness.push(Randomness {
+            candidate_id,
+            randomness: elgamal::encode_scalar(&r),
+        });
+    }
+
+    let count_proof = elgamal::prove_membership(
+        &public_key,
+        &count,
+        selected.len() as u64,
+        count_randomness,
+        &target.allowed_counts,
+        &encrypted_tally::ballot_proof_context(contest_id, Uuid::nil(), &proof_nonce),
+    );
+    let ballot = serde_json::json!(EncryptedBallotInput {
+        proof_nonce,
+        selections,
+        selection_count_proof: proof_inputs(&count_proof),
+    });
+    let ballot_hash = ballot_hash(&ballot);
+
+    let prepared_ballot_id = Uuid::new_v4();
+    let randomness = serde_json::to_vec(&randomness).map_err(|_| AppError::Internal)?;
+    sqlx::query(
+        r#"
+        INSERT INTO prepared_ballots
+          (id, contest_id, voter_id, ballot, sealed_randomness, ballot_hash)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(prepared_ballot_id)
+    .bind(contest_id)
+    .bind(voter_id)
+    .bind(&ballot)
+    .bind(ballot_link::seal(link_key, prepared_ballot_id, &randomness))
+    .bind(&ballot_hash)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+
+    Ok(serde_json::json!({
+        "prepared_ballot_id": prepared_ballot_id,
+        "contest_id": contest_id,
+        "public_key": target.public_key,
+        "ballot_hash": ballot_hash,
+        "ballot": ballot
+    }))
+}
+
+// Spoils a prepared ballot: the randomness is revealed so the voter can check offline that each
+// ciphertext holds what they chose, and the opened ballot is published without the voter. A
+// challenged ballot can never be cast.
+pub async fn challenge(
+    pool: &PgPool,
+    link_key: &str,
+    contest_id: Uuid,
+    voter_id: Uuid,
+    prepared_ballot_id: Uuid,
+) -> Result<serde_json::Value, AppError> {
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+
+    let (ballot, sealed_randomness, ballot_hash) =
+        sqlx::query_as::<_, (serde_json::Value, String, String)>(
+            r#"
+            DELETE FROM prepared_ballots
+            WHERE id = $1 AND contest_id = $2 AND voter_id = $3
+            RETURNING ballot, sealed_randomness, ballot_hash
+            "#,
+        )
+        .bind(prepared_ballot_id)
+        .bind(contest_id)
+        .bind(voter_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|_| AppError::Internal)?
+        .ok_or_else(|| AppError::NotFound("prepared ballot not found".to_string()))?;
+    let randomness: serde_json::Value =
+        ballot_link::open(link_key, prepared_ballot_id, &sealed_randomness)
+            .and_then(|plaintext| serde_json::from_slice(&plaintext).ok())
+            .ok_or(AppError::Internal)?;
+
+    let (election_id, public_key) = sqlx::query_as::<_, (Uuid, Option<String>)>(
+        r#"
+        SELECT e.id, e.encryption_public_key
+        FROM contests c
+        JOIN elections e ON e.id = c.election_id
+        WHERE c.id = $1
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    let public_key = public_key
+        .as_deref()
+        .and_then(elgamal::decode_point)
+        .ok_or(AppError::Internal)?;
+
+    // Opening every ciphertext here catches a bad preparation before it is published.
+    let opened: EncryptedBallotInput =
+        serde_json::from_value(ballot.clone()).map_err(|_| AppError::Internal)?;
+    let revealed: Vec<Randomness> =
+        serde_json::from_value(randomness.clone()).map_err(|_| AppError::Internal)?;
+    let mut selections = Vec::new();
+    for selection in &opened.selections {
+        let r = revealed
+            .iter()
+            .find(|r| r.candidate_id == selection.candidate_id)
+            .and_then(|r| elgamal::decode_scalar(&r.randomness));
+        let ciphertext = match (
+            elgamal::decode_point(&selection.ciphertext.a),
+            elgamal::decode_point(&selection.ciphertext.b),
+        ) {
+            (Some(a), Some(b)) => Ciphertext { a, b },
+            _ => return Err(AppError::Internal),
+        };
+        match r.and_then(|r| elgamal::open(&public_key, &ciphertext, &r, 1)) {
+            Some(1) => selections.push(selection.candidate_id),
+            Some(_) => {}
+            None => return Err(AppError::Internal),
+        }
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO spoiled_ballots (id, contest_id, ballot, randomness, selections, ballot_hash)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(prepared_ballot_id)
+    .bind(contest_id)
+    .bind(&ballot)
+    .bind(&randomness)
+    .bind(serde_json::json!(selections))
+    .bind(&ballot_hash)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    audit::record(
+        &mut tx,
+        "ballot_spoiled",
+        voter_id,
+        election_id,
+        serde_json::json!({ "contest_id": contest_id }),
+    )
+    .await?;
+
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+
+    Ok(serde_json::json!({
+        "prepared_ballot_id": prepared_ballot_id,
+        "contest_id": contest_id,
+        "public_key": elgamal::encode_point(&public_key),
+        "ballot_hash": ballot_hash,
+        "ballot": ballot,
+        "randomness": randomness,
+        "selections": selections
+    }))
+}
+
+// Prepared ballots still waiting when the election closes can no longer be cast, so they are
+// deleted with their sealed randomness.
+pub(crate) async fn discard_election(
+    tx: &mut Transaction<'_, Postgres>,
+    election_id: Uuid,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        DELETE FROM prepared_ballots
+        WHERE contest_id IN (SELECT id FROM contests WHERE election_id = $1)
+        "#,
+    )
+    .bind(election_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    Ok(())
+}
+
+// Every challenged ballot of the contest, opened. Anyone can re-encrypt `selections` with the
+// revealed randomness and compare the result with `ballot`.
+pub async fn spoiled(pool: &PgPool, contest_id: Uuid) -> Result<serde_json::Value, AppError> {
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM contests WHERE id = $1")
+        .bind(contest_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| AppError::Internal)?
+        .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
+
+    let rows = sqlx::query_as::<
+        _,
+        (String, serde_json::Value, serde_json::Value, serde_json::Value),
+    >(
+        r#"
+        SELECT ballot_hash, ballot, randomness, selections
+        FROM spoiled_ballots
+        WHERE contest_id = $1
+        ORDER BY ballot_hash ASC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok(serde_json::json!({
+        "contest_id": contest_id,
+        "spoiled_ballots": rows
+            .into_iter()
+            .map(|(ballot_hash, ballot, randomness, selections)| {
+                serde_json::json!({
+                    "ballot_hash": ballot_hash,
+                    "ballot": ballot,
+                    "randomness": randomness,
+                    "selections": selections
+                })
+            })
+            .collect::<Vec<_>>()
+    }))
+}
@@ -0,0 +1,820 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{AllocationMethod, PassThreshold, ReferendumResponse, TieBreakPolicy, VotingMethod},
+    errors::AppError,
+    services::{
+        encrypted_tally,
+        tie_break::{TieBreaker, STAGE_WINNERS},
+    },
+    tally,
+};
+
+// Reading results never writes: tie-break draws are persisted by `record_tie_break` when the
+// tally becomes final, and recomputed identically here.
+pub async fn contest(pool: &PgPool, contest_id: Uuid) -> Result<serde_json::Value, AppError> {
+    tabulate(pool, contest_id, None).await
+}
+
+// Stores the contest's tie-break outcome once its tally is final: at close, after a decryption
+// and after each write-in adjudication. Does nothing until the election is closed.
+pub async fn record_tie_break(
+    pool: &PgPool,
+    contest_id: Uuid,
+    actor_id: Uuid,
+) -> Result<(), AppError> {
+    match tabulate(pool, contest_id, Some(actor_id)).await {
+        Ok(_) | Err(AppError::Forbidden) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+async fn tabulate(
+    pool: &PgPool,
+    contest_id: Uuid,
+    recorded_by: Option<Uuid>,
+) -> Result<serde_json::Value, AppError> {
+    let row = sqlx::query_as::<
+        _,
+        (
+            Uuid,
+            String,
+            String,
+            String,
+            String,
+            i32,
+            Option<String>,
+            Option<String>,
+            String,
+            Option<String>,
+            bool,
+            Option<String>,
+            Option<String>,
+        ),
+    >(
+        r#"
+        SELECT e.id, e.status, e.title, c.title, c.voting_method, c.seats, c.pass_threshold,
+               c.allocation_method, e.tie_break_policy, e.tie_break_seed, e.encrypted_ballots,
+               e.tie_break_commitment, s.merkle_root
+        FROM contests c
+        JOIN elections e ON e.id = c.election_id
+        LEFT JOIN bulletin_snapshots s ON s.contest_id = c.id
+        WHERE c.id = $1
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
+
+    if row.1 != "closed" {
+        return Err(AppError::Forbidden);
+    }
+
+    let voting_method = VotingMethod::from_db(&row.4).ok_or(AppError::Internal)?;
+    let seats = row.5.max(1) as usize;
+    let tie_breaker = TieBreaker {
+        election_id: row.0,
+        contest_id,
+        recorded_by,
+        policy: TieBreakPolicy::from_db(&row.8).ok_or(AppError::Internal)?,
+        // Only the seed committed to at publish is drawn with; one that no longer matches the
+        // commitment leaves the tie for the officers.
+        seed: row
+            .9
+            .clone()
+            .filter(|seed| row.11.as_deref() == Some(tally::lot::commitment(seed).as_str())),
+        entropy: row.12.clone(),
+    };
+
+    let blank_ballots = sqlx::query_scalar::<_, i64>(
+        "SELECT COALESCE(SUM(weight), 0)::bigint FROM votes WHERE contest_id = $1 AND is_blank",
+    )
+    .bind(contest_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let turnout = turnout(pool, contest_id).await?;
+    let mut data = serde_json::json!({
+        "contest_id": contest_id,
+        "contest_title": row.3,
+        "election_id": row.0,
+        "election_title": row.2,
+        "voting_method": voting_method,
+        "seats": seats,
+        "valid": turnout["quorum_met"],
+        "turnout": turnout,
+        "blank_ballots": blank_ballots,
+        "write_ins": pending_write_ins(pool, contest_id).await?
+    });
+
+    match voting_method {
+        VotingMethod::Plurality | VotingMethod::Approval if row.10 => {
+            let results = encrypted_tally::totals(pool, contest_id).await?;
+            data["decryption"] =
+                serde_json::json!(if results.is_empty() { "pending" } else { "decrypted" });
+            declare_top_n(pool, &tie_breaker, &mut data, results, seats).await?;
+        }
+        VotingMethod::Plurality | VotingMethod::Approval => {
+            let results = plurality_totals(pool, contest_id).await?;
+            declare_top_n(pool, &tie_breaker, &mut data, results, seats).await?;
+        }
+        VotingMethod::Ranked => {
+            let candidates = load_candidates(pool, contest_id).await?;
+            let ballots = load_ranked_ballots(pool, contest_id).await?;
+            let candidate_ids: Vec<Uuid> = candidates.iter().map(|(id, _)| *id).collect();
+
+            data["results"] = serde_json::Value::Array(first_preferences(&candidates, &ballots));
+
+            if seats == 1 {
+                let (runoff, tie_breaks) = settle_ties(
+                    pool,
+                    &tie_breaker,
+                    |decisions| tally::irv::tabulate(&candidate_ids, &ballots, decisions),
+                    |runoff| runoff.pending_tie.as_ref(),
+                )
+                .await?;
+                data["winner"] = match runoff.winner {
+                    Some(id) => candidate_json(&candidates, id),
+                    None => serde_json::Value::Null,
+                };
+                data["tabulation"] = serde_json::json!({
+                    "method": "instant_runoff",
+                    "total_ballots": runoff.total_ballots,
+                    "rounds": runoff.rounds
+                });
+                data["tie_breaks"] = serde_json::json!(tie_breaks);
+            } else {
+                let (stv, tie_breaks) = settle_ties(
+                    pool,
+                    &tie_breaker,
+                    |decisions| tally::stv::tabulate(&candidate_ids, &ballots, seats, decisions),
+                    |stv| stv.pending_tie.as_ref(),
+                )
+                .await?;
+                data["elected"] = serde_json::Value::Array(
+                    stv.elected
+                        .iter()
+                        .map(|id| candidate_json(&candidates, *id))
+                        .collect(),
+                );
+                data["tabulation"] = serde_json::json!({
+                    "method": "single_transferable_vote",
+                    "seats": stv.seats,
+                    "quota": stv.quota,
+                    "total_ballots": stv.total_ballots,
+                    "rounds": stv.rounds
+                });
+                data["tie_breaks"] = serde_json::json!(tie_breaks);
+            }
+        }
+        VotingMethod::Score => {
+            let results = score_totals(pool, contest_id).await?;
+            declare_top_n(pool, &tie_breaker, &mut data, results, seats).await?;
+        }
+        VotingMethod::Cumulative | VotingMethod::Quadratic => {
+            let results = points_totals(pool, contest_id).await?;
+            declare_top_n(pool, &tie_breaker, &mut data, results, seats).await?;
+        }
+        VotingMethod::Schulze => {
+            let candidates = load_candidates(pool, contest_id).await?;
+            let ballots = load_ranked_ballots(pool, contest_id).await?;
+            let candidate_ids: Vec<Uuid> = candidates.iter().map(|(id, _)| *id).collect();
+            let schulze = tally::schulze::tabulate(&candidate_ids, &ballots);
+
+            data["results"] = serde_json::Value::Array(first_preferences(&candidates, &ballots));
+            data["winners"] = serde_json::Value::Array(
+                schulze
+                    .winners
+                    .iter()
+                    .map(|id| candidate_json(&candidates, *id))
+                    .collect(),
+            );
+
+            // Whole tiers fill the seats in ranking order; a tier that would overrun them is tied.
+            let mut elected: Vec<Uuid> = Vec::new();
+            let mut tied: Vec<Uuid> = Vec::new();
+            for tier in &schulze.ranking {
+                if elected.len() + tier.len() > seats {
+                    tied = tier.clone();
+                    break;
+                }
+                elected.extend(tier);
+            }
+            apply_tie_break(pool, &tie_breaker, &mut data, &mut elected, &tied, seats).await?;
+            data["elected"] = serde_json::Value::Array(
+                elected
+                    .iter()
+                    .map(|id| candidate_json(&candidates, *id))
+                    .collect(),
+            );
+            data["tied"] = serde_json::json!(tied);
+            data["unfilled_seats"] = serde_json::json!(seats - elected.len());
+            data["tabulation"] = serde_json::json!({
+                "method": "schulze",
+                "total_ballots": ballots.iter().map(|b| b.weight).sum::<u64>(),
+                "candidates": schulze.candidates,
+                "pairwise": schulze.pairwise,
+                "strongest_paths": schulze.strongest_paths,
+                "ranking": schulze.ranking
+            });
+        }
+        VotingMethod::Referendum => {
+            let threshold = row
+                .6
+                .as_deref()
+                .and_then(PassThreshold::from_db)
+                .unwrap_or(PassThreshold::SimpleMajority);
+            let totals = referendum_totals(pool, contest_id).await?;
+            let count = |response: ReferendumResponse| {
+                totals
+                    .iter()
+                    .find(|(r, _)| r == response.as_str())
+                    .map(|(_, total)| *total)
+                    .unwrap_or(0)
+            };
+            let (yes, no) = (
+                count(ReferendumResponse::Yes),
+                count(ReferendumResponse::No),
+            );
+            let eligible_voters = sqlx::query_scalar::<_, i64>(
+                "SELECT COALESCE(SUM(weight), 0)::bigint FROM voter_rolls WHERE contest_id = $1",
+            )
+            .bind(contest_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|_| AppError::Internal)?;
+
+            data["results"] = serde_json::Value::Array(
+                ReferendumResponse::ALL
+                    .iter()
+                    .map(|response| {
+                        serde_json::json!({ "response": response, "total": count(*response) })
+                    })
+                    .collect(),
+            );
+            data["ballots"] = serde_json::json!(totals.iter().map(|(_, total)| total).sum::<i64>());
+            data["eligible_voters"] = serde_json::json!(eligible_voters);
+            data["pass_threshold"] = serde_json::json!(threshold);
+            let ballots = totals.iter().map(|(_, total)| total).sum::<i64>() + blank_ballots;
+            data["passed"] =
+                serde_json::json!(referendum_passes(threshold, yes, no, ballots, eligible_voters));
+        }
+        VotingMethod::PartyList => {
+            let method = row
+                .7
+                .as_deref()
+                .and_then(AllocationMethod::from_db)
+                .unwrap_or(AllocationMethod::Dhondt);
+            let parties = party_totals(pool, contest_id).await?;
+            let lists = load_party_lists(pool, contest_id).await?;
+            let votes: Vec<(Uuid, u64)> = parties
+                .iter()
+                .map(|(id, _, total)| (*id, *total as u64))
+                .collect();
+            let (allocation, tie_breaks) = settle_ties(
+                pool,
+                &tie_breaker,
+                |decisions| tally::highest_averages::allocate(method, &votes, seats, decisions),
+                |allocation| allocation.pending_tie.as_ref(),
+            )
+            .await?;
+            let party_name = |party_id: Uuid| {
+                parties
+                    .iter()
+                    .find(|(id, _, _)| *id == party_id)
+                    .map(|(_, name, _)| name.as_str())
+            };
+
+            let mut results: Vec<_> = parties.iter().collect();
+            results.sort_by_key(|(_, _, total)| std::cmp::Reverse(*total));
+            data["results"] = serde_json::Value::Array(
+                results
+                    .into_iter()
+                    .map(|(party_id, name, total)| {
+                        serde_json::json!({ "party_id": party_id, "name": name, "total": total })
+                    })
+                    .collect(),
+            );
+
+            let filled = fill_lists(&allocation.parties, &lists);
+            let elected: Vec<serde_json::Value> = filled
+                .iter()
+                .flat_map(|(candidates, _)| candidates)
+                .map(|(candidate_id, name, party_id)| {
+                    serde_json::json!({
+                        "candidate_id": candidate_id,
+                        "name": name,
+                        "party_id": party_id
+                    })
+                })
+                .collect();
+            data["unfilled_seats"] = serde_json::json!(seats - elected.len());
+            data["elected"] = serde_json::Value::Array(elected);
+            data["allocation"] = serde_json::json!({
+                "method": allocation.method,
+                "seats": allocation.seats,
+                "total_votes": allocation.total_votes,
+                "parties": allocation
+                    .parties
+                    .iter()
+                    .zip(&filled)
+                    .map(|(party, (_, unfilled_seats))| {
+                        serde_json::json!({
+                            "party_id": party.party_id,
+                            "name": party_name(party.party_id),
+                            "votes": party.votes,
+                            "seats": party.seats,
+                            "unfilled_seats": unfilled_seats,
+                            "quotients": party.quotients
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+                "awards": allocation.awards
+            });
+            data["tie_breaks"] = serde_json::json!(tie_breaks);
+        }
+    }
+
+    Ok(data)
+}
+
+// Turnout counts voters with a receipt (blank ballots included) against the contest's roll,
+// regardless of voter weight. Results below either quorum are reported as not valid.
+pub async fn turnout(pool: &PgPool, contest_id: Uuid) -> Result<serde_json::Value, AppError> {
+    let (quorum_ballots, quorum_percent, eligible_voters, ballots_cast) =
+        sqlx::query_as::<_, (Option<i32>, Option<f64>, i64, i64)>(
+            r#"
+            SELECT
+              c.quorum_ballots,
+              c.quorum_percent,
+              (SELECT COUNT(*) FROM voter_rolls vr WHERE vr.contest_id = c.id),
+              (
+                SELECT COUNT(*)
+                FROM vote_receipts r
+                WHERE r.contest_id = c.id AND r.superseded_at IS NULL
+              )
+            FROM contests c
+            WHERE c.id = $1
+            "#,
+        )
+        .bind(contest_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| AppError::Internal)?
+        .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
+
+    let percent = if eligible_voters > 0 {
+        (ballots_cast as f64 / eligible_voters as f64 * 10_000.0).round() / 100.0
+    } else {
+        0.0
+    };
+    let quorum_met = quorum_ballots.is_none_or(|minimum| ballots_cast >= minimum as i64)
+        && quorum_percent
+            .is_none_or(|minimum| ballots_cast as f64 * 100.0 >= minimum * eligible_voters as f64);
+
+    Ok(serde_json::json!({
+        "contest_id": contest_id,
+        "eligible_voters": eligible_voters,
+        "ballots_cast": ballots_cast,
+        "percent": percent,
+        "quorum_ballots": quorum_ballots,
+        "quorum_percent": quorum_percent,
+        "quorum_met": quorum_met
+    }))
+}
+
+// Flags each result row as elected or tied and adds the declared winners for methods decided by
+// highest totals.
+async fn declare_top_n(
+    pool: &PgPool,
+    tie_breaker: &TieBreaker,
+    data: &mut serde_json::Value,
+    mut results: Vec<serde_json::Value>,
+    seats: usize,
+) -> Result<(), AppError> {
+    let totals: Vec<(Uuid, i64)> = results
+        .iter()
+        .filter_map(|item| {
+            let candidate_id = item["candidate_id"].as_str()?.parse().ok()?;
+            Some((candidate_id, item["total"].as_i64()?))
+        })
+        .collect();
+    let outcome = tally::top_n::declare(&totals, seats);
+    let mut winners = outcome.elected.clone();
+    apply_tie_break(pool, tie_breaker, data, &mut winners, &outcome.tied, seats).await?;
+
+    let mut elected = Vec::new();
+    for item in &mut results {
+        let Some(candidate_id) = item["candidate_id"].as_str().and_then(|id| id.parse().ok())
+        else {
+            continue;
+        };
+        let is_elected = winners.contains(&candidate_id);
+        item["elected"] = serde_json::json!(is_elected);
+        item["tied"] = serde_json::json!(outcome.tied.contains(&candidate_id));
+        if is_elected {
+            elected.push(serde_json::json!({ "candidate_id": candidate_id, "name": item["name"] }));
+        }
+    }
+
+    data["results"] = serde_json::Value::Array(results);
+    data["elected"] = serde_json::Value::Array(elected);
+    data["tied"] = serde_json::json!(outcome.tied);
+    data["unfilled_seats"] = serde_json::json!(seats - winners.len());
+    data["margin"] = serde_json::json!(outcome.margin);
+    Ok(())
+}
+
+// Seats left open by a tie are filled according to the election's tie-break policy.
+async fn apply_tie_break(
+    pool: &PgPool,
+    tie_breaker: &TieBreaker,
+    data: &mut serde_json::Value,
+    elected: &mut Vec<Uuid>,
+    tied: &[Uuid],
+    seats: usize,
+) -> Result<(), AppError> {
+    let unfilled = seats.saturating_sub(elected.len());
+    if tied.is_empty() || unfilled == 0 {
+        return Ok(());
+    }
+
+    let resolution = tie_breaker.resolve(pool, STAGE_WINNERS, tied, unfilled).await?;
+    elected.extend(resolution.elected);
+    data["tie_break"] = resolution.record;
+    Ok(())
+}
+
+// Tallies again with each tie the tally hands back settled by the election's tie-break policy, until
+// none is left or one is left for the officers. Returns the last tally and every tie's record.
+async fn settle_ties<T>(
+    pool: &PgPool,
+    tie_breaker: &TieBreaker,
+    tabulate: impl Fn(&tally::Decisions) -> T,
+    pending_tie: impl Fn(&T) -> Option<&tally::Tie>,
+) -> Result<(T, Vec<serde_json::Value>), AppError> {
+    let mut decisions = tally::Decisions::new();
+    let mut records = Vec::new();
+    loop {
+        let result = tabulate(&decisions);
+        let Some(tie) = pending_tie(&result) else {
+            return Ok((result, records));
+        };
+        if decisions.contains_key(&tie.stage) {
+            return Ok((result, records));
+        }
+
+        let resolution = tie_breaker
+            .resolve(pool, &tie.stage, &tie.tied, tie.seats)
+            .await?;
+        records.push(resolution.record);
+        if resolution.elected.is_empty() {
+            return Ok((result, records));
+        }
+        decisions.insert(tie.stage.clone(), resolution.elected);
+    }
+}
+
+async fn plurality_totals(
+    pool: &PgPool,
+    contest_id: Uuid,
+) -> Result<Vec<serde_json::Value>, AppError> {
+    let results = sqlx::query_as::<_, (Uuid, String, i64)>(
+        r#"
+        SELECT v.candidate_id, c.name, SUM(v.weight)::bigint as total
+        FROM candidate_marks v
+        JOIN candidates c ON c.id = v.candidate_id
+        WHERE v.contest_id = $1
+        GROUP BY v.candidate_id, c.name
+        ORDER BY total DESC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok(results
+        .into_iter()
+        .map(|(candidate_id, name, total)| serde_json::json!({ "candidate_id": candidate_id, "name": name, "total": total }))
+        .collect())
+}
+
+async fn score_totals(pool: &PgPool, contest_id: Uuid) -> Result<Vec<serde_json::Value>, AppError> {
+    let results = sqlx::query_as::<_, (Uuid, String, i64, i64)>(
+        r#"
+        SELECT v.candidate_id, c.name, COALESCE(SUM(v.score * v.weight), 0)::bigint AS total,
+               SUM(v.weight)::bigint
+        FROM candidate_marks v
+        JOIN candidates c ON c.id = v.candidate_id
+        WHERE v.contest_id = $1
+        GROUP BY v.candidate_id, c.name
+        ORDER BY total DESC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok(results
+        .into_iter()
+        .map(|(candidate_id, name, total, ballots)| {
+            let average = (total as f64 / ballots as f64 * 100.0).round() / 100.0;
+            serde_json::json!({
+                "candidate_id": candidate_id,
+                "name": name,
+                "total": total,
+                "ballots": ballots,
+                "average": average
+            })
+        })
+        .collect())
+}
+
+async fn points_totals(
+    pool: &PgPool,
+    contest_id: Uuid,
+) -> Result<Vec<serde_json::Value>, AppError> {
+    let results = sqlx::query_as::<_, (Uuid, String, i64)>(
+        r#"
+        SELECT v.candidate_id, c.name, SUM(COALESCE(v.points, 1) * v.weight)::bigint AS total
+        FROM candidate_marks v
+        JOIN candidates c ON c.id = v.candidate_id
+        WHERE v.contest_id = $1
+        GROUP BY v.candidate_id, c.name
+        ORDER BY total DESC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok(results
+        .into_iter()
+        .map(|(candidate_id, name, total)| {
+            serde_json::json!({ "candidate_id": candidate_id, "name": name, "total": total })
+        })
+        .collect())
+}
+
+async fn pending_write_ins(
+    pool: &PgPool,
+    contest_id: Uuid,
+) -> Result<Vec<serde_json::Value>, AppError> {
+    let rows = sqlx::query_as::<_, (String, i64)>(
+        r#"
+        SELECT MIN(name), SUM(COALESCE(points, 1) * weight)::bigint AS total
+        FROM write_ins
+        WHERE contest_id = $1 AND candidate_id IS NULL
+        GROUP BY normalized_name
+        ORDER BY total DESC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(name, total)| serde_json::json!({ "name": name, "total": total }))
+        .collect())
+}
+
+async fn referendum_totals(
+    pool: &PgPool,
+    contest_id: Uuid,
+) -> Result<Vec<(String, i64)>, AppError> {
+    sqlx::query_as::<_, (String, i64)>(
+        r#"
+        SELECT response, SUM(weight)::bigint
+        FROM votes
+        WHERE contest_id = $1 AND response IS NOT NULL
+        GROUP BY response
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)
+}
+
+// Abstentions count toward turnout but never toward the yes/no comparison. All arguments are
+// voter weights, and `ballots` includes abstentions and blank ballots. `QuorumOfEligible` is a
+// turnout quorum: more yes than no, with ballots from more than half of the roll's weight.
+fn referendum_passes(
+    threshold: PassThreshold,
+    yes: i64,
+    no: i64,
+    ballots: i64,
+    eligible_voters: i64,
+) -> bool {
+    match threshold {
+        PassThreshold::SimpleMajority => yes > no,
+        PassThreshold::TwoThirds => yes > 0 && yes * 3 >= (yes + no) * 2,
+        PassThreshold::QuorumOfEligible => yes > no && ballots * 2 > eligible_voters,
+    }
+}
+
+async fn party_totals(
+    pool: &PgPool,
+    contest_id: Uuid,
+) -> Result<Vec<(Uuid, String, i64)>, AppError> {
+    sqlx::query_as::<_, (Uuid, String, i64)>(
+        r#"
+        SELECT p.id, p.name, COALESCE(SUM(v.weight), 0)::bigint
+        FROM parties p
+        LEFT JOIN votes v ON v.party_id = p.id AND v.contest_id = $1
+        WHERE p.id IN (SELECT party_id FROM candidates WHERE contest_id = $1)
+        GROUP BY p.id, p.name, p.created_at
+        ORDER BY p.created_at ASC, p.id ASC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)
+}
+
+async fn load_party_lists(pool: &PgPool, contest_id: Uuid) -> Result<Vec<ListCandidate>, AppError> {
+    sqlx::query_as::<_, ListCandidate>(
+        r#"
+        SELECT id, name, party_id
+        FROM candidates
+        WHERE contest_id = $1 AND party_id IS NOT NULL
+        ORDER BY created_at ASC, id ASC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)
+}
+
+// A list candidate's id, name and party id.
+type ListCandidate = (Uuid, String, Uuid);
+
+// Each party's seats go to its list in order. Seats a party won beyond the length of its list are
+// reported as unfilled for that party rather than passed to another party.
+fn fill_lists<'a>(
+    parties: &[tally::highest_averages::PartyAllocation],
+    lists: &'a [ListCandidate],
+) -> Vec<(Vec<&'a ListCandidate>, usize)> {
+    parties
+        .iter()
+        .map(|party| {
+            let candidates: Vec<_> = lists
+                .iter()
+                .filter(|(_, _, party_id)| *party_id == party.party_id)
+                .take(party.seats)
+                .collect();
+            let unfilled_seats = party.seats - candidates.len();
+            (candidates, unfilled_seats)
+        })
+        .collect()
+}
+
+async fn load_candidates(pool: &PgPool, contest_id: Uuid) -> Result<Vec<(Uuid, String)>, AppError> {
+    sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT id, name FROM candidates WHERE contest_id = $1 ORDER BY created_at ASC, id ASC",
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)
+}
+
+async fn load_ranked_ballots(
+    pool: &PgPool,
+    contest_id: Uuid,
+) -> Result<Vec<tally::Ballot>, AppError> {
+    let rows = sqlx::query_as::<_, (Uuid, Uuid, i64)>(
+        r#"
+        SELECT ballot_id, candidate_id, weight
+        FROM candidate_marks
+        WHERE contest_id = $1
+        ORDER BY ballot_id ASC, rank ASC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let mut ballots: Vec<tally::Ballot> = Vec::new();
+    let mut current_ballot = None;
+    for (ballot_id, candidate_id, weight) in rows {
+        if current_ballot != Some(ballot_id) {
+            current_ballot = Some(ballot_id);
+            ballots.push(tally::Ballot {
+                ranking: Vec::new(),
+                weight: weight as u64,
+            });
+        }
+        if let Some(ballot) = ballots.last_mut() {
+            ballot.ranking.push(candidate_id);
+        }
+    }
+
+    Ok(ballots)
+}
+
+fn first_preferences(
+    candidates: &[(Uuid, String)],
+    ballots: &[tally::Ballot],
+) -> Vec<serde_json::Value> {
+    let mut totals: Vec<(Uuid, &str, u64)> = candidates
+        .iter()
+        .map(|(id, name)| (*id, name.as_str(), 0))
+        .collect();
+
+    for ballot in ballots {
+        let Some(first) = ballot.ranking.first() else {
+            continue;
+        };
+        if let Some(entry) = totals.iter_mut().find(|(id, _, _)| id == first) {
+            entry.2 += ballot.weight;
+        }
+    }
+
+    totals.retain(|(_, _, total)| *total > 0);
+    totals.sort_by_key(|(_, _, total)| std::cmp::Reverse(*total));
+    totals
+        .into_iter()
+        .map(|(candidate_id, name, total)| {
+            serde_json::json!({ "candidate_id": candidate_id, "name": name, "total": total })
+        })
+        .collect()
+}
+
+fn candidate_json(candidates: &[(Uuid, String)], candidate_id: Uuid) -> serde_json::Value {
+    let name = candidates
+        .iter()
+        .find(|(id, _)| *id == candidate_id)
+        .map(|(_, name)| name.as_str());
+
+    serde_json::json!({ "candidate_id": candidate_id, "name": name })
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{fill_lists, referendum_passes};
+    use crate::{domain::PassThreshold, tally::highest_averages::PartyAllocation};
+
+    #[test]
+    fn simple_majority_needs_more_yes_than_no() {
+        assert!(referendum_passes(PassThreshold::SimpleMajority, 6, 5, 11, 100));
+        assert!(!referendum_passes(PassThreshold::SimpleMajority, 5, 5, 10, 100));
+        assert!(!referendum_passes(PassThreshold::SimpleMajority, 4, 5, 9, 100));
+        assert!(!referendum_passes(PassThreshold::SimpleMajority, 0, 0, 0, 0));
+    }
+
+    #[test]
+    fn two_thirds_passes_at_exactly_two_thirds() {
+        assert!(referendum_passes(PassThreshold::TwoThirds, 2, 1, 3, 100));
+        assert!(referendum_passes(PassThreshold::TwoThirds, 7, 3, 10, 100));
+        assert!(!referendum_passes(PassThreshold::TwoThirds, 199, 101, 300, 400));
+        assert!(referendum_passes(PassThreshold::TwoThirds, 1, 0, 1, 0));
+        assert!(!referendum_passes(PassThreshold::TwoThirds, 0, 0, 0, 100));
+    }
+
+    #[test]
+    fn quorum_of_eligible_needs_turnout_from_more_than_half_the_roll() {
+        assert!(referendum_passes(PassThreshold::QuorumOfEligible, 30, 21, 51, 100));
+        assert!(!referendum_passes(PassThreshold::QuorumOfEligible, 30, 20, 50, 100));
+        // Abstentions and blank ballots count toward the quorum, not toward yes.
+        assert!(referendum_passes(PassThreshold::QuorumOfEligible, 10, 5, 60, 100));
+        assert!(!referendum_passes(PassThreshold::QuorumOfEligible, 30, 30, 90, 100));
+        assert!(!referendum_passes(PassThreshold::QuorumOfEligible, 0, 0, 0, 0));
+    }
+
+    #[test]
+    fn seats_beyond_a_party_list_stay_unfilled() {
+        let (short, full) = (Uuid::new_v4(), Uuid::new_v4());
+        let party = |party_id, seats| PartyAllocation {
+            party_id,
+            votes: 0,
+            seats,
+            quotients: Vec::new(),
+        };
+        let lists: Vec<(Uuid, String, Uuid)> = [(short, 2), (full, 3)]
+            .iter()
+            .flat_map(|(party_id, len)| {
+                (0..*len).map(|n| (Uuid::new_v4(), format!("List {}", n + 1), *party_id))
+            })
+            .collect();
+
+        let filled = fill_lists(&[party(short, 3), party(full, 1)], &lists);
+
+        assert_eq!(filled[0], (vec![&lists[0], &lists[1]], 1));
+        assert_eq!(filled[1], (vec![&lists[2]], 0));
+    }
+}
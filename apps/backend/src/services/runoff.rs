@@ -0,0 +1,327 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::{CreateElectionRequest, CreateRunoffRequest, TieBreakPolicy, VotingMethod},
+    errors::AppError,
+    services::{audit, election},
+};
+
+const DEFAULT_CANDIDATES: i32 = 2;
+
+// Builds a draft runoff election from a closed single-seat plurality contest whose leader did
+// not win a majority of valid ballots. The top `candidates` advance, plus anyone level with the
+// last qualifying place, and the contest's voter roll (with weights) is copied across. A contest
+// gets at most one runoff. Encrypted contests are counted from their decrypted totals.
+pub async fn create(
+    pool: &PgPool,
+    contest_id: Uuid,
+    officer_id: Uuid,
+    input: CreateRunoffRequest,
+) -> Result<serde_json::Value, AppError> {
+    let advance = input.candidates.unwrap_or(DEFAULT_CANDIDATES);
+    if advance < 2 {
+        return Err(AppError::BadRequest(
+            "candidates must be at least 2".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+
+    let (
+        election_id,
+        organization_id,
+        status,
+        tie_break_policy,
+        allow_revote,
+        encrypted,
+        title,
+        method,
+        seats,
+        max_selections,
+        order,
+    ) = sqlx::query_as::<
+        _,
+        (Uuid, Uuid, String, String, bool, bool, String, String, i32, i32, String),
+    >(
+        r#"
+        SELECT e.id, e.organization_id, e.status, e.tie_break_policy, e.allow_revote,
+               e.encrypted_ballots, c.title, c.voting_method, c.seats, c.max_selections,
+               c.ballot_order
+        FROM contests c
+        JOIN elections e ON e.id = c.election_id
+        WHERE c.id = $1
+        FOR UPDATE OF c
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
+
+    if status != "closed" {
+        return Err(AppError::Conflict(
+            "runoffs can only be created from closed elections".to_string(),
+        ));
+    }
+    if VotingMethod::from_db(&method) != Some(VotingMethod::Plurality) || seats != 1 {
+        return Err(AppError::BadRequest(
+            "runoffs are only supported for single-seat plurality contests".to_string(),
+        ));
+    }
+
+    // The contest row lock above keeps two requests from both passing this check.
+    let existing_runoff = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM contests WHERE metadata->>'runoff_of' = $1::text",
+    )
+    .bind(contest_id.to_string())
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if let Some(runoff_contest_id) = existing_runoff {
+        return Err(AppError::Conflict(format!(
+            "contest already has a runoff (contest {runoff_contest_id})"
+        )));
+    }
+
+    let pending_write_ins = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM write_ins WHERE contest_id = $1 AND candidate_id IS NULL",
+    )
+    .bind(contest_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if pending_write_ins > 0 {
+        return Err(AppError::Conflict(
+            "adjudicate pending write-ins before creating a runoff".to_string(),
+        ));
+    }
+
+    let (totals, valid_ballots) = if encrypted {
+        encrypted_totals(&mut tx, contest_id, max_selections).await?
+    } else {
+        plaintext_totals(&mut tx, contest_id).await?
+    };
+
+    let leader = totals.first().map(|(_, total)| *total).unwrap_or(0);
+    if valid_ballots == 0 {
+        return Err(AppError::Conflict(
+            "contest has no valid ballots".to_string(),
+        ));
+    }
+    if leader * 2 > valid_ballots {
+        return Err(AppError::Conflict(
+            "contest already has a majority winner".to_string(),
+        ));
+    }
+
+    let cutoff = totals.get(advance as usize - 1).map(|(_, total)| *total);
+    let advancing: Vec<Uuid> = totals
+        .iter()
+        .enumerate()
+        .filter(|(index, (_, total))| *index < advance as usize || Some(*total) == cutoff)
+        .map(|(_, (id, _))| *id)
+        .collect();
+    if advancing.len() < 2 {
+        return Err(AppError::Conflict(
+            "contest has fewer than two candidates".to_string(),
+        ));
+    }
+
+    let runoff_title = input
+        .title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| format!("{title} (runoff)"));
+    let (runoff_election_id, runoff_contest_id) = election::insert(
+        &mut tx,
+        CreateElectionRequest {
+            organization_id,
+            title: runoff_title.clone(),
+            description: None,
+            opens_at: input.opens_at,
+            closes_at: input.closes_at,
+            tie_break_policy: TieBreakPolicy::from_db(&tie_break_policy),
+            tie_break_seed: None,
+            allow_revote: Some(allow_revote),
+            encrypted_ballots: Some(encrypted),
+        },
+    )
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE contests
+        SET metadata = jsonb_build_object('runoff_of', $2::text), ballot_order = $3
+        WHERE id = $1
+        "#,
+    )
+    .bind(runoff_contest_id)
+    .bind(contest_id.to_string())
+    .bind(order)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO parties (id, election_id, name, abbreviation, created_at)
+        SELECT gen_random_uuid(), $1, p.name, p.abbreviation, p.created_at
+        FROM parties p
+        WHERE p.id IN (SELECT party_id FROM candidates WHERE id = ANY($2))
+        "#,
+    )
+    .bind(runoff_election_id)
+    .bind(&advancing)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    // created_at is carried over so registration-order tie breaks keep the original order.
+    let candidates = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO candidates (
+          id, election_id, contest_id, name, manifesto, party_id, ballot_position, created_at
+        )
+        SELECT gen_random_uuid(), $1, $2, c.name, c.manifesto, np.id, c.ballot_position,
+               c.created_at
+        FROM candidates c
+        LEFT JOIN parties op ON op.id = c.party_id
+        LEFT JOIN parties np ON np.election_id = $1 AND lower(np.name) = lower(op.name)
+        WHERE c.id = ANY($3)
+        RETURNING id
+        "#,
+    )
+    .bind(runoff_election_id)
+    .bind(runoff_contest_id)
+    .bind(&advancing)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let voters = sqlx::query(
+        r#"
+        INSERT INTO voter_rolls (id, election_id, contest_id, user_id, weight)
+        SELECT gen_random_uuid(), $1, $2, user_id, weight
+        FROM voter_rolls
+        WHERE contest_id = $3
+        "#,
+    )
+    .bind(runoff_election_id)
+    .bind(runoff_contest_id)
+    .bind(contest_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .rows_affected();
+
+    audit::record(
+        &mut tx,
+        "runoff_created",
+        officer_id,
+        election_id,
+        serde_json::json!({
+            "contest_id": contest_id,
+            "runoff_election_id": runoff_election_id,
+            "runoff_contest_id": runoff_contest_id,
+            "advancing": advancing,
+            "leader_votes": leader,
+            "valid_ballots": valid_ballots,
+            "voters": voters
+        }),
+    )
+    .await?;
+
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+
+    Ok(serde_json::json!({
+        "election_id": runoff_election_id,
+        "contest_id": runoff_contest_id,
+        "title": runoff_title,
+        "candidates": candidates,
+        "voters": voters
+    }))
+}
+
+// Candidate totals, highest first with registration order between equals, and the weight of valid
+// ballots. Blank ballots are not valid votes for the majority test.
+async fn plaintext_totals(
+    tx: &mut Transaction<'_, Postgres>,
+    contest_id: Uuid,
+) -> Result<(Vec<(Uuid, i64)>, i64), AppError> {
+    let totals = sqlx::query_as::<_, (Uuid, i64)>(
+        r#"
+        SELECT c.id, COALESCE(SUM(m.weight), 0)::bigint AS total
+        FROM candidates c
+        LEFT JOIN candidate_marks m ON m.candidate_id = c.id
+        WHERE c.contest_id = $1
+        GROUP BY c.id, c.created_at
+        ORDER BY total DESC, c.created_at ASC, c.id ASC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let valid_ballots = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COALESCE(SUM(weight), 0)::bigint
+        FROM (
+          SELECT DISTINCT ballot_id, weight FROM candidate_marks WHERE contest_id = $1
+        ) ballots
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok((totals, valid_ballots))
+}
+
+// The same from an encrypted contest's decrypted totals. Individual ballots are never opened, so
+// valid ballots are only known when each one marks at most one candidate: their weight is then
+// the sum of the totals.
+async fn encrypted_totals(
+    tx: &mut Transaction<'_, Postgres>,
+    contest_id: Uuid,
+    max_selections: i32,
+) -> Result<(Vec<(Uuid, i64)>, i64), AppError> {
+    if max_selections != 1 {
+        return Err(AppError::BadRequest(
+            "runoffs from encrypted contests need max_selections of 1".to_string(),
+        ));
+    }
+
+    let decrypted = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM encrypted_tallies WHERE contest_id = $1)",
+    )
+    .bind(contest_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if !decrypted {
+        return Err(AppError::Conflict(
+            "decrypt the contest before creating a runoff".to_string(),
+        ));
+    }
+
+    let totals = sqlx::query_as::<_, (Uuid, i64)>(
+        r#"
+        SELECT c.id, COALESCE(t.total, 0)::bigint AS total
+        FROM candidates c
+        LEFT JOIN encrypted_tallies t ON t.candidate_id = c.id
+        WHERE c.contest_id = $1
+        ORDER BY total DESC, c.created_at ASC, c.id ASC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    let valid_ballots = totals.iter().map(|(_, total)| total).sum();
+
+    Ok((totals, valid_ballots))
+}
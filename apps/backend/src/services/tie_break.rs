@@ -0,0 +1,148 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{domain::TieBreakPolicy, errors::AppError, services::audit, tally};
+
+// Stage of a tie at the seat cutoff of a top-N or Schulze result. Ties the tallies hand back are
+// staged per IRV/STV round (`round_3`) or highest-averages seat (`seat_4`).
+pub const STAGE_WINNERS: &str = "winners";
+
+pub struct TieBreaker {
+    pub election_id: Uuid,
+    pub contest_id: Uuid,
+    // Who the draw is recorded for. Reading results leaves it unset and writes nothing.
+    pub recorded_by: Option<Uuid>,
+    pub policy: TieBreakPolicy,
+    pub seed: Option<String>,
+    // The contest's closing bulletin root. It only exists once voting has ended, so the seed
+    // fixed at publish cannot have been picked with the draw in mind.
+    pub entropy: Option<String>,
+}
+
+pub struct Resolution {
+    pub elected: Vec<Uuid>,
+    pub record: serde_json::Value,
+}
+
+impl TieBreaker {
+    // Fills `seats` from the `tied` candidates (or parties). When recording, the outcome is stored
+    // once per contest and stage, and again only if a later computation (e.g. after write-in
+    // adjudication) changes it.
+    pub async fn resolve(
+        &self,
+        pool: &PgPool,
+        stage: &str,
+        tied: &[Uuid],
+        seats: usize,
+    ) -> Result<Resolution, AppError> {
+        let draw_seed = self
+            .seed
+            .as_deref()
+            .map(|seed| tally::lot::mix(seed, self.entropy.as_deref()));
+        let drawn = match (self.policy, draw_seed.as_deref()) {
+            (TieBreakPolicy::Lot, Some(seed)) => tally::lot::draw(seed, self.contest_id, tied),
+            (TieBreakPolicy::RegistrationOrder, _) => sqlx::query_scalar::<_, Uuid>(
+                r#"
+                SELECT id
+                FROM (
+                  SELECT id, created_at FROM candidates WHERE contest_id = $1
+                  UNION ALL
+                  SELECT p.id, p.created_at
+                  FROM parties p
+                  WHERE p.id IN (SELECT party_id FROM candidates WHERE contest_id = $1)
+                ) registered
+                WHERE id = ANY($2)
+                ORDER BY created_at ASC, id ASC
+                "#,
+            )
+            .bind(self.contest_id)
+            .bind(tied)
+            .fetch_all(pool)
+            .await
+            .map_err(|_| AppError::Internal)?,
+            _ => Vec::new(),
+        };
+        let policy = if drawn.is_empty() {
+            TieBreakPolicy::Manual
+        } else {
+            self.policy
+        };
+        let elected: Vec<Uuid> = drawn.iter().take(seats).copied().collect();
+
+        let seed_commitment = self.seed.as_deref().map(tally::lot::commitment);
+        let (seed, entropy) = if policy == TieBreakPolicy::Lot {
+            (self.seed.as_deref(), self.entropy.as_deref())
+        } else {
+            (None, None)
+        };
+        if let Some(actor_id) = self.recorded_by {
+            let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+            let changed = sqlx::query(
+                r#"
+                INSERT INTO tie_breaks (id, election_id, contest_id, stage, policy, tied, drawn, seats)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (contest_id, stage) DO UPDATE
+                SET policy = EXCLUDED.policy, tied = EXCLUDED.tied, drawn = EXCLUDED.drawn,
+                    seats = EXCLUDED.seats, created_at = NOW()
+                WHERE (tie_breaks.policy, tie_breaks.tied, tie_breaks.drawn, tie_breaks.seats)
+                  IS DISTINCT FROM (EXCLUDED.policy, EXCLUDED.tied, EXCLUDED.drawn, EXCLUDED.seats)
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(self.election_id)
+            .bind(self.contest_id)
+            .bind(stage)
+            .bind(policy.as_str())
+            .bind(serde_json::json!(tied))
+            .bind(serde_json::json!(drawn))
+            .bind(seats as i32)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| AppError::Internal)?
+            .rows_affected();
+
+            if changed > 0 {
+                let event_type = if policy == TieBreakPolicy::Manual {
+                    "tie_break_flagged"
+                } else {
+                    "tie_break_drawn"
+                };
+                audit::record(
+                    &mut tx,
+                    event_type,
+                    actor_id,
+                    self.election_id,
+                    serde_json::json!({
+                        "contest_id": self.contest_id,
+                        "stage": stage,
+                        "policy": policy,
+                        "seats": seats,
+                        "tied": tied,
+                        "drawn": drawn,
+                        "seed_commitment": seed_commitment,
+                        "seed": seed,
+                        "entropy": entropy
+                    }),
+                )
+                .await?;
+            }
+            tx.commit().await.map_err(|_| AppError::Internal)?;
+        }
+
+        Ok(Resolution {
+            record: serde_json::json!({
+                "stage": stage,
+                "policy": policy,
+                "status": if policy == TieBreakPolicy::Manual { "pending" } else { "resolved" },
+                "seats": seats,
+                "tied": tied,
+                "drawn": drawn,
+                "elected": elected,
+                "seed_commitment": seed_commitment,
+                "seed": seed,
+                "entropy": entropy
+            }),
+            elected,
+        })
+    }
+}
@@ -3,12 +3,36 @@ use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 use crate::{
-    domain::{CastVoteRequest, VoteReceiptResponse},
+    domain::{
+        BallotOptionInput, BallotReceiptResponse, CastVoteRequest, EncryptedBallotInput,
+        ReceiptStatus, SubmitBallotRequest, VoteReceiptResponse, VotingMethod,
+    },
     errors::AppError,
+    services::{
+        audit,
+        ballot_queue::{self, QueuedBallot, QueuedCiphertext, QueuedMark},
+        bulletin, encrypted_tally, write_in,
+    },
 };
 
+struct OpenContest {
+    election_id: Uuid,
+    max_selections: i32,
+    min_selections: i32,
+    voting_method: VotingMethod,
+    score_min: Option<i32>,
+    score_max: Option<i32>,
+    total_points: Option<i32>,
+    allow_blank: bool,
+    allow_write_ins: bool,
+    allow_revote: bool,
+    encrypted_ballots: bool,
+    encryption_public_key: Option<String>,
+}
+
 pub async fn cast(
     pool: &PgPool,
+    link_key: &str,
     election_id: Uuid,
     voter_id: Uuid,
     input: CastVoteRequest,
@@ -22,47 +46,378 @@ pub async fn cast(
     .map_err(|_| AppError::Internal)?
     .ok_or_else(|| AppError::NotFound("default contest not found".to_string()))?;
 
-    cast_contest(pool, default_contest_id, voter_id, input).await
+    cast_contest(pool, link_key, default_contest_id, voter_id, input).await
 }
 
 pub async fn cast_contest(
     pool: &PgPool,
+    link_key: &str,
     contest_id: Uuid,
     voter_id: Uuid,
     input: CastVoteRequest,
 ) -> Result<VoteReceiptResponse, AppError> {
-    if input.selections.is_empty() {
-        return Err(AppError::BadRequest("selections cannot be empty".to_string()));
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+    let receipt = record(&mut tx, link_key, contest_id, voter_id, &input, None).await?;
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+    flush_queue(pool, link_key, contest_id).await;
+
+    Ok(receipt)
+}
+
+// The vote is already recorded when this runs; a batch that fails to move stays queued for the
+// next cast or for close.
+async fn flush_queue(pool: &PgPool, link_key: &str, contest_id: Uuid) {
+    if let Err(err) = ballot_queue::flush(pool, link_key, contest_id).await {
+        tracing::warn!("moving queued ballots for contest {} failed: {}", contest_id, err);
     }
+}
+
+// What the server needs to encrypt a voter's choices in an encrypted contest.
+pub(crate) struct EncryptionTarget {
+    pub public_key: String,
+    pub allowed_counts: Vec<u64>,
+}
 
-    let mut seen = std::collections::HashSet::new();
-    for selection in &input.selections {
-        if !seen.insert(selection.candidate_id) {
+// Checks that the voter could cast `selections` in this contest right now, so that a ballot
+// prepared for them would be accepted when cast.
+pub(crate) async fn encryption_target(
+    tx: &mut Transaction<'_, Postgres>,
+    contest_id: Uuid,
+    voter_id: Uuid,
+    selections: Vec<BallotOptionInput>,
+) -> Result<EncryptionTarget, AppError> {
+    let contest = ensure_contest_open(tx, contest_id).await?;
+    ensure_voter_eligible(tx, contest_id, voter_id).await?;
+
+    let public_key = match (contest.encrypted_ballots, &contest.encryption_public_key) {
+        (true, Some(public_key)) => public_key.clone(),
+        _ => {
             return Err(AppError::BadRequest(
-                "selections cannot contain duplicates".to_string(),
-            ));
+                "encrypted ballots are not enabled for this election".to_string(),
+            ))
         }
+    };
+    ensure_encryptable(&contest)?;
+    validate_selections(
+        &contest,
+        &CastVoteRequest {
+            idempotency_key: String::new(),
+            selections,
+            response: None,
+            party_id: None,
+            encrypted: None,
+        },
+    )?;
+
+    Ok(EncryptionTarget {
+        public_key,
+        allowed_counts: allowed_counts(&contest),
+    })
+}
+
+// Casts a ballot the server prepared for the voter. The prepared row, and with it the
+// randomness that could open the ballot, is deleted in the same transaction.
+pub async fn cast_prepared(
+    pool: &PgPool,
+    link_key: &str,
+    contest_id: Uuid,
+    voter_id: Uuid,
+    prepared_ballot_id: Uuid,
+    idempotency_key: String,
+) -> Result<VoteReceiptResponse, AppError> {
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+
+    if let Some(existing_receipt_id) =
+        fetch_receipt_by_idempotency(&mut tx, contest_id, voter_id, &idempotency_key).await?
+    {
+        let election_id =
+            sqlx::query_scalar::<_, Uuid>("SELECT election_id FROM contests WHERE id = $1")
+                .bind(contest_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|_| AppError::Internal)?;
+        let receipt = load_receipt(&mut tx, link_key, existing_receipt_id, election_id, contest_id).await?;
+        tx.commit().await.map_err(|_| AppError::Internal)?;
+        return Ok(receipt);
+    }
+
+    let ballot = sqlx::query_scalar::<_, serde_json::Value>(
+        r#"
+        DELETE FROM prepared_ballots
+        WHERE id = $1 AND contest_id = $2 AND voter_id = $3
+        RETURNING ballot
+        "#,
+    )
+    .bind(prepared_ballot_id)
+    .bind(contest_id)
+    .bind(voter_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .ok_or_else(|| AppError::NotFound("prepared ballot not found".to_string()))?;
+    let encrypted: EncryptedBallotInput =
+        serde_json::from_value(ballot).map_err(|_| AppError::Internal)?;
+
+    let input = CastVoteRequest {
+        idempotency_key,
+        selections: Vec::new(),
+        response: None,
+        party_id: None,
+        encrypted: Some(encrypted),
+    };
+    let receipt = record(&mut tx, link_key, contest_id, voter_id, &input, None).await?;
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+    flush_queue(pool, link_key, contest_id).await;
+
+    Ok(receipt)
+}
+
+// Casts every contest of a multi-contest ballot in one transaction: either all contests are
+// recorded under one ballot receipt or none are. Errors name the contest that caused them.
+pub async fn cast_ballot(
+    pool: &PgPool,
+    link_key: &str,
+    election_id: Uuid,
+    voter_id: Uuid,
+    input: SubmitBallotRequest,
+) -> Result<BallotReceiptResponse, AppError> {
+    if input.contests.is_empty() {
+        return Err(AppError::BadRequest("contests cannot be empty".to_string()));
+    }
+    let mut contest_ids: Vec<Uuid> = input.contests.iter().map(|c| c.contest_id).collect();
+    contest_ids.sort();
+    contest_ids.dedup();
+    if contest_ids.len() != input.contests.len() {
+        return Err(AppError::BadRequest(
+            "each contest can appear only once".to_string(),
+        ));
     }
 
     let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
 
-    let (election_id, max_selections) = ensure_contest_open(&mut tx, contest_id).await?;
-    ensure_voter_eligible(&mut tx, contest_id, voter_id).await?;
+    let existing = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT id
+        FROM ballot_receipts
+        WHERE election_id = $1 AND voter_id = $2 AND idempotency_key = $3
+        "#,
+    )
+    .bind(election_id)
+    .bind(voter_id)
+    .bind(&input.idempotency_key)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if let Some(ballot_receipt_id) = existing {
+        let receipt = load_ballot_receipt(&mut tx, link_key, ballot_receipt_id).await?;
+        tx.commit().await.map_err(|_| AppError::Internal)?;
+        return Ok(receipt);
+    }
 
-    if input.selections.len() > max_selections as usize {
+    // The ballot must cover exactly the contests the voter is on the roll for.
+    let eligible = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT c.id
+        FROM contests c
+        JOIN voter_rolls vr ON vr.contest_id = c.id
+        WHERE c.election_id = $1 AND vr.user_id = $2
+        "#,
+    )
+    .bind(election_id)
+    .bind(voter_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if let Some(extra) = contest_ids.iter().find(|id| !eligible.contains(id)) {
         return Err(AppError::BadRequest(format!(
-            "too many selections (max {})",
-            max_selections
+            "contest {} is not on this voter's ballot for the election",
+            extra
+        )));
+    }
+    if let Some(missing) = eligible.iter().find(|id| !contest_ids.contains(id)) {
+        return Err(AppError::BadRequest(format!(
+            "contest {} is missing from the ballot",
+            missing
         )));
     }
 
-    let candidate_ids: Vec<Uuid> = input.selections.iter().map(|s| s.candidate_id).collect();
+    let ballot_receipt_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO ballot_receipts (id, election_id, voter_id, idempotency_key)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(ballot_receipt_id)
+    .bind(election_id)
+    .bind(voter_id)
+    .bind(&input.idempotency_key)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505") => {
+            AppError::Conflict("ballot is already being submitted".to_string())
+        }
+        _ => AppError::Internal,
+    })?;
+
+    for contest in input.contests {
+        let contest_id = contest.contest_id;
+        let cast = CastVoteRequest {
+            idempotency_key: input.idempotency_key.clone(),
+            selections: contest.selections,
+            response: contest.response,
+            party_id: contest.party_id,
+            encrypted: contest.encrypted,
+        };
+        record(&mut tx, link_key, contest_id, voter_id, &cast, Some(ballot_receipt_id))
+            .await
+            .map_err(|err| match err {
+                AppError::BadRequest(message) => {
+                    AppError::BadRequest(format!("contest {}: {}", contest_id, message))
+                }
+                AppError::Conflict(message) => {
+                    AppError::Conflict(format!("contest {}: {}", contest_id, message))
+                }
+                AppError::NotFound(message) => {
+                    AppError::NotFound(format!("contest {}: {}", contest_id, message))
+                }
+                err => err,
+            })?;
+    }
+
+    audit::record(
+        &mut tx,
+        "ballot_cast",
+        voter_id,
+        election_id,
+        serde_json::json!({
+            "ballot_receipt_id": ballot_receipt_id,
+            "contests": contest_ids
+        }),
+    )
+    .await?;
+
+    let receipt = load_ballot_receipt(&mut tx, link_key, ballot_receipt_id).await?;
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+    for contest_id in contest_ids {
+        flush_queue(pool, link_key, contest_id).await;
+    }
+
+    Ok(receipt)
+}
+
+// Loads a voter's own ballot receipt with its per-contest receipts.
+pub async fn ballot_receipt(
+    pool: &PgPool,
+    link_key: &str,
+    election_id: Uuid,
+    voter_id: Uuid,
+    ballot_receipt_id: Uuid,
+) -> Result<BallotReceiptResponse, AppError> {
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+    let owned = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM ballot_receipts WHERE id = $1 AND election_id = $2 AND voter_id = $3",
+    )
+    .bind(ballot_receipt_id)
+    .bind(election_id)
+    .bind(voter_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+    if owned == 0 {
+        return Err(AppError::NotFound("receipt not found".to_string()));
+    }
+
+    let receipt = load_ballot_receipt(&mut tx, link_key, ballot_receipt_id).await?;
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+
+    Ok(receipt)
+}
+
+async fn load_ballot_receipt(
+    tx: &mut Transaction<'_, Postgres>,
+    link_key: &str,
+    ballot_receipt_id: Uuid,
+) -> Result<BallotReceiptResponse, AppError> {
+    let (election_id, submitted_at) = sqlx::query_as::<_, (Uuid, chrono::DateTime<Utc>)>(
+        "SELECT election_id, created_at FROM ballot_receipts WHERE id = $1",
+    )
+    .bind(ballot_receipt_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let rows = sqlx::query_as::<_, (Uuid, Uuid)>(
+        r#"
+        SELECT r.id, r.contest_id
+        FROM vote_receipts r
+        JOIN contests c ON c.id = r.contest_id
+        WHERE r.ballot_receipt_id = $1
+        ORDER BY c.is_default DESC, c.created_at ASC
+        "#,
+    )
+    .bind(ballot_receipt_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let mut contests = Vec::with_capacity(rows.len());
+    for (receipt_id, contest_id) in rows {
+        contests.push(load_receipt(tx, link_key, receipt_id, election_id, contest_id).await?);
+    }
+
+    Ok(BallotReceiptResponse {
+        ballot_receipt_id,
+        election_id,
+        submitted_at,
+        contests,
+    })
+}
+
+// Records one contest's ballot inside the caller's transaction. `ballot_receipt_id` links it to
+// a multi-contest submission.
+//
+// The receipt records who took part; the ballot and its marks record what was chosen. Nothing
+// stored joins the two: the ballot is queued sealed and only written to `ballots` by a later
+// batch in its own transaction (see `ballot_queue`), ballot ids are random, ballot rows carry no
+// timestamps, and the only link is the keyed `revote_tag`, written just when the election allows
+// revoting.
+async fn record(
+    tx: &mut Transaction<'_, Postgres>,
+    link_key: &str,
+    contest_id: Uuid,
+    voter_id: Uuid,
+    input: &CastVoteRequest,
+    ballot_receipt_id: Option<Uuid>,
+) -> Result<VoteReceiptResponse, AppError> {
+    let contest = ensure_contest_open(tx, contest_id).await?;
+    let election_id = contest.election_id;
+    let weight = ensure_voter_eligible(tx, contest_id, voter_id).await?;
+
+    if contest.encrypted_ballots {
+        validate_encrypted(&contest, input)?;
+    } else if input.encrypted.is_some() {
+        return Err(AppError::BadRequest(
+            "encrypted ballots are not enabled for this election".to_string(),
+        ));
+    } else {
+        validate_selections(&contest, input)?;
+    }
+
+    let mut candidate_ids: Vec<Uuid> = input
+        .selections
+        .iter()
+        .filter_map(|s| s.candidate_id)
+        .collect();
+    candidate_ids.sort();
+    candidate_ids.dedup();
     let valid_candidates = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM candidates WHERE contest_id = $1 AND id = ANY($2)",
     )
     .bind(contest_id)
     .bind(&candidate_ids)
-    .fetch_one(&mut *tx)
+    .fetch_one(&mut **tx)
     .await
     .map_err(|_| AppError::Internal)?;
 
@@ -72,46 +427,89 @@ pub async fn cast_contest(
         ));
     }
 
-    if let Some(existing_receipt_id) =
-        fetch_receipt_by_idempotency(&mut tx, contest_id, voter_id, &input.idempotency_key).await?
-    {
-        let row = sqlx::query_as::<_, (Uuid, chrono::DateTime<Utc>)>(
-            "SELECT id, created_at FROM vote_receipts WHERE id = $1",
+    if let Some(party_id) = input.party_id {
+        let party_on_ballot = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM candidates WHERE contest_id = $1 AND party_id = $2",
         )
-        .bind(existing_receipt_id)
-        .fetch_one(&mut *tx)
+        .bind(contest_id)
+        .bind(party_id)
+        .fetch_one(&mut **tx)
         .await
         .map_err(|_| AppError::Internal)?;
 
-        tx.commit().await.map_err(|_| AppError::Internal)?;
-        return Ok(VoteReceiptResponse {
-            receipt_id: row.0,
-            election_id,
-            contest_id,
-            submitted_at: row.1,
-        });
+        if party_on_ballot == 0 {
+            return Err(AppError::BadRequest(
+                "party_id is not on this ballot".to_string(),
+            ));
+        }
+    }
+
+    if let Some(existing_receipt_id) =
+        fetch_receipt_by_idempotency(tx, contest_id, voter_id, &input.idempotency_key).await?
+    {
+        return load_receipt(tx, link_key, existing_receipt_id, election_id, contest_id).await;
     }
 
-    let duplicate_vote = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM vote_receipts WHERE contest_id = $1 AND voter_id = $2",
+    // Locking the current receipt makes concurrent revotes by the same voter take turns.
+    let previous_receipt_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT id
+        FROM vote_receipts
+        WHERE contest_id = $1 AND voter_id = $2 AND superseded_at IS NULL
+        FOR UPDATE
+        "#,
     )
     .bind(contest_id)
     .bind(voter_id)
-    .fetch_one(&mut *tx)
+    .fetch_optional(&mut **tx)
     .await
     .map_err(|_| AppError::Internal)?;
 
-    if duplicate_vote > 0 {
-        return Err(AppError::Conflict(
-            "voter has already submitted vote".to_string(),
-        ));
+    if let Some(previous_receipt_id) = previous_receipt_id {
+        if !contest.allow_revote {
+            return Err(AppError::Conflict(
+                "voter has already submitted vote".to_string(),
+            ));
+        }
+        supersede(tx, link_key, previous_receipt_id).await?;
     }
 
+    let encrypted_selections = match (&input.encrypted, &contest.encryption_public_key) {
+        (Some(encrypted), Some(public_key)) => {
+            encrypted_tally::verify_ballot(
+                tx,
+                link_key,
+                contest_id,
+                voter_id,
+                public_key,
+                &allowed_counts(&contest),
+                encrypted,
+            )
+            .await?
+        }
+        (Some(_), None) => return Err(AppError::Internal),
+        (None, _) => Vec::new(),
+    };
+
+    let points_used = if contest.voting_method.uses_points() {
+        let used = points_used(contest.voting_method, &input.selections);
+        Some(i32::try_from(used).map_err(|_| {
+            AppError::BadRequest("too many points for one ballot".to_string())
+        })?)
+    } else {
+        None
+    };
+
+    let (nonce, ballot_commitment) = bulletin::commit(contest_id, input);
+
     let receipt_id = Uuid::new_v4();
     if let Err(err) = sqlx::query(
         r#"
-        INSERT INTO vote_receipts (id, election_id, contest_id, voter_id, idempotency_key)
-        VALUES ($1, $2, $3, $4, $5)
+        INSERT INTO vote_receipts (
+          id, election_id, contest_id, voter_id, idempotency_key, ballot_receipt_id,
+          sealed_commitment
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         "#,
     )
     .bind(receipt_id)
@@ -119,7 +517,9 @@ pub async fn cast_contest(
     .bind(contest_id)
     .bind(voter_id)
     .bind(&input.idempotency_key)
-    .execute(&mut *tx)
+    .bind(ballot_receipt_id)
+    .bind(bulletin::seal_commitment(link_key, receipt_id, &ballot_commitment))
+    .execute(&mut **tx)
     .await
     {
         if let sqlx::Error::Database(db_err) = &err {
@@ -127,32 +527,19 @@ pub async fn cast_contest(
                 if db_err.constraint() == Some("vote_receipts_contest_id_voter_id_idempotency_key_key")
                 {
                     if let Some(existing_receipt_id) = fetch_receipt_by_idempotency(
-                        &mut tx,
+                        tx,
                         contest_id,
                         voter_id,
                         &input.idempotency_key,
                     )
                     .await?
                     {
-                        let row = sqlx::query_as::<_, (Uuid, chrono::DateTime<Utc>)>(
-                            "SELECT id, created_at FROM vote_receipts WHERE id = $1",
-                        )
-                        .bind(existing_receipt_id)
-                        .fetch_one(&mut *tx)
-                        .await
-                        .map_err(|_| AppError::Internal)?;
-
-                        tx.commit().await.map_err(|_| AppError::Internal)?;
-                        return Ok(VoteReceiptResponse {
-                            receipt_id: row.0,
-                            election_id,
-                            contest_id,
-                            submitted_at: row.1,
-                        });
+                        return load_receipt(tx, link_key, existing_receipt_id, election_id, contest_id)
+                            .await;
                     }
                 }
 
-                if db_err.constraint() == Some("vote_receipts_contest_id_voter_id_key") {
+                if db_err.constraint() == Some("uniq_vote_receipts_current") {
                     return Err(AppError::Conflict(
                         "voter has already submitted vote".to_string(),
                     ));
@@ -163,60 +550,162 @@ pub async fn cast_contest(
         return Err(AppError::Internal);
     }
 
-    for selection in &input.selections {
-        sqlx::query(
-            r#"
-            INSERT INTO votes (id, receipt_id, election_id, contest_id, candidate_id)
-            VALUES ($1, $2, $3, $4, $5)
-            "#,
-        )
-        .bind(Uuid::new_v4())
-        .bind(receipt_id)
-        .bind(election_id)
-        .bind(contest_id)
-        .bind(selection.candidate_id)
-        .execute(&mut *tx)
-        .await
-        .map_err(|_| AppError::Internal)?;
-    }
+    let marks = input
+        .selections
+        .iter()
+        .enumerate()
+        .map(|(position, selection)| QueuedMark {
+            candidate_id: selection.candidate_id,
+            write_in: selection.write_in.clone(),
+            rank: contest
+                .voting_method
+                .is_ranked()
+                .then_some(position as i32 + 1),
+            score: selection.score,
+            points: contest
+                .voting_method
+                .uses_points()
+                .then(|| selection.points.unwrap_or(1)),
+        })
+        .collect();
+    let ballot = QueuedBallot {
+        election_id,
+        weight,
+        allow_revote: contest.allow_revote,
+        points_used,
+        blank: is_blank(input),
+        response: input.response.map(|response| response.as_str().to_string()),
+        party_id: input.party_id,
+        marks,
+        selection_count_proof: input
+            .encrypted
+            .as_ref()
+            .map(|encrypted| serde_json::json!(encrypted.selection_count_proof)),
+        proof_nonce: input
+            .encrypted
+            .as_ref()
+            .map(|encrypted| encrypted.proof_nonce.clone()),
+        ciphertexts: encrypted_selections.iter().map(QueuedCiphertext::from).collect(),
+        commitment: Some(ballot_commitment.clone()),
+    };
+    ballot_queue::enqueue(tx, link_key, contest_id, receipt_id, &ballot).await?;
 
-    sqlx::query(
-        r#"
-        INSERT INTO audit_events (id, event_type, actor_id, election_id, metadata)
-        VALUES ($1, 'vote_cast', $2, $3, jsonb_build_object('receipt_id', $4, 'contest_id', $5))
-        "#,
+    audit::record(
+        tx,
+        "vote_cast",
+        voter_id,
+        election_id,
+        serde_json::json!({
+            "receipt_id": receipt_id,
+            "contest_id": contest_id,
+            "supersedes": previous_receipt_id
+        }),
+    )
+    .await?;
+
+    let submitted_at = sqlx::query_scalar::<_, chrono::DateTime<Utc>>(
+        "SELECT created_at FROM vote_receipts WHERE id = $1",
     )
-    .bind(Uuid::new_v4())
-    .bind(voter_id)
-    .bind(election_id)
     .bind(receipt_id)
-    .bind(contest_id)
-    .execute(&mut *tx)
+    .fetch_one(&mut **tx)
     .await
     .map_err(|_| AppError::Internal)?;
 
-    let submitted_at = sqlx::query_scalar::<_, chrono::DateTime<Utc>>(
-        "SELECT created_at FROM vote_receipts WHERE id = $1",
+    Ok(VoteReceiptResponse {
+        receipt_id,
+        election_id,
+        contest_id,
+        submitted_at,
+        points_used,
+        status: ReceiptStatus::Current,
+        superseded_at: None,
+        ballot_commitment: Some(ballot_commitment),
+        commitment_nonce: Some(nonce),
+        inclusion_proof: None,
+        signature: None,
+    })
+}
+
+async fn load_receipt(
+    tx: &mut Transaction<'_, Postgres>,
+    link_key: &str,
+    receipt_id: Uuid,
+    election_id: Uuid,
+    contest_id: Uuid,
+) -> Result<VoteReceiptResponse, AppError> {
+    let (submitted_at, superseded_at, ballot_commitment, sealed_commitment) = sqlx::query_as::<
+        _,
+        (
+            chrono::DateTime<Utc>,
+            Option<chrono::DateTime<Utc>>,
+            Option<String>,
+            Option<String>,
+        ),
+    >(
+        r#"
+        SELECT created_at, superseded_at, ballot_commitment, sealed_commitment
+        FROM vote_receipts
+        WHERE id = $1
+        "#,
     )
     .bind(receipt_id)
-    .fetch_one(&mut *tx)
+    .fetch_one(&mut **tx)
     .await
     .map_err(|_| AppError::Internal)?;
+    let ballot_commitment = bulletin::receipt_commitment(
+        link_key,
+        receipt_id,
+        ballot_commitment,
+        sealed_commitment.as_deref(),
+    );
 
-    tx.commit().await.map_err(|_| AppError::Internal)?;
+    let inclusion_proof = match ballot_commitment.as_deref() {
+        Some(ballot_commitment) => {
+            bulletin::inclusion_proof(tx, contest_id, ballot_commitment).await?
+        }
+        None => None,
+    };
 
     Ok(VoteReceiptResponse {
         receipt_id,
         election_id,
         contest_id,
         submitted_at,
+        points_used: None,
+        status: ReceiptStatus::from_superseded_at(superseded_at),
+        superseded_at,
+        ballot_commitment,
+        commitment_nonce: None,
+        inclusion_proof,
+        signature: None,
     })
 }
 
+// The superseded receipt stays so the voter can still look it up, but the ballot it cast is
+// withdrawn from the queue or deleted (its marks cascade), so only the latest ballot is counted.
+async fn supersede(
+    tx: &mut Transaction<'_, Postgres>,
+    link_key: &str,
+    receipt_id: Uuid,
+) -> Result<(), AppError> {
+    sqlx::query("UPDATE vote_receipts SET superseded_at = NOW() WHERE id = $1")
+        .bind(receipt_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+
+    if !ballot_queue::withdraw(tx, link_key, receipt_id).await? {
+        return Err(AppError::Conflict(
+            "previous ballot cannot be replaced".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 async fn ensure_contest_open(
     tx: &mut Transaction<'_, Postgres>,
     contest_id: Uuid,
-) -> Result<(Uuid, i32), AppError> {
+) -> Result<OpenContest, AppError> {
     let row = sqlx::query_as::<
         _,
         (
@@ -225,13 +714,39 @@ async fn ensure_contest_open(
             chrono::DateTime<Utc>,
             chrono::DateTime<Utc>,
             String,
+            String,
+            i32,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            bool,
+            bool,
+            bool,
+            bool,
+            Option<String>,
         ),
     >(
         r#"
-        SELECT c.election_id, c.max_selections, e.opens_at, e.closes_at, e.status
+        SELECT
+          c.election_id,
+          c.max_selections,
+          e.opens_at,
+          e.closes_at,
+          e.status,
+          c.voting_method,
+          c.min_selections,
+          c.score_min,
+          c.score_max,
+          c.total_points,
+          c.allow_blank,
+          c.allow_write_ins,
+          e.allow_revote,
+          e.encrypted_ballots,
+          e.encryption_public_key
         FROM contests c
         JOIN elections e ON e.id = c.election_id
         WHERE c.id = $1
+        FOR SHARE OF e
         "#,
     )
     .bind(contest_id)
@@ -240,6 +755,8 @@ async fn ensure_contest_open(
     .map_err(|_| AppError::Internal)?
     .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
 
+    // The share lock holds off closing until this ballot is queued, so the queue is empty once
+    // the election is closed.
     let now = Utc::now();
     if row.4 != "published" || now < row.2 || now > row.3 {
         return Err(AppError::BadRequest(
@@ -247,27 +764,242 @@ async fn ensure_contest_open(
         ));
     }
 
-    Ok((row.0, row.1))
+    Ok(OpenContest {
+        election_id: row.0,
+        max_selections: row.1,
+        min_selections: row.6,
+        voting_method: VotingMethod::from_db(&row.5).ok_or(AppError::Internal)?,
+        score_min: row.7,
+        score_max: row.8,
+        total_points: row.9,
+        allow_blank: row.10,
+        allow_write_ins: row.11,
+        allow_revote: row.12,
+        encrypted_ballots: row.13,
+        encryption_public_key: row.14,
+    })
+}
+
+fn is_blank(input: &CastVoteRequest) -> bool {
+    input.selections.is_empty()
+        && input.response.is_none()
+        && input.party_id.is_none()
+        && input.encrypted.is_none()
+}
+
+// In an encrypted election the choices travel only as ciphertexts; whether the ballot is valid
+// is left to the proofs checked in `encrypted_tally::verify_ballot`.
+fn validate_encrypted(contest: &OpenContest, input: &CastVoteRequest) -> Result<(), AppError> {
+    if input.encrypted.is_none() {
+        return Err(AppError::BadRequest(
+            "this election accepts only encrypted ballots".to_string(),
+        ));
+    }
+    if !input.selections.is_empty() || input.response.is_some() || input.party_id.is_some() {
+        return Err(AppError::BadRequest(
+            "encrypted ballots cannot also carry plaintext choices".to_string(),
+        ));
+    }
+    ensure_encryptable(contest)
+}
+
+fn ensure_encryptable(contest: &OpenContest) -> Result<(), AppError> {
+    if !matches!(
+        contest.voting_method,
+        VotingMethod::Plurality | VotingMethod::Approval
+    ) || contest.allow_write_ins
+    {
+        return Err(AppError::BadRequest(
+            "encrypted ballots support only plurality and approval contests without write-ins"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// Selection counts an encrypted ballot may prove it holds.
+fn allowed_counts(contest: &OpenContest) -> Vec<u64> {
+    let min = contest.min_selections.max(1) as u64;
+    let max = contest.max_selections.max(0) as u64;
+    let mut counts: Vec<u64> = (min..=max).collect();
+    if contest.allow_blank {
+        counts.insert(0, 0);
+    }
+    counts
+}
+
+fn validate_selections(contest: &OpenContest, input: &CastVoteRequest) -> Result<(), AppError> {
+    if input.response.is_some() && contest.voting_method != VotingMethod::Referendum {
+        return Err(AppError::BadRequest(
+            "responses are only accepted in referendum contests".to_string(),
+        ));
+    }
+    if input.party_id.is_some() && contest.voting_method != VotingMethod::PartyList {
+        return Err(AppError::BadRequest(
+            "party_id is only accepted in party-list contests".to_string(),
+        ));
+    }
+
+    if is_blank(input) && contest.allow_blank {
+        return Ok(());
+    }
+
+    match contest.voting_method {
+        VotingMethod::Referendum => {
+            if input.response.is_none() {
+                return Err(AppError::BadRequest(
+                    "response is required for referendum contests".to_string(),
+                ));
+            }
+            if !input.selections.is_empty() {
+                return Err(AppError::BadRequest(
+                    "referendum contests do not accept selections".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+        VotingMethod::PartyList => {
+            if input.party_id.is_none() {
+                return Err(AppError::BadRequest(
+                    "party_id is required for party-list contests".to_string(),
+                ));
+            }
+            if !input.selections.is_empty() {
+                return Err(AppError::BadRequest(
+                    "party-list contests do not accept selections".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let selections: &[BallotOptionInput] = &input.selections;
+    if selections.is_empty() {
+        return Err(AppError::BadRequest("selections cannot be empty".to_string()));
+    }
+
+    // Cumulative voters may stack several selections on the same candidate.
+    if contest.voting_method != VotingMethod::Cumulative {
+        let mut seen = std::collections::HashSet::new();
+        for selection in selections {
+            let write_in = selection.write_in.as_deref().map(write_in::normalize);
+            if !seen.insert((selection.candidate_id, write_in)) {
+                return Err(AppError::BadRequest(
+                    "selections cannot contain duplicates".to_string(),
+                ));
+            }
+        }
+    }
+
+    if selections.len() > contest.max_selections as usize {
+        return Err(AppError::BadRequest(format!(
+            "too many selections (max {})",
+            contest.max_selections
+        )));
+    }
+
+    if selections.len() < contest.min_selections as usize {
+        return Err(AppError::BadRequest(format!(
+            "too few selections (min {})",
+            contest.min_selections
+        )));
+    }
+
+    for selection in selections {
+        match (selection.candidate_id, selection.write_in.as_deref()) {
+            (Some(_), None) => {}
+            (None, Some(name)) => {
+                if !contest.allow_write_ins {
+                    return Err(AppError::BadRequest(
+                        "write-ins are not accepted in this contest".to_string(),
+                    ));
+                }
+                let length = name.trim().chars().count();
+                if length == 0 || length > write_in::MAX_LENGTH {
+                    return Err(AppError::BadRequest(format!(
+                        "write_in must be between 1 and {} characters",
+                        write_in::MAX_LENGTH
+                    )));
+                }
+            }
+            _ => {
+                return Err(AppError::BadRequest(
+                    "each selection requires exactly one of candidate_id or write_in".to_string(),
+                ));
+            }
+        }
+
+        match (contest.voting_method, selection.score) {
+            (VotingMethod::Score, None) => {
+                return Err(AppError::BadRequest(
+                    "each selection requires a score".to_string(),
+                ));
+            }
+            (VotingMethod::Score, Some(score)) => {
+                let min = contest.score_min.unwrap_or(0);
+                let max = contest.score_max.unwrap_or(min);
+                if score < min || score > max {
+                    return Err(AppError::BadRequest(format!(
+                        "score must be between {} and {}",
+                        min, max
+                    )));
+                }
+            }
+            (_, Some(_)) => {
+                return Err(AppError::BadRequest(
+                    "scores are only accepted in score contests".to_string(),
+                ));
+            }
+            (_, None) => {}
+        }
+
+        match selection.points {
+            Some(_) if !contest.voting_method.uses_points() => {
+                return Err(AppError::BadRequest(
+                    "points are only accepted in cumulative and quadratic contests".to_string(),
+                ));
+            }
+            Some(points) if points < 1 => {
+                return Err(AppError::BadRequest("points must be >= 1".to_string()));
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(total_points) = contest.total_points {
+        if points_used(contest.voting_method, selections) > total_points as i64 {
+            return Err(AppError::BadRequest(format!(
+                "too many points (max {})",
+                total_points
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn points_used(voting_method: VotingMethod, selections: &[BallotOptionInput]) -> i64 {
+    selections
+        .iter()
+        .map(|selection| voting_method.points_cost(selection.points.unwrap_or(1)))
+        .fold(0i64, i64::saturating_add)
 }
 
 async fn ensure_voter_eligible(
     tx: &mut Transaction<'_, Postgres>,
     contest_id: Uuid,
     voter_id: Uuid,
-) -> Result<(), AppError> {
-    let exists = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM voter_rolls WHERE contest_id = $1 AND user_id = $2",
+) -> Result<i64, AppError> {
+    sqlx::query_scalar::<_, i64>(
+        "SELECT weight FROM voter_rolls WHERE contest_id = $1 AND user_id = $2",
     )
     .bind(contest_id)
     .bind(voter_id)
-    .fetch_one(&mut **tx)
+    .fetch_optional(&mut **tx)
     .await
-    .map_err(|_| AppError::Internal)?;
-
-    if exists == 0 {
-        return Err(AppError::Forbidden);
-    }
-    Ok(())
+    .map_err(|_| AppError::Internal)?
+    .ok_or(AppError::Forbidden)
 }
 
 async fn fetch_receipt_by_idempotency(
@@ -293,3 +1025,187 @@ async fn fetch_receipt_by_idempotency(
 
     Ok(row)
 }
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{points_used, validate_selections, OpenContest};
+    use crate::{
+        domain::{BallotOptionInput, CastVoteRequest, ReferendumResponse, VotingMethod},
+        errors::AppError,
+    };
+
+    fn contest(voting_method: VotingMethod, min: i32, max: i32) -> OpenContest {
+        OpenContest {
+            election_id: Uuid::new_v4(),
+            max_selections: max,
+            min_selections: min,
+            voting_method,
+            score_min: None,
+            score_max: None,
+            total_points: None,
+            allow_blank: false,
+            allow_write_ins: false,
+            allow_revote: false,
+            encrypted_ballots: false,
+            encryption_public_key: None,
+        }
+    }
+
+    fn mark(score: Option<i32>, points: Option<i32>) -> BallotOptionInput {
+        mark_for(Uuid::new_v4(), score, points)
+    }
+
+    fn mark_for(candidate_id: Uuid, score: Option<i32>, points: Option<i32>) -> BallotOptionInput {
+        BallotOptionInput {
+            candidate_id: Some(candidate_id),
+            write_in: None,
+            score,
+            points,
+        }
+    }
+
+    fn ballot(selections: Vec<BallotOptionInput>) -> CastVoteRequest {
+        CastVoteRequest {
+            idempotency_key: "key".to_string(),
+            selections,
+            response: None,
+            party_id: None,
+            encrypted: None,
+        }
+    }
+
+    fn marks(count: usize) -> Vec<BallotOptionInput> {
+        (0..count).map(|_| mark(None, None)).collect()
+    }
+
+    fn rejection(contest: &OpenContest, input: &CastVoteRequest) -> String {
+        match validate_selections(contest, input) {
+            Err(AppError::BadRequest(message)) => message,
+            other => panic!("expected a bad request, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn selection_count_must_be_within_min_and_max() {
+        let approval = contest(VotingMethod::Approval, 2, 3);
+
+        assert_eq!(rejection(&approval, &ballot(marks(1))), "too few selections (min 2)");
+        assert!(validate_selections(&approval, &ballot(marks(2))).is_ok());
+        assert!(validate_selections(&approval, &ballot(marks(3))).is_ok());
+        assert_eq!(rejection(&approval, &ballot(marks(4))), "too many selections (max 3)");
+
+        let repeated = Uuid::new_v4();
+        let twice = ballot(vec![mark_for(repeated, None, None), mark_for(repeated, None, None)]);
+        assert_eq!(rejection(&approval, &twice), "selections cannot contain duplicates");
+    }
+
+    #[test]
+    fn scores_must_be_within_the_contest_range() {
+        let mut score = contest(VotingMethod::Score, 1, 1);
+        score.score_min = Some(0);
+        score.score_max = Some(5);
+
+        assert!(validate_selections(&score, &ballot(vec![mark(Some(0), None)])).is_ok());
+        assert!(validate_selections(&score, &ballot(vec![mark(Some(5), None)])).is_ok());
+        for out_of_range in [-1, 6] {
+            assert_eq!(
+                rejection(&score, &ballot(vec![mark(Some(out_of_range), None)])),
+                "score must be between 0 and 5"
+            );
+        }
+        assert_eq!(
+            rejection(&score, &ballot(vec![mark(None, None)])),
+            "each selection requires a score"
+        );
+
+        let plurality = contest(VotingMethod::Plurality, 1, 1);
+        assert_eq!(
+            rejection(&plurality, &ballot(vec![mark(Some(3), None)])),
+            "scores are only accepted in score contests"
+        );
+    }
+
+    #[test]
+    fn blank_ballots_are_accepted_only_when_allowed() {
+        let mut plurality = contest(VotingMethod::Plurality, 1, 1);
+        assert_eq!(rejection(&plurality, &ballot(Vec::new())), "selections cannot be empty");
+        plurality.allow_blank = true;
+        assert!(validate_selections(&plurality, &ballot(Vec::new())).is_ok());
+
+        let mut referendum = contest(VotingMethod::Referendum, 1, 1);
+        assert_eq!(
+            rejection(&referendum, &ballot(Vec::new())),
+            "response is required for referendum contests"
+        );
+        referendum.allow_blank = true;
+        assert!(validate_selections(&referendum, &ballot(Vec::new())).is_ok());
+    }
+
+    #[test]
+    fn cumulative_points_must_fit_the_budget() {
+        let mut cumulative = contest(VotingMethod::Cumulative, 1, 3);
+        cumulative.total_points = Some(5);
+
+        // Cumulative voters may spend several selections on one candidate.
+        let stacked = Uuid::new_v4();
+        let spend = |points: &[i32]| {
+            ballot(points.iter().map(|p| mark_for(stacked, None, Some(*p))).collect())
+        };
+        assert!(validate_selections(&cumulative, &spend(&[3, 2])).is_ok());
+        assert_eq!(rejection(&cumulative, &spend(&[3, 3])), "too many points (max 5)");
+        assert_eq!(rejection(&cumulative, &spend(&[0])), "points must be >= 1");
+
+        let approval = contest(VotingMethod::Approval, 1, 1);
+        assert_eq!(
+            rejection(&approval, &ballot(vec![mark(None, Some(1))])),
+            "points are only accepted in cumulative and quadratic contests"
+        );
+    }
+
+    #[test]
+    fn points_used_defaults_each_selection_to_one_point() {
+        let selections = vec![mark(None, Some(2)), mark(None, Some(3)), mark(None, None)];
+        assert_eq!(points_used(VotingMethod::Cumulative, &selections), 6);
+    }
+
+    #[test]
+    fn quadratic_votes_cost_their_square() {
+        let mut quadratic = contest(VotingMethod::Quadratic, 1, 2);
+        quadratic.total_points = Some(10);
+        let exact = ballot(vec![mark(None, Some(3)), mark(None, Some(1))]);
+        assert!(validate_selections(&quadratic, &exact).is_ok());
+        let over = ballot(vec![mark(None, Some(3)), mark(None, Some(2))]);
+        assert_eq!(rejection(&quadratic, &over), "too many points (max 10)");
+
+        let selections = vec![mark(None, Some(2)), mark(None, Some(3)), mark(None, None)];
+        assert_eq!(points_used(VotingMethod::Quadratic, &selections), 14);
+
+        // Three maximal quadratic costs overflow i64, so the sum saturates instead of wrapping.
+        let huge: Vec<_> = (0..3).map(|_| mark(None, Some(i32::MAX))).collect();
+        assert_eq!(points_used(VotingMethod::Quadratic, &huge), i64::MAX);
+    }
+
+    #[test]
+    fn referendum_ballots_take_a_response_instead_of_selections() {
+        let referendum = contest(VotingMethod::Referendum, 1, 1);
+        let mut answered = ballot(Vec::new());
+        answered.response = Some(ReferendumResponse::Yes);
+        assert!(validate_selections(&referendum, &answered).is_ok());
+
+        answered.selections = marks(1);
+        assert_eq!(
+            rejection(&referendum, &answered),
+            "referendum contests do not accept selections"
+        );
+
+        let plurality = contest(VotingMethod::Plurality, 1, 1);
+        let mut response = ballot(marks(1));
+        response.response = Some(ReferendumResponse::No);
+        assert_eq!(
+            rejection(&plurality, &response),
+            "responses are only accepted in referendum contests"
+        );
+    }
+}
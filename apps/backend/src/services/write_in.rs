@@ -0,0 +1,299 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::AdjudicateWriteInRequest,
+    errors::AppError,
+    services::{audit, results},
+};
+
+pub const MAX_LENGTH: usize = 200;
+
+// Write-ins that differ only in case or spacing are adjudicated together.
+pub fn normalize(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+// A write-in's id, ballot, adjudicated candidate and rank.
+type AdjudicatedWriteIn = (Uuid, Uuid, Uuid, Option<i32>);
+
+// Write-ins adjudicated to a candidate their ballot already marks would count the ballot twice or
+// give it two ranks. They are flagged instead: the ballot's own candidate mark is kept, and among
+// write-ins alone the best-ranked (then lowest id) one.
+fn duplicate_marks(write_ins: &[AdjudicatedWriteIn], marked: &[(Uuid, Uuid)]) -> Vec<Uuid> {
+    let mut ordered: Vec<&AdjudicatedWriteIn> = write_ins.iter().collect();
+    ordered.sort_by_key(|(id, _, _, rank)| (rank.unwrap_or(0), *id));
+
+    let mut kept: Vec<(Uuid, Uuid)> = Vec::new();
+    let mut duplicates = Vec::new();
+    for (id, ballot_id, candidate_id, _) in ordered {
+        let mark = (*ballot_id, *candidate_id);
+        if marked.contains(&mark) || kept.contains(&mark) {
+            duplicates.push(*id);
+        } else {
+            kept.push(mark);
+        }
+    }
+    duplicates
+}
+
+// Re-flags the contest's duplicate write-ins after an adjudication, which may also have moved a
+// name off a candidate it duplicated before.
+async fn flag_duplicates(
+    tx: &mut Transaction<'_, Postgres>,
+    contest_id: Uuid,
+) -> Result<(), AppError> {
+    let write_ins = sqlx::query_as::<_, AdjudicatedWriteIn>(
+        r#"
+        SELECT id, ballot_id, candidate_id, rank
+        FROM write_ins
+        WHERE contest_id = $1 AND candidate_id IS NOT NULL
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    let marked = sqlx::query_as::<_, (Uuid, Uuid)>(
+        r#"
+        SELECT ballot_id, candidate_id
+        FROM votes
+        WHERE contest_id = $1
+          AND candidate_id IS NOT NULL
+          AND ballot_id IN (
+            SELECT ballot_id FROM write_ins WHERE contest_id = $1 AND candidate_id IS NOT NULL
+          )
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    sqlx::query("UPDATE write_ins SET duplicate_mark = (id = ANY($2)) WHERE contest_id = $1")
+        .bind(contest_id)
+        .bind(duplicate_marks(&write_ins, &marked))
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| AppError::Internal)?;
+    Ok(())
+}
+
+pub async fn list(pool: &PgPool, contest_id: Uuid) -> Result<Vec<serde_json::Value>, AppError> {
+    let rows = sqlx::query_as::<_, (String, String, Option<Uuid>, Option<String>, i64, i64)>(
+        r#"
+        SELECT w.normalized_name, MIN(w.name), w.candidate_id, c.name,
+               COALESCE(SUM(COALESCE(w.points, 1) * w.weight) FILTER (WHERE NOT w.duplicate_mark),
+                        0)::bigint AS total,
+               COUNT(*) FILTER (WHERE w.duplicate_mark)
+        FROM write_ins w
+        LEFT JOIN candidates c ON c.id = w.candidate_id
+        WHERE w.contest_id = $1
+        GROUP BY w.normalized_name, w.candidate_id, c.name
+        ORDER BY total DESC, w.normalized_name ASC
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(normalized_name, name, candidate_id, candidate_name, total, duplicates)| {
+                serde_json::json!({
+                    "name": name,
+                    "normalized_name": normalized_name,
+                    "total": total,
+                    "duplicates": duplicates,
+                    "adjudicated": candidate_id.is_some(),
+                    "candidate_id": candidate_id,
+                    "candidate_name": candidate_name
+                })
+            },
+        )
+        .collect())
+}
+
+pub async fn adjudicate(
+    pool: &PgPool,
+    contest_id: Uuid,
+    officer_id: Uuid,
+    input: AdjudicateWriteInRequest,
+) -> Result<serde_json::Value, AppError> {
+    let normalized_name = normalize(&input.name);
+    if normalized_name.is_empty() {
+        return Err(AppError::BadRequest("name is required".to_string()));
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| AppError::Internal)?;
+
+    let (election_id, status) = sqlx::query_as::<_, (Uuid, String)>(
+        r#"
+        SELECT e.id, e.status
+        FROM contests c
+        JOIN elections e ON e.id = c.election_id
+        WHERE c.id = $1
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .ok_or_else(|| AppError::NotFound("contest not found".to_string()))?;
+
+    if status == "draft" {
+        return Err(AppError::Conflict(
+            "write-ins can only be adjudicated once voting has started".to_string(),
+        ));
+    }
+
+    let candidate_id = match (input.candidate_id, input.new_candidate_name.as_deref()) {
+        (Some(candidate_id), None) => {
+            let exists = sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM candidates WHERE id = $1 AND contest_id = $2",
+            )
+            .bind(candidate_id)
+            .bind(contest_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|_| AppError::Internal)?;
+
+            if exists == 0 {
+                return Err(AppError::BadRequest("candidate_id is invalid".to_string()));
+            }
+            candidate_id
+        }
+        (None, Some(name)) => {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(AppError::BadRequest(
+                    "new_candidate_name is required".to_string(),
+                ));
+            }
+
+            let candidate_id = Uuid::new_v4();
+            sqlx::query(
+                r#"
+                INSERT INTO candidates (id, election_id, contest_id, name)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(candidate_id)
+            .bind(election_id)
+            .bind(contest_id)
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| AppError::Internal)?;
+            candidate_id
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "exactly one of candidate_id or new_candidate_name is required".to_string(),
+            ));
+        }
+    };
+
+    let adjudicated = sqlx::query(
+        r#"
+        UPDATE write_ins
+        SET candidate_id = $1, adjudicated_by = $2, adjudicated_at = NOW()
+        WHERE contest_id = $3 AND normalized_name = $4
+        "#,
+    )
+    .bind(candidate_id)
+    .bind(officer_id)
+    .bind(contest_id)
+    .bind(&normalized_name)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?
+    .rows_affected();
+
+    if adjudicated == 0 {
+        return Err(AppError::NotFound("write-in not found".to_string()));
+    }
+
+    flag_duplicates(&mut tx, contest_id).await?;
+    let duplicates = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM write_ins
+        WHERE contest_id = $1 AND normalized_name = $2 AND duplicate_mark
+        "#,
+    )
+    .bind(contest_id)
+    .bind(&normalized_name)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    audit::record(
+        &mut tx,
+        "write_in_adjudicated",
+        officer_id,
+        election_id,
+        serde_json::json!({
+            "contest_id": contest_id,
+            "name": normalized_name,
+            "candidate_id": candidate_id,
+            "count": adjudicated as i64,
+            "duplicates": duplicates
+        }),
+    )
+    .await?;
+
+    tx.commit().await.map_err(|_| AppError::Internal)?;
+
+    // A merged write-in can change a closed contest's winners and so its tie-break.
+    results::record_tie_break(pool, contest_id, officer_id).await?;
+
+    Ok(serde_json::json!({
+        "name": normalized_name,
+        "candidate_id": candidate_id,
+        "adjudicated": adjudicated,
+        "duplicates": duplicates
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{duplicate_marks, normalize};
+
+    #[test]
+    fn normalize_ignores_case_and_spacing() {
+        assert_eq!(normalize("  Jane   DOE "), "jane doe");
+        assert_eq!(normalize("Jane Doe"), normalize("jane\tdoe"));
+    }
+
+    #[test]
+    fn write_ins_for_a_candidate_the_ballot_already_marks_are_duplicates() {
+        let (ballot, other_ballot, candidate) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let (ranked_first, ranked_third, separate) =
+            (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        // The ballot marks the candidate itself: every write-in mapped to them is a duplicate.
+        let write_ins = [(ranked_first, ballot, candidate, Some(1))];
+        assert_eq!(
+            duplicate_marks(&write_ins, &[(ballot, candidate)]),
+            vec![ranked_first]
+        );
+
+        // Two write-ins on one ballot map to the same candidate: the better rank counts.
+        let write_ins = [
+            (ranked_third, ballot, candidate, Some(3)),
+            (ranked_first, ballot, candidate, Some(1)),
+            (separate, other_ballot, candidate, Some(3)),
+        ];
+        assert_eq!(duplicate_marks(&write_ins, &[]), vec![ranked_third]);
+        assert!(duplicate_marks(&write_ins[2..], &[(ballot, candidate)]).is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{Decisions, Tie, TieBreak};
+use crate::domain::AllocationMethod;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PartyAllocation {
+    pub party_id: Uuid,
+    pub votes: u64,
+    pub seats: usize,
+    pub quotients: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeatAward {
+    pub seat: usize,
+    pub party_id: Uuid,
+    pub quotient: f64,
+    pub tie_break: Option<TieBreak>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HighestAverages {
+    pub method: AllocationMethod,
+    pub seats: usize,
+    pub total_votes: u64,
+    pub parties: Vec<PartyAllocation>,
+    pub awards: Vec<SeatAward>,
+    pub pending_tie: Option<Tie>,
+}
+
+// `parties` must be in registration order with their list votes. `quotients` holds each party's
+// first `seats` averages so the whole allocation table can be shown. Parties level on the highest
+// quotient take consecutive seats, so they only tie when fewer seats are left than parties; those
+// seats are settled from `decisions` or left open as `pending_tie`.
+pub fn allocate(
+    method: AllocationMethod,
+    parties: &[(Uuid, u64)],
+    seats: usize,
+    decisions: &Decisions,
+) -> HighestAverages {
+    let total_votes = parties.iter().map(|(_, votes)| votes).sum();
+    let mut won = vec![0usize; parties.len()];
+    let mut awards: Vec<SeatAward> = Vec::new();
+    let mut pending_tie = None;
+
+    if total_votes > 0 {
+        while awards.len() < seats {
+            let mut best: Vec<usize> = Vec::new();
+            for (i, (_, votes)) in parties.iter().enumerate() {
+                let Some(&leader) = best.first() else {
+                    best.push(i);
+                    continue;
+                };
+                let lhs = *votes as u128 * divisor(method, won[leader]) as u128;
+                let rhs = parties[leader].1 as u128 * divisor(method, won[i]) as u128;
+                if lhs > rhs {
+                    best = vec![i];
+                } else if lhs == rhs {
+                    best.push(i);
+                }
+            }
+
+            let remaining = seats - awards.len();
+            let (winners, tie_break) = if best.len() > remaining {
+                let stage = format!("seat_{}", awards.len() + 1);
+                let chosen: Vec<usize> = decisions
+                    .get(&stage)
+                    .map(|chosen| {
+                        best.iter()
+                            .copied()
+                            .filter(|i| chosen.contains(&parties[*i].0))
+                            .take(remaining)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if chosen.is_empty() {
+                    pending_tie = Some(Tie {
+                        stage,
+                        tied: best.iter().map(|i| parties[*i].0).collect(),
+                        seats: remaining,
+                    });
+                    break;
+                }
+                (chosen, Some(TieBreak::Policy))
+            } else {
+                (best, None)
+            };
+
+            for winner in winners {
+                awards.push(SeatAward {
+                    seat: awards.len() + 1,
+                    party_id: parties[winner].0,
+                    quotient: rounded(
+                        parties[winner].1 as f64 / divisor(method, won[winner]) as f64,
+                    ),
+                    tie_break,
+                });
+                won[winner] += 1;
+            }
+        }
+    }
+
+    let parties = parties
+        .iter()
+        .zip(won)
+        .map(|((party_id, votes), seats_won)| PartyAllocation {
+            party_id: *party_id,
+            votes: *votes,
+            seats: seats_won,
+            quotients: (0..seats)
+                .map(|n| rounded(*votes as f64 / divisor(method, n) as f64))
+                .collect(),
+        })
+        .collect();
+
+    HighestAverages {
+        method,
+        seats,
+        total_votes,
+        parties,
+        awards,
+        pending_tie,
+    }
+}
+
+fn divisor(method: AllocationMethod, seats_won: usize) -> u64 {
+    match method {
+        AllocationMethod::Dhondt => seats_won as u64 + 1,
+        AllocationMethod::SainteLague => seats_won as u64 * 2 + 1,
+    }
+}
+
+fn rounded(value: f64) -> f64 {
+    (value * 10_000.0).round() / 10_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::allocate;
+    use crate::{
+        domain::AllocationMethod,
+        tally::{Decisions, TieBreak},
+    };
+
+    fn parties(votes: &[u64]) -> Vec<(Uuid, u64)> {
+        votes.iter().map(|v| (Uuid::new_v4(), *v)).collect()
+    }
+
+    fn seats_won(result: &super::HighestAverages) -> Vec<usize> {
+        result.parties.iter().map(|p| p.seats).collect()
+    }
+
+    #[test]
+    fn dhondt_favours_larger_parties() {
+        let p = parties(&[1000, 800, 300, 200]);
+        let result = allocate(AllocationMethod::Dhondt, &p, 8, &Decisions::new());
+
+        assert_eq!(seats_won(&result), vec![4, 3, 1, 0]);
+        assert_eq!(result.awards.len(), 8);
+        assert_eq!(
+            result.parties[0].quotients,
+            vec![1000.0, 500.0, 333.3333, 250.0, 200.0, 166.6667, 142.8571, 125.0]
+        );
+    }
+
+    #[test]
+    fn sainte_lague_uses_odd_divisors() {
+        let p = parties(&[1000, 800, 300, 200]);
+        let result = allocate(AllocationMethod::SainteLague, &p, 8, &Decisions::new());
+
+        assert_eq!(seats_won(&result), vec![3, 3, 1, 1]);
+        assert_eq!(result.awards[2].party_id, p[0].0);
+        assert_eq!(result.awards[2].quotient, 333.3333);
+    }
+
+    #[test]
+    fn equal_quotients_tie_only_when_seats_run_out() {
+        let p = parties(&[50, 50, 0]);
+        let result = allocate(AllocationMethod::Dhondt, &p, 2, &Decisions::new());
+
+        assert_eq!(seats_won(&result), vec![1, 1, 0]);
+        assert!(result.awards.iter().all(|award| award.tie_break.is_none()));
+        assert!(result.pending_tie.is_none());
+
+        let result = allocate(AllocationMethod::Dhondt, &p, 1, &Decisions::new());
+        assert!(result.awards.is_empty());
+        let tie = result.pending_tie.expect("pending tie");
+        assert_eq!(tie.stage, "seat_1");
+        assert_eq!(tie.tied, vec![p[0].0, p[1].0]);
+        assert_eq!(tie.seats, 1);
+
+        let decisions = Decisions::from([("seat_1".to_string(), vec![p[1].0])]);
+        let result = allocate(AllocationMethod::Dhondt, &p, 1, &decisions);
+        assert_eq!(result.awards[0].party_id, p[1].0);
+        assert_eq!(result.awards[0].tie_break, Some(TieBreak::Policy));
+
+        let result = allocate(AllocationMethod::Dhondt, &parties(&[0, 0]), 2, &Decisions::new());
+        assert!(result.awards.is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{settle_elimination, Ballot, Decisions, Tie, TieBreak};
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CandidateTally {
+    pub candidate_id: Uuid,
+    pub votes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Round {
+    pub round: usize,
+    pub tallies: Vec<CandidateTally>,
+    pub continuing_ballots: u64,
+    pub exhausted_ballots: u64,
+    pub elected: Option<Uuid>,
+    pub eliminated: Option<Uuid>,
+    pub tie_break: Option<TieBreak>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstantRunoff {
+    pub total_ballots: u64,
+    pub rounds: Vec<Round>,
+    pub winner: Option<Uuid>,
+    pub pending_tie: Option<Tie>,
+}
+
+// `candidates` must be in registration order; ballots list candidate ids by preference. An
+// elimination tie that earlier rounds cannot split is settled from `decisions` (the tied
+// candidates kept in the count), or stops the count as `pending_tie`.
+pub fn tabulate(candidates: &[Uuid], ballots: &[Ballot], decisions: &Decisions) -> InstantRunoff {
+    let total_ballots: u64 = ballots.iter().map(|b| b.weight).sum();
+    let mut continuing: Vec<Uuid> = candidates.to_vec();
+    let mut rounds: Vec<Round> = Vec::new();
+    let mut winner = None;
+    let mut pending_tie = None;
+
+    while !continuing.is_empty() {
+        let mut counts: HashMap<Uuid, u64> = continuing.iter().map(|id| (*id, 0)).collect();
+        let mut exhausted = 0u64;
+
+        for ballot in ballots {
+            match ballot.ranking.iter().find(|id| counts.contains_key(*id)) {
+                Some(id) => *counts.entry(*id).or_default() += ballot.weight,
+                None => exhausted += ballot.weight,
+            }
+        }
+
+        let continuing_ballots = total_ballots - exhausted;
+        let mut tallies: Vec<CandidateTally> = continuing
+            .iter()
+            .map(|id| CandidateTally {
+                candidate_id: *id,
+                votes: counts[id],
+            })
+            .collect();
+        tallies.sort_by_key(|t| std::cmp::Reverse(t.votes));
+
+        let mut round = Round {
+            round: rounds.len() + 1,
+            tallies,
+            continuing_ballots,
+            exhausted_ballots: exhausted,
+            elected: None,
+            eliminated: None,
+            tie_break: None,
+        };
+
+        if continuing_ballots == 0 {
+            rounds.push(round);
+            break;
+        }
+
+        let leader = &round.tallies[0];
+        if leader.votes * 2 > continuing_ballots || continuing.len() == 1 {
+            round.elected = Some(leader.candidate_id);
+            winner = round.elected;
+            rounds.push(round);
+            break;
+        }
+
+        let (loser, tie_break) = match pick_elimination(&continuing, &counts, &rounds) {
+            Ok(pick) => pick,
+            Err(tied) => {
+                let stage = format!("round_{}", round.round);
+                match settle_elimination(decisions, stage, tied) {
+                    Ok(loser) => (loser, Some(TieBreak::Policy)),
+                    Err(tie) => {
+                        pending_tie = Some(tie);
+                        rounds.push(round);
+                        break;
+                    }
+                }
+            }
+        };
+        round.eliminated = Some(loser);
+        round.tie_break = tie_break;
+        continuing.retain(|id| *id != loser);
+        rounds.push(round);
+    }
+
+    InstantRunoff {
+        total_ballots,
+        rounds,
+        winner,
+        pending_tie,
+    }
+}
+
+fn pick_elimination(
+    continuing: &[Uuid],
+    counts: &HashMap<Uuid, u64>,
+    previous_rounds: &[Round],
+) -> Result<(Uuid, Option<TieBreak>), Vec<Uuid>> {
+    let fewest = continuing.iter().map(|id| counts[id]).min().unwrap_or(0);
+    let mut tied: Vec<Uuid> = continuing
+        .iter()
+        .copied()
+        .filter(|id| counts[id] == fewest)
+        .collect();
+
+    if tied.len() == 1 {
+        return Ok((tied[0], None));
+    }
+
+    for round in previous_rounds.iter().rev() {
+        let votes_in = |id: &Uuid| {
+            round
+                .tallies
+                .iter()
+                .find(|t| t.candidate_id == *id)
+                .map(|t| t.votes)
+                .unwrap_or(0)
+        };
+        let fewest = tied.iter().map(votes_in).min().unwrap_or(0);
+        tied.retain(|id| votes_in(id) == fewest);
+        if tied.len() == 1 {
+            return Ok((tied[0], Some(TieBreak::PreviousRounds)));
+        }
+    }
+
+    Err(tied)
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::tabulate;
+    use crate::tally::{Ballot, Decisions, Tie, TieBreak};
+
+    fn ids(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::new_v4()).collect()
+    }
+
+    fn unweighted(rankings: &[Vec<Uuid>]) -> Vec<Ballot> {
+        rankings.iter().cloned().map(Ballot::from).collect()
+    }
+
+    #[test]
+    fn first_round_majority_wins() {
+        let c = ids(3);
+        let ballots = vec![vec![c[0]], vec![c[0], c[1]], vec![c[1]]];
+        let result = tabulate(&c, &unweighted(&ballots), &Decisions::new());
+
+        assert_eq!(result.winner, Some(c[0]));
+        assert_eq!(result.rounds.len(), 1);
+    }
+
+    #[test]
+    fn eliminated_votes_transfer_to_next_preference() {
+        let c = ids(3);
+        let ballots = vec![
+            vec![c[0]],
+            vec![c[0]],
+            vec![c[1]],
+            vec![c[1]],
+            vec![c[2], c[1]],
+        ];
+        let result = tabulate(&c, &unweighted(&ballots), &Decisions::new());
+
+        assert_eq!(result.rounds[0].eliminated, Some(c[2]));
+        assert_eq!(result.rounds[1].elected, Some(c[1]));
+        assert_eq!(result.winner, Some(c[1]));
+    }
+
+    #[test]
+    fn ballots_without_continuing_preferences_are_exhausted() {
+        let c = ids(3);
+        let ballots = vec![vec![c[0]], vec![c[0]], vec![c[1]], vec![c[1]], vec![c[2]]];
+        let result = tabulate(&c, &unweighted(&ballots), &Decisions::new());
+
+        assert_eq!(result.rounds[1].exhausted_ballots, 1);
+        assert_eq!(result.rounds[1].continuing_ballots, 4);
+    }
+
+    #[test]
+    fn lowest_tie_is_broken_by_previous_round_then_the_policy() {
+        let c = ids(4);
+        let mut ballots = vec![vec![c[0]]; 4];
+        ballots.extend(vec![vec![c[1]]; 2]);
+        ballots.extend(vec![vec![c[2]]; 3]);
+        ballots.push(vec![c[3], c[1]]);
+        let result = tabulate(&c, &unweighted(&ballots), &Decisions::new());
+
+        assert_eq!(result.rounds[0].eliminated, Some(c[3]));
+        assert_eq!(result.rounds[1].eliminated, Some(c[1]));
+        assert_eq!(result.rounds[1].tie_break, Some(TieBreak::PreviousRounds));
+        assert_eq!(result.winner, Some(c[0]));
+
+        let c = ids(3);
+        let ballots = vec![vec![c[0]], vec![c[0]], vec![c[1]], vec![c[2]]];
+        let result = tabulate(&c, &unweighted(&ballots), &Decisions::new());
+
+        assert_eq!(result.rounds.len(), 1);
+        assert_eq!(result.rounds[0].eliminated, None);
+        assert_eq!(result.winner, None);
+        assert_eq!(
+            result.pending_tie,
+            Some(Tie {
+                stage: "round_1".to_string(),
+                tied: vec![c[1], c[2]],
+                seats: 1,
+            })
+        );
+
+        let decisions = Decisions::from([("round_1".to_string(), vec![c[2]])]);
+        let result = tabulate(&c, &unweighted(&ballots), &decisions);
+
+        assert_eq!(result.rounds[0].eliminated, Some(c[1]));
+        assert_eq!(result.rounds[0].tie_break, Some(TieBreak::Policy));
+        assert_eq!(result.pending_tie, None);
+    }
+
+    #[test]
+    fn an_even_final_round_is_left_to_the_policy() {
+        let c = ids(2);
+        let ballots = vec![vec![c[0]], vec![c[1]]];
+        let result = tabulate(&c, &unweighted(&ballots), &Decisions::new());
+
+        assert_eq!(result.winner, None);
+        assert_eq!(result.pending_tie.map(|tie| tie.tied), Some(vec![c[0], c[1]]));
+
+        let decisions = Decisions::from([("round_1".to_string(), vec![c[1]])]);
+        let result = tabulate(&c, &unweighted(&ballots), &decisions);
+
+        assert_eq!(result.rounds[0].eliminated, Some(c[0]));
+        assert_eq!(result.winner, Some(c[1]));
+    }
+
+    #[test]
+    fn ballots_count_with_their_weight() {
+        let c = ids(2);
+        let ballots = vec![
+            Ballot {
+                ranking: vec![c[0]],
+                weight: 3,
+            },
+            vec![c[1]].into(),
+            vec![c[1]].into(),
+        ];
+        let result = tabulate(&c, &ballots, &Decisions::new());
+
+        assert_eq!(result.total_ballots, 5);
+        assert_eq!(result.rounds[0].tallies[0].votes, 3);
+        assert_eq!(result.winner, Some(c[0]));
+    }
+}
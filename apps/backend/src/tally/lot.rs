@@ -0,0 +1,78 @@
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+pub fn generate_seed() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+// Published before voting so the seed cannot be chosen after the tie is known.
+pub fn commitment(seed: &str) -> String {
+    format!("{:x}", Sha256::digest(seed.as_bytes()))
+}
+
+// Appends entropy that only exists after voting closes (the contest's closing bulletin root), so
+// the seed committed at publish cannot be tuned towards a particular draw.
+pub fn mix(seed: &str, entropy: Option<&str>) -> String {
+    match entropy {
+        Some(entropy) => format!("{}:{}", seed, entropy),
+        None => seed.to_string(),
+    }
+}
+
+// Orders `candidates` by SHA-256("{seed}:{scope}:{candidate_id}"), so anyone holding the revealed
+// seed can reproduce the draw.
+pub fn draw(seed: &str, scope: Uuid, candidates: &[Uuid]) -> Vec<Uuid> {
+    let mut keyed: Vec<(String, Uuid)> = candidates
+        .iter()
+        .map(|id| (commitment(&format!("{}:{}:{}", seed, scope, id)), *id))
+        .collect();
+    keyed.sort();
+    keyed.into_iter().map(|(_, id)| id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{commitment, draw, mix};
+
+    #[test]
+    fn draw_is_reproducible_from_the_seed() {
+        let scope = Uuid::new_v4();
+        let candidates: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let mut reversed = candidates.clone();
+        reversed.reverse();
+
+        let first = draw("public-seed", scope, &candidates);
+        assert_eq!(first, draw("public-seed", scope, &reversed));
+        assert_eq!(first.len(), 5);
+        assert!(candidates.iter().all(|id| first.contains(id)));
+    }
+
+    #[test]
+    fn entropy_changes_the_draw_seed() {
+        assert_eq!(mix("public-seed", None), "public-seed");
+        assert_eq!(mix("public-seed", Some("root")), "public-seed:root");
+
+        let scope = Uuid::new_v4();
+        let candidates: Vec<Uuid> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let orders: Vec<Vec<Uuid>> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|root| draw(&mix("public-seed", Some(root)), scope, &candidates))
+            .collect();
+        assert!(orders.iter().any(|order| *order != orders[0]));
+    }
+
+    #[test]
+    fn commitment_is_sha256_hex() {
+        assert_eq!(
+            commitment("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
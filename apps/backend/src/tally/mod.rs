@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+pub mod highest_averages;
+pub mod irv;
+pub mod lot;
+pub mod schulze;
+pub mod stv;
+pub mod top_n;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreak {
+    PreviousRounds,
+    // Settled by the election's tie-break policy (see `Tie`).
+    Policy,
+}
+
+// A tie the method's own rules leave open: `seats` of the `tied` candidates (or parties) go
+// through. A tally stops at the first tie it has no decision for and returns it, so the caller can
+// settle it by the election's tie-break policy and tally again with the decision added.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Tie {
+    pub stage: String,
+    pub tied: Vec<Uuid>,
+    pub seats: usize,
+}
+
+// The ids chosen for each settled tie, keyed by the tie's `stage`.
+pub type Decisions = HashMap<String, Vec<Uuid>>;
+
+// A ranked ballot and the weight it carries: 1 for ordinary voters, the roll weight (shares,
+// delegate units) in weighted elections. Ballot counts in tallies are sums of these weights.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ballot {
+    pub ranking: Vec<Uuid>,
+    pub weight: u64,
+}
+
+impl From<Vec<Uuid>> for Ballot {
+    fn from(ranking: Vec<Uuid>) -> Self {
+        Self { ranking, weight: 1 }
+    }
+}
+
+// Picks the candidate to eliminate from a tie the count could not split: the one the `stage`
+// decision did not keep. Without a decision the tie is handed back to be settled.
+pub(crate) fn settle_elimination(
+    decisions: &Decisions,
+    stage: String,
+    tied: Vec<Uuid>,
+) -> Result<Uuid, Tie> {
+    let loser = decisions
+        .get(&stage)
+        .and_then(|kept| tied.iter().copied().find(|id| !kept.contains(id)));
+    loser.ok_or(Tie {
+        stage,
+        seats: tied.len() - 1,
+        tied,
+    })
+}
@@ -0,0 +1,167 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::Ballot;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Schulze {
+    pub candidates: Vec<Uuid>,
+    pub pairwise: Vec<Vec<u64>>,
+    pub strongest_paths: Vec<Vec<u64>>,
+    pub ranking: Vec<Vec<Uuid>>,
+    // Candidates no other candidate beats.
+    pub winners: Vec<Uuid>,
+}
+
+// Matrices are indexed in `candidates` order: `pairwise[i][j]` is the weight of ballots
+// preferring candidate i over candidate j. Unranked candidates tie below ranked ones.
+pub fn tabulate(candidates: &[Uuid], ballots: &[Ballot]) -> Schulze {
+    let n = candidates.len();
+    let mut pairwise = vec![vec![0u64; n]; n];
+
+    for ballot in ballots {
+        let positions: Vec<Option<usize>> = candidates
+            .iter()
+            .map(|id| ballot.ranking.iter().position(|c| c == id))
+            .collect();
+
+        for i in 0..n {
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+                let prefers = match (positions[i], positions[j]) {
+                    (Some(a), Some(b)) => a < b,
+                    (Some(_), None) => true,
+                    _ => false,
+                };
+                if prefers {
+                    pairwise[i][j] += ballot.weight;
+                }
+            }
+        }
+    }
+
+    let mut paths = vec![vec![0u64; n]; n];
+    for i in 0..n {
+        for j in 0..n {
+            if i != j && pairwise[i][j] > pairwise[j][i] {
+                paths[i][j] = pairwise[i][j];
+            }
+        }
+    }
+
+    for k in 0..n {
+        for i in 0..n {
+            if i == k {
+                continue;
+            }
+            for j in 0..n {
+                if j == i || j == k {
+                    continue;
+                }
+                paths[i][j] = paths[i][j].max(paths[i][k].min(paths[k][j]));
+            }
+        }
+    }
+
+    // The beatpath relation is only a partial order: candidates with different win counts can
+    // both be unbeaten. Each tier is therefore the set no remaining candidate beats, removed in
+    // turn, starting with the Schulze winners.
+    let beats = |i: usize, j: usize| paths[i][j] > paths[j][i];
+    let mut remaining: Vec<usize> = (0..n).collect();
+    let mut ranking: Vec<Vec<Uuid>> = Vec::new();
+    while !remaining.is_empty() {
+        let (tier, rest): (Vec<usize>, Vec<usize>) = remaining
+            .iter()
+            .partition(|&&j| !remaining.iter().any(|&i| beats(i, j)));
+        ranking.push(tier.iter().map(|&i| candidates[i]).collect());
+        remaining = rest;
+    }
+
+    Schulze {
+        candidates: candidates.to_vec(),
+        pairwise,
+        strongest_paths: paths,
+        winners: ranking.first().cloned().unwrap_or_default(),
+        ranking,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::tabulate;
+    use crate::tally::Ballot;
+
+    fn ids(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::new_v4()).collect()
+    }
+
+    fn unweighted(rankings: &[Vec<Uuid>]) -> Vec<Ballot> {
+        rankings.iter().cloned().map(Ballot::from).collect()
+    }
+
+    #[test]
+    fn condorcet_winner_is_ranked_first() {
+        let c = ids(3);
+        let mut ballots = vec![vec![c[0], c[1], c[2]]; 4];
+        ballots.extend(vec![vec![c[1], c[0], c[2]]; 3]);
+        ballots.extend(vec![vec![c[2], c[1], c[0]]; 2]);
+        let result = tabulate(&c, &unweighted(&ballots));
+
+        assert_eq!(result.pairwise[1][0], 5);
+        assert_eq!(result.pairwise[0][1], 4);
+        assert_eq!(result.ranking, vec![vec![c[1]], vec![c[0]], vec![c[2]]]);
+    }
+
+    #[test]
+    fn resolves_condorcet_cycle_with_strongest_paths() {
+        // Classic example from Schulze's paper with 45 voters and 5 candidates.
+        let c = ids(5);
+        let (a, b, cc, d, e) = (c[0], c[1], c[2], c[3], c[4]);
+        let mut ballots = Vec::new();
+        for (count, ranking) in [
+            (5, vec![a, cc, b, e, d]),
+            (5, vec![a, d, e, cc, b]),
+            (8, vec![b, e, d, a, cc]),
+            (3, vec![cc, a, b, e, d]),
+            (7, vec![cc, a, e, b, d]),
+            (2, vec![cc, b, a, d, e]),
+            (7, vec![d, cc, e, b, a]),
+            (8, vec![e, b, a, d, cc]),
+        ] {
+            ballots.extend(vec![ranking; count]);
+        }
+        let result = tabulate(&c, &unweighted(&ballots));
+
+        assert_eq!(result.strongest_paths[4][3], 31);
+        assert_eq!(
+            result.ranking,
+            vec![vec![e], vec![a], vec![cc], vec![b], vec![d]]
+        );
+    }
+
+    #[test]
+    fn every_unbeaten_candidate_shares_the_first_tier() {
+        // A beats C while A–B and B–C are pairwise ties, so B is unbeaten despite fewer wins.
+        let c = ids(3);
+        let ballots = vec![vec![c[0], c[2], c[1]], vec![c[1], c[0], c[2]]];
+        let result = tabulate(&c, &unweighted(&ballots));
+
+        assert_eq!(result.winners, vec![c[0], c[1]]);
+        assert_eq!(result.ranking, vec![vec![c[0], c[1]], vec![c[2]]]);
+    }
+
+    #[test]
+    fn unranked_candidates_are_tied_below_ranked_ones() {
+        let c = ids(3);
+        let ballots = vec![vec![c[0]], vec![c[0]]];
+        let result = tabulate(&c, &unweighted(&ballots));
+
+        assert_eq!(result.pairwise[0][1], 2);
+        assert_eq!(result.pairwise[1][2], 0);
+        assert_eq!(result.ranking, vec![vec![c[0]], vec![c[1], c[2]]]);
+    }
+}
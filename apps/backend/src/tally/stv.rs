@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{settle_elimination, Ballot, Decisions, Tie, TieBreak};
+
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateTally {
+    pub candidate_id: Uuid,
+    pub votes: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferKind {
+    Surplus,
+    Elimination,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Transfer {
+    pub from: Uuid,
+    pub kind: TransferKind,
+    pub votes: f64,
+    pub transfer_value: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Round {
+    pub round: usize,
+    pub tallies: Vec<CandidateTally>,
+    pub exhausted_votes: f64,
+    pub elected: Vec<Uuid>,
+    pub eliminated: Option<Uuid>,
+    pub transfers: Vec<Transfer>,
+    pub tie_break: Option<TieBreak>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SingleTransferableVote {
+    pub seats: usize,
+    pub quota: u64,
+    pub total_ballots: u64,
+    pub rounds: Vec<Round>,
+    pub elected: Vec<Uuid>,
+    pub pending_tie: Option<Tie>,
+}
+
+// Droop quota with inclusive Gregory surplus transfers: every ballot held by an elected
+// candidate moves on at `surplus / votes` of its current value. Elimination ties that earlier
+// rounds cannot split are settled from `decisions` or stop the count, as in `irv::tabulate`.
+pub fn tabulate(
+    candidates: &[Uuid],
+    ballots: &[Ballot],
+    seats: usize,
+    decisions: &Decisions,
+) -> SingleTransferableVote {
+    let valid_ballots: u64 = ballots
+        .iter()
+        .filter(|b| !b.ranking.is_empty())
+        .map(|b| b.weight)
+        .sum();
+    let quota = valid_ballots / (seats as u64 + 1) + 1;
+
+    let mut weights: Vec<f64> = ballots.iter().map(|b| b.weight as f64).collect();
+    let mut continuing: Vec<Uuid> = candidates.to_vec();
+    let mut elected: Vec<Uuid> = Vec::new();
+    let mut rounds: Vec<Round> = Vec::new();
+    let mut pending_tie = None;
+
+    while elected.len() < seats && !continuing.is_empty() {
+        let mut counts: HashMap<Uuid, f64> = continuing.iter().map(|id| (*id, 0.0)).collect();
+        let mut holders: Vec<Option<Uuid>> = Vec::with_capacity(ballots.len());
+        let mut exhausted = 0.0f64;
+
+        for (ballot, weight) in ballots.iter().zip(&weights) {
+            let holder = ballot
+                .ranking
+                .iter()
+                .copied()
+                .find(|id| counts.contains_key(id));
+            match holder {
+                Some(id) => *counts.entry(id).or_default() += weight,
+                None => exhausted += weight,
+            }
+            holders.push(holder);
+        }
+
+        let mut tallies: Vec<CandidateTally> = continuing
+            .iter()
+            .map(|id| CandidateTally {
+                candidate_id: *id,
+                votes: rounded(counts[id]),
+            })
+            .collect();
+        tallies.sort_by(|a, b| b.votes.total_cmp(&a.votes));
+
+        let mut round = Round {
+            round: rounds.len() + 1,
+            tallies,
+            exhausted_votes: rounded(exhausted),
+            elected: Vec::new(),
+            eliminated: None,
+            transfers: Vec::new(),
+            tie_break: None,
+        };
+
+        let remaining_seats = seats - elected.len();
+        if continuing.len() <= remaining_seats {
+            round.elected = round.tallies.iter().map(|t| t.candidate_id).collect();
+            elected.extend(round.elected.iter().copied());
+            rounds.push(round);
+            break;
+        }
+
+        let reached: Vec<Uuid> = continuing
+            .iter()
+            .copied()
+            .filter(|id| counts[id] + EPSILON >= quota as f64)
+            .collect();
+
+        if !reached.is_empty() {
+            let mut reached = reached;
+            reached.sort_by(|a, b| counts[b].total_cmp(&counts[a]));
+            reached.truncate(remaining_seats);
+
+            for id in reached {
+                let votes = counts[&id];
+                let surplus = (votes - quota as f64).max(0.0);
+                let transfer_value = if votes > 0.0 { surplus / votes } else { 0.0 };
+
+                for (holder, weight) in holders.iter().zip(weights.iter_mut()) {
+                    if *holder == Some(id) {
+                        *weight *= transfer_value;
+                    }
+                }
+
+                round.transfers.push(Transfer {
+                    from: id,
+                    kind: TransferKind::Surplus,
+                    votes: rounded(surplus),
+                    transfer_value: rounded(transfer_value),
+                });
+                round.elected.push(id);
+                elected.push(id);
+                continuing.retain(|c| *c != id);
+            }
+
+            rounds.push(round);
+            continue;
+        }
+
+        let (loser, tie_break) = match pick_elimination(&continuing, &counts, &rounds) {
+            Ok(pick) => pick,
+            Err(tied) => {
+                let stage = format!("round_{}", round.round);
+                match settle_elimination(decisions, stage, tied) {
+                    Ok(loser) => (loser, Some(TieBreak::Policy)),
+                    Err(tie) => {
+                        pending_tie = Some(tie);
+                        rounds.push(round);
+                        break;
+                    }
+                }
+            }
+        };
+        round.transfers.push(Transfer {
+            from: loser,
+            kind: TransferKind::Elimination,
+            votes: rounded(counts[&loser]),
+            transfer_value: 1.0,
+        });
+        round.eliminated = Some(loser);
+        round.tie_break = tie_break;
+        continuing.retain(|id| *id != loser);
+        rounds.push(round);
+    }
+
+    SingleTransferableVote {
+        seats,
+        quota,
+        total_ballots: ballots.iter().map(|b| b.weight).sum(),
+        rounds,
+        elected,
+        pending_tie,
+    }
+}
+
+fn pick_elimination(
+    continuing: &[Uuid],
+    counts: &HashMap<Uuid, f64>,
+    previous_rounds: &[Round],
+) -> Result<(Uuid, Option<TieBreak>), Vec<Uuid>> {
+    let fewest = continuing
+        .iter()
+        .map(|id| counts[id])
+        .fold(f64::INFINITY, f64::min);
+    let mut tied: Vec<Uuid> = continuing
+        .iter()
+        .copied()
+        .filter(|id| counts[id] - fewest < EPSILON)
+        .collect();
+
+    if tied.len() == 1 {
+        return Ok((tied[0], None));
+    }
+
+    for round in previous_rounds.iter().rev() {
+        let votes_in = |id: &Uuid| {
+            round
+                .tallies
+                .iter()
+                .find(|t| t.candidate_id == *id)
+                .map(|t| t.votes)
+                .unwrap_or(0.0)
+        };
+        let fewest = tied.iter().map(votes_in).fold(f64::INFINITY, f64::min);
+        tied.retain(|id| votes_in(id) - fewest < EPSILON);
+        if tied.len() == 1 {
+            return Ok((tied[0], Some(TieBreak::PreviousRounds)));
+        }
+    }
+
+    Err(tied)
+}
+
+fn rounded(value: f64) -> f64 {
+    (value * 10_000.0).round() / 10_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{tabulate, TransferKind};
+    use crate::tally::{Ballot, Decisions, TieBreak};
+
+    fn ids(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::new_v4()).collect()
+    }
+
+    fn unweighted(rankings: &[Vec<Uuid>]) -> Vec<Ballot> {
+        rankings.iter().cloned().map(Ballot::from).collect()
+    }
+
+    #[test]
+    fn surplus_transfers_at_fractional_value() {
+        let c = ids(4);
+        let mut ballots = vec![vec![c[0], c[1]]; 9];
+        ballots.extend(vec![vec![c[2]]; 3]);
+        ballots.push(vec![c[3]]);
+        let result = tabulate(&c, &unweighted(&ballots), 2, &Decisions::new());
+
+        assert_eq!(result.quota, 5);
+        assert_eq!(result.rounds[0].elected, vec![c[0]]);
+        assert_eq!(result.rounds[0].transfers[0].kind, TransferKind::Surplus);
+        assert_eq!(result.rounds[0].transfers[0].transfer_value, 0.4444);
+
+        let b_round_two = result.rounds[1]
+            .tallies
+            .iter()
+            .find(|t| t.candidate_id == c[1])
+            .map(|t| t.votes);
+        assert_eq!(b_round_two, Some(4.0));
+        assert_eq!(result.elected, vec![c[0], c[1]]);
+    }
+
+    #[test]
+    fn remaining_candidates_fill_remaining_seats() {
+        let c = ids(3);
+        let ballots = vec![vec![c[0]], vec![c[0]], vec![c[1]], vec![c[2]]];
+        let result = tabulate(&c, &unweighted(&ballots), 3, &Decisions::new());
+
+        assert_eq!(result.rounds.len(), 1);
+        assert_eq!(result.elected.len(), 3);
+        assert_eq!(result.elected[0], c[0]);
+    }
+
+    #[test]
+    fn empty_ballots_do_not_count_toward_quota() {
+        let c = ids(2);
+        let ballots = vec![vec![c[0]], vec![c[1]], vec![], vec![]];
+        let result = tabulate(&c, &unweighted(&ballots), 1, &Decisions::new());
+
+        assert_eq!(result.quota, 2);
+    }
+
+    #[test]
+    fn quota_is_computed_from_ballot_weight() {
+        let c = ids(3);
+        let ballots = vec![
+            Ballot {
+                ranking: vec![c[0], c[1]],
+                weight: 10,
+            },
+            vec![c[1]].into(),
+            vec![c[2]].into(),
+        ];
+        let result = tabulate(&c, &ballots, 2, &Decisions::new());
+
+        assert_eq!(result.total_ballots, 12);
+        assert_eq!(result.quota, 5);
+        assert_eq!(result.rounds[0].transfers[0].votes, 5.0);
+        assert_eq!(result.elected, vec![c[0], c[1]]);
+    }
+
+    #[test]
+    fn unsplit_elimination_ties_wait_for_a_decision() {
+        let c = ids(3);
+        let mut ballots = vec![vec![c[0]]; 3];
+        ballots.push(vec![c[1]]);
+        ballots.push(vec![c[2]]);
+        let result = tabulate(&c, &unweighted(&ballots), 2, &Decisions::new());
+
+        assert_eq!(result.elected, vec![c[0]]);
+        let tie = result.pending_tie.expect("pending tie");
+        assert_eq!(tie.stage, "round_2");
+        assert_eq!(tie.tied, vec![c[1], c[2]]);
+        assert_eq!(tie.seats, 1);
+
+        let decisions = Decisions::from([("round_2".to_string(), vec![c[2]])]);
+        let result = tabulate(&c, &unweighted(&ballots), 2, &decisions);
+
+        assert_eq!(result.rounds[1].eliminated, Some(c[1]));
+        assert_eq!(result.rounds[1].tie_break, Some(TieBreak::Policy));
+        assert_eq!(result.elected, vec![c[0], c[2]]);
+        assert!(result.pending_tie.is_none());
+    }
+}
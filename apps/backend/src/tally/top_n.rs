@@ -0,0 +1,94 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TopN {
+    pub seats: usize,
+    pub elected: Vec<Uuid>,
+    pub tied: Vec<Uuid>,
+    pub unfilled_seats: usize,
+    pub margin: Option<i64>,
+}
+
+// Elects the `seats` highest totals. Candidates level at the cutoff are reported as `tied` and
+// their seats left unfilled rather than being decided by list order. `margin` is the gap between
+// the last elected total and the best unelected one.
+pub fn declare(totals: &[(Uuid, i64)], seats: usize) -> TopN {
+    let mut sorted: Vec<(Uuid, i64)> = totals.to_vec();
+    sorted.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+
+    if sorted.len() <= seats {
+        return TopN {
+            seats,
+            elected: sorted.iter().map(|(id, _)| *id).collect(),
+            tied: Vec::new(),
+            unfilled_seats: seats - sorted.len(),
+            margin: None,
+        };
+    }
+
+    let cutoff = sorted[seats - 1].1;
+    let runner_up = sorted[seats].1;
+    let decided = cutoff > runner_up;
+    let elected: Vec<Uuid> = sorted
+        .iter()
+        .filter(|(_, total)| *total > cutoff || (decided && *total == cutoff))
+        .map(|(id, _)| *id)
+        .collect();
+    let tied: Vec<Uuid> = sorted
+        .iter()
+        .filter(|(_, total)| !decided && *total == cutoff)
+        .map(|(id, _)| *id)
+        .collect();
+
+    TopN {
+        seats,
+        unfilled_seats: seats - elected.len(),
+        elected,
+        tied,
+        margin: Some(cutoff - runner_up),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::declare;
+
+    fn totals(values: &[i64]) -> Vec<(Uuid, i64)> {
+        values.iter().map(|v| (Uuid::new_v4(), *v)).collect()
+    }
+
+    #[test]
+    fn highest_totals_fill_the_seats() {
+        let t = totals(&[40, 70, 55, 10]);
+        let result = declare(&t, 2);
+
+        assert_eq!(result.elected, vec![t[1].0, t[2].0]);
+        assert!(result.tied.is_empty());
+        assert_eq!(result.unfilled_seats, 0);
+        assert_eq!(result.margin, Some(15));
+    }
+
+    #[test]
+    fn tie_at_the_cutoff_leaves_the_seat_unfilled() {
+        let t = totals(&[50, 30, 30, 10]);
+        let result = declare(&t, 2);
+
+        assert_eq!(result.elected, vec![t[0].0]);
+        assert_eq!(result.tied, vec![t[1].0, t[2].0]);
+        assert_eq!(result.unfilled_seats, 1);
+        assert_eq!(result.margin, Some(0));
+    }
+
+    #[test]
+    fn fewer_candidates_than_seats_are_all_elected() {
+        let t = totals(&[5, 3]);
+        let result = declare(&t, 3);
+
+        assert_eq!(result.elected.len(), 2);
+        assert_eq!(result.unfilled_seats, 1);
+        assert_eq!(result.margin, None);
+    }
+}
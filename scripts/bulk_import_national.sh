@@ -7,6 +7,7 @@ ELECTION_ID="${ELECTION_ID:-}"
 CONTESTS_CSV="${CONTESTS_CSV:-}"
 CANDIDATES_CSV="${CANDIDATES_CSV:-}"
 VOTER_ROLLS_CSV="${VOTER_ROLLS_CSV:-}"
+PARTIES_CSV="${PARTIES_CSV:-}"
 VOTER_DRY_RUN="${VOTER_DRY_RUN:-false}"
 
 usage() {
@@ -20,26 +21,33 @@ Usage:
     --contests <contests.csv> \
     --candidates <candidates.csv> \
     --voter-rolls <voter_rolls.csv> \
+    [--parties <parties.csv>] \
     [--voter-dry-run true|false]
 
 CSV formats:
 
-1) contests.csv
-   contest_key,title,max_selections,province,district
+0) parties.csv (optional)
+   party_key,name,abbreviation
+   pa,Party A,PA
+
+1) contests.csv (voting_method, seats, allocation_method are optional)
+   contest_key,title,max_selections,province,district,voting_method,seats,allocation_method
    bkk-d1,Bangkok - District 1,1,Bangkok,1
+   national-list,National Party List,1,,,party_list,100,dhondt
 
-2) candidates.csv
-   contest_key,name,manifesto
-   bkk-d1,Candidate A,Manifesto text
+2) candidates.csv (party_key is optional; required for party_list contests)
+   contest_key,name,manifesto,party_key
+   bkk-d1,Candidate A,Manifesto text,pa
 
 3) voter_rolls.csv
-   contest_key,identifier
+   contest_key,identifier[,weight]
    bkk-d1,voter1@example.com
    bkk-d1,550e8400-e29b-41d4-a716-446655440000
 
 Notes:
 - Election must be in `draft` status (Admin APIs enforce this).
 - Contest mapping uses metadata.import_key (contest_key).
+- Parties are matched by name (case-insensitive); existing parties are reused.
 - Voter import uses `/contests/{id}/voter-rolls/import` with format=json.
 USAGE
 }
@@ -181,6 +189,7 @@ while [ "${1:-}" != "" ]; do
     --contests) CONTESTS_CSV="${2:-}"; shift 2 ;;
     --candidates) CANDIDATES_CSV="${2:-}"; shift 2 ;;
     --voter-rolls) VOTER_ROLLS_CSV="${2:-}"; shift 2 ;;
+    --parties) PARTIES_CSV="${2:-}"; shift 2 ;;
     --voter-dry-run) VOTER_DRY_RUN="${2:-}"; shift 2 ;;
     -h|--help)
       usage
@@ -208,6 +217,10 @@ for f in "$CONTESTS_CSV" "$CANDIDATES_CSV" "$VOTER_ROLLS_CSV"; do
     exit 1
   fi
 done
+if [ -n "$PARTIES_CSV" ] && [ ! -f "$PARTIES_CSV" ]; then
+  echo "File not found: $PARTIES_CSV" >&2
+  exit 1
+fi
 
 VOTER_DRY_RUN="$(normalize_bool "$VOTER_DRY_RUN")"
 
@@ -215,6 +228,8 @@ TMP_DIR="$(mktemp -d)"
 trap 'rm -rf "$TMP_DIR"' EXIT
 MAP_FILE="$TMP_DIR/contest_map.tsv"
 touch "$MAP_FILE"
+PARTY_MAP_FILE="$TMP_DIR/party_map.tsv"
+touch "$PARTY_MAP_FILE"
 
 echo "Checking election status..."
 election_json="$(api_request GET "/elections/${ELECTION_ID}")"
@@ -232,11 +247,68 @@ printf '%s' "$contests_json" | jq -r '
   | "\(.metadata.import_key)\t\(.id)"
 ' > "$MAP_FILE"
 
+party_created=0
+party_reused=0
+if [ -n "$PARTIES_CSV" ]; then
+  echo "Importing parties from ${PARTIES_CSV}..."
+  parties_json="$(api_request GET "/elections/${ELECTION_ID}/parties")"
+  line_no=0
+  while IFS=',' read -r raw_key raw_name raw_abbreviation rest; do
+    line_no=$((line_no + 1))
+    raw_key="${raw_key//$'\r'/}"
+    raw_name="${raw_name//$'\r'/}"
+    raw_abbreviation="${raw_abbreviation//$'\r'/}"
+
+    key="$(trim "$raw_key")"
+    name="$(trim "$raw_name")"
+    abbreviation="$(trim "$raw_abbreviation")"
+
+    if [ "$line_no" -eq 1 ] && [ "$key" = "party_key" ]; then
+      continue
+    fi
+    if [ -z "$key" ] && [ -z "$name" ]; then
+      continue
+    fi
+    if [ -z "$key" ] || [ -z "$name" ]; then
+      echo "parties.csv line ${line_no}: party_key and name are required" >&2
+      exit 1
+    fi
+
+    party_id="$(
+      printf '%s' "$parties_json" | jq -r --arg name "$name" '
+        .data.parties[]
+        | select((.name | ascii_downcase) == ($name | ascii_downcase))
+        | .id
+      ' | head -n 1
+    )"
+    if [ -n "$party_id" ]; then
+      party_reused=$((party_reused + 1))
+    else
+      payload="$(
+        jq -nc \
+          --arg name "$name" \
+          --arg abbreviation "$abbreviation" \
+          '
+          {
+            name: $name,
+            abbreviation: (if ($abbreviation | length) > 0 then $abbreviation else null end)
+          }
+          '
+      )"
+      create_res="$(api_request POST "/elections/${ELECTION_ID}/parties" "$payload")"
+      party_id="$(printf '%s' "$create_res" | jq -r '.data.party_id')"
+      party_created=$((party_created + 1))
+    fi
+
+    map_set "$key" "$party_id" "$PARTY_MAP_FILE"
+  done < "$PARTIES_CSV"
+fi
+
 echo "Importing contests from ${CONTESTS_CSV}..."
 contest_created=0
 contest_updated=0
 line_no=0
-while IFS=',' read -r raw_key raw_title raw_max raw_province raw_district rest; do
+while IFS=',' read -r raw_key raw_title raw_max raw_province raw_district raw_method raw_seats raw_allocation rest; do
   line_no=$((line_no + 1))
 
   raw_key="${raw_key//$'\r'/}"
@@ -244,12 +316,18 @@ while IFS=',' read -r raw_key raw_title raw_max raw_province raw_district rest;
   raw_max="${raw_max//$'\r'/}"
   raw_province="${raw_province//$'\r'/}"
   raw_district="${raw_district//$'\r'/}"
+  raw_method="${raw_method//$'\r'/}"
+  raw_seats="${raw_seats//$'\r'/}"
+  raw_allocation="${raw_allocation//$'\r'/}"
 
   key="$(trim "$raw_key")"
   title="$(trim "$raw_title")"
   max_sel="$(trim "$raw_max")"
   province="$(trim "$raw_province")"
   district="$(trim "$raw_district")"
+  voting_method="$(trim "$raw_method")"
+  seats="$(trim "$raw_seats")"
+  allocation_method="$(trim "$raw_allocation")"
 
   if [ "$line_no" -eq 1 ] && [ "$key" = "contest_key" ]; then
     continue
@@ -275,6 +353,13 @@ while IFS=',' read -r raw_key raw_title raw_max raw_province raw_district rest;
     echo "contests.csv line ${line_no}: max_selections must be integer >= 1 (contest_key=${key})" >&2
     exit 1
   fi
+  case "$seats" in
+    '') ;;
+    *[!0-9]*)
+      echo "contests.csv line ${line_no}: seats must be integer >= 1 (contest_key=${key})" >&2
+      exit 1
+      ;;
+  esac
 
   metadata="$(
     jq -nc \
@@ -301,6 +386,9 @@ while IFS=',' read -r raw_key raw_title raw_max raw_province raw_district rest;
       --arg title "$title" \
       --argjson max_selections "$max_sel" \
       --argjson metadata "$metadata" \
+      --arg voting_method "$voting_method" \
+      --arg seats "$seats" \
+      --arg allocation_method "$allocation_method" \
       '
       {
         title: $title,
@@ -308,6 +396,9 @@ while IFS=',' read -r raw_key raw_title raw_max raw_province raw_district rest;
         max_selections: $max_selections,
         metadata: $metadata
       }
+      + (if ($voting_method | length) > 0 then { voting_method: $voting_method } else {} end)
+      + (if ($seats | length) > 0 then { seats: ($seats | tonumber) } else {} end)
+      + (if ($allocation_method | length) > 0 then { allocation_method: $allocation_method } else {} end)
       '
   )"
 
@@ -332,15 +423,17 @@ echo "Importing candidates from ${CANDIDATES_CSV}..."
 candidate_created=0
 candidate_skipped=0
 line_no=0
-while IFS=',' read -r raw_key raw_name raw_manifesto; do
+while IFS=',' read -r raw_key raw_name raw_manifesto raw_party rest; do
   line_no=$((line_no + 1))
   raw_key="${raw_key//$'\r'/}"
   raw_name="${raw_name//$'\r'/}"
   raw_manifesto="${raw_manifesto//$'\r'/}"
+  raw_party="${raw_party//$'\r'/}"
 
   key="$(trim "$raw_key")"
   name="$(trim "$raw_name")"
   manifesto="$(trim "$raw_manifesto")"
+  party_key="$(trim "$raw_party")"
 
   if [ "$line_no" -eq 1 ] && [ "$key" = "contest_key" ]; then
     continue
@@ -359,6 +452,15 @@ while IFS=',' read -r raw_key raw_name raw_manifesto; do
     exit 1
   fi
 
+  party_id=""
+  if [ -n "$party_key" ]; then
+    party_id="$(map_get "$party_key" "$PARTY_MAP_FILE")"
+    if [ -z "$party_id" ]; then
+      echo "candidates.csv line ${line_no}: unknown party_key=${party_key}" >&2
+      exit 1
+    fi
+  fi
+
   load_candidate_cache "$contest_id"
   if candidate_exists_in_cache "$contest_id" "$name"; then
     candidate_skipped=$((candidate_skipped + 1))
@@ -369,10 +471,12 @@ while IFS=',' read -r raw_key raw_name raw_manifesto; do
     jq -nc \
       --arg name "$name" \
       --arg manifesto "$manifesto" \
+      --arg party_id "$party_id" \
       '
       {
         name: $name,
-        manifesto: (if ($manifesto | length) > 0 then $manifesto else null end)
+        manifesto: (if ($manifesto | length) > 0 then $manifesto else null end),
+        party_id: (if ($party_id | length) > 0 then $party_id else null end)
       }
       '
   )"
@@ -383,13 +487,15 @@ done < "$CANDIDATES_CSV"
 
 echo "Preparing voter-roll payloads from ${VOTER_ROLLS_CSV}..."
 line_no=0
-while IFS=',' read -r raw_key raw_identifier rest; do
+while IFS=',' read -r raw_key raw_identifier raw_weight rest; do
   line_no=$((line_no + 1))
   raw_key="${raw_key//$'\r'/}"
   raw_identifier="${raw_identifier//$'\r'/}"
+  raw_weight="${raw_weight//$'\r'/}"
 
   key="$(trim "$raw_key")"
   identifier="$(trim "$raw_identifier")"
+  weight="$(trim "$raw_weight")"
 
   if [ "$line_no" -eq 1 ] && [ "$key" = "contest_key" ]; then
     continue
@@ -408,7 +514,12 @@ while IFS=',' read -r raw_key raw_identifier rest; do
     exit 1
   fi
 
-  printf '%s\n' "$identifier" >> "$TMP_DIR/voters_${contest_id}.txt"
+  if [ -n "$weight" ] && ! [[ "$weight" =~ ^[1-9][0-9]*$ ]]; then
+    echo "voter_rolls.csv line ${line_no}: weight must be a positive integer" >&2
+    exit 1
+  fi
+
+  printf '%s,%s\n' "$identifier" "$weight" >> "$TMP_DIR/voters_${contest_id}.txt"
 done < "$VOTER_ROLLS_CSV"
 
 echo "Importing voter rolls by contest (dry_run=${VOTER_DRY_RUN})..."
@@ -429,6 +540,7 @@ for file in "$TMP_DIR"/voters_*.txt; do
     split("\n")
     | map(gsub("\r"; "") | gsub("^\\s+|\\s+$"; ""))
     | map(select(length > 0))
+    | map(split(",") | { identifier: .[0], weight: ((.[1] // "") | if . == "" then null else tonumber end) })
   ' "$file")"
 
   payload="$(
@@ -456,6 +568,7 @@ done
 
 echo ""
 echo "Bulk import completed:"
+echo "- parties: created=${party_created}, reused=${party_reused}"
 echo "- contests: created=${contest_created}, updated=${contest_updated}"
 echo "- candidates: created=${candidate_created}, skipped_existing=${candidate_skipped}"
 echo "- voter_rolls: total_rows=${voter_total_rows}, valid_rows=${voter_valid_rows}, inserted_rows=${voter_inserted_rows}, duplicate_rows=${voter_duplicates}, already_in_roll_rows=${voter_already_in_roll}, not_found_rows=${voter_not_found}, dry_run=${VOTER_DRY_RUN}"
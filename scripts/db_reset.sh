@@ -18,6 +18,21 @@ docker compose -f infra/docker-compose.yml --env-file infra/.env up -d >/dev/nul
 
 DROP_SQL=$(
   cat <<'SQL'
+DROP TABLE IF EXISTS spoiled_ballots CASCADE;
+DROP TABLE IF EXISTS prepared_ballots CASCADE;
+DROP TABLE IF EXISTS encrypted_tallies CASCADE;
+DROP TABLE IF EXISTS decryption_shares CASCADE;
+DROP TABLE IF EXISTS encrypted_votes CASCADE;
+DROP TABLE IF EXISTS trustee_shares CASCADE;
+DROP TABLE IF EXISTS trustees CASCADE;
+DROP TABLE IF EXISTS bulletin_snapshots CASCADE;
+DROP TABLE IF EXISTS bulletin_entries CASCADE;
+DROP TABLE IF EXISTS ballots CASCADE;
+DROP TABLE IF EXISTS ballot_receipts CASCADE;
+DROP TABLE IF EXISTS tie_breaks CASCADE;
+DROP TABLE IF EXISTS write_ins CASCADE;
+DROP TABLE IF EXISTS parties CASCADE;
+DROP TABLE IF EXISTS audit_chain_head CASCADE;
 DROP TABLE IF EXISTS audit_events CASCADE;
 DROP TABLE IF EXISTS votes CASCADE;
 DROP TABLE IF EXISTS vote_receipts CASCADE;
@@ -95,6 +95,45 @@ SECOND_VOTE_STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "${API_BASE}
 curl -sS -X PATCH "${API_BASE}/elections/${DEMO_STUDENT_ELECTION_ID}/close" \
   -H "authorization: Bearer ${ADMIN_ACCESS}" >/dev/null
 
+# Ballot rows must share neither a transaction id nor any stored value with the receipts.
+LINKED=$(docker exec -i "$PG_CONTAINER" psql -U "$PG_USER" -d "$PG_DB" -Atc "
+  WITH ballot_rows AS (
+    SELECT xmin, to_jsonb(b) AS row FROM ballots b
+    UNION ALL SELECT xmin, to_jsonb(v) FROM votes v
+    UNION ALL SELECT xmin, to_jsonb(w) FROM write_ins w
+    UNION ALL SELECT xmin, to_jsonb(e) FROM encrypted_votes e
+    UNION ALL SELECT xmin, to_jsonb(p) FROM bulletin_entries p
+  ),
+  receipt_values AS (
+    SELECT kv.value
+    FROM vote_receipts r, jsonb_each_text(to_jsonb(r) - 'election_id' - 'contest_id') kv
+    WHERE kv.value IS NOT NULL
+  )
+  SELECT
+    (SELECT COUNT(*) FROM ballot_rows b JOIN vote_receipts r ON r.xmin = b.xmin)
+    + (
+      SELECT COUNT(*)
+      FROM ballot_rows b, jsonb_each_text(b.row - 'election_id' - 'contest_id') kv
+      WHERE kv.value IN (SELECT value FROM receipt_values)
+    );")
+[ "$LINKED" = "0" ]
+[ "$(docker exec -i "$PG_CONTAINER" psql -U "$PG_USER" -d "$PG_DB" -Atc "SELECT COUNT(*) FROM ballots;")" -gt 0 ]
+
+# The snapshot's leaves are exactly the commitments stored with counted ballots.
+UNMATCHED=$(docker exec -i "$PG_CONTAINER" psql -U "$PG_USER" -d "$PG_DB" -Atc "
+  SELECT
+    (SELECT COALESCE(SUM(leaf_count), 0) FROM bulletin_snapshots)
+    - (SELECT COUNT(*) FROM ballots WHERE commitment IS NOT NULL)
+    + (
+      SELECT COUNT(*) FROM ballots b
+      WHERE b.commitment IS NOT NULL
+        AND NOT EXISTS (
+          SELECT 1 FROM bulletin_entries p
+          WHERE p.commitment = b.commitment AND p.contest_id = b.contest_id
+        )
+    );")
+[ "$UNMATCHED" = "0" ]
+
 RESULTS=$(curl -sS "${API_BASE}/elections/${DEMO_STUDENT_ELECTION_ID}/results" -H "authorization: Bearer ${ADMIN_ACCESS}")
 COUNT=$(echo "$RESULTS" | jq -r '.data.results | length')
 [ "$COUNT" -gt 0 ]